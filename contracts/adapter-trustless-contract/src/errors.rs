@@ -0,0 +1,18 @@
+use soroban_sdk::contracterror;
+
+// Error types for the Trustless Work escrow adapter contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EscrowAdapterError {
+    NotAdmin = 1,
+    NotCreditLine = 2,
+    InvalidAmount = 3,
+    EscrowAlreadyExists = 4,
+    EscrowNotFound = 5,
+    NotBorrower = 6,
+    InvalidMilestone = 7,
+    MilestoneAlreadyReleased = 8,
+    OverflowError = 9,
+    EscrowNotFunded = 10,
+}
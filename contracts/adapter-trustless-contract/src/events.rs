@@ -0,0 +1,32 @@
+use soroban_sdk::{symbol_short, Env, Symbol};
+
+use crate::types::LoanEscrow;
+
+// Event topics
+const ESCROW_OPENED: Symbol = symbol_short!("ESCOPEN");
+const MILESTONE_RELEASED: Symbol = symbol_short!("MSRELEAS");
+const ESCROW_REFUNDED: Symbol = symbol_short!("ESCRFND");
+
+/// Emit an escrow opened event
+pub fn emit_escrow_opened(env: &Env, escrow: &LoanEscrow, milestone_count: u32) {
+    env.events().publish(
+        (ESCROW_OPENED, escrow.borrower.clone(), escrow.loan_id),
+        (escrow.merchant.clone(), escrow.amount, milestone_count),
+    );
+}
+
+/// Emit a milestone released event
+pub fn emit_milestone_released(env: &Env, escrow: &LoanEscrow, milestone_index: u32, amount: i128) {
+    env.events().publish(
+        (MILESTONE_RELEASED, escrow.merchant.clone(), escrow.loan_id),
+        (milestone_index, amount, escrow.released_amount),
+    );
+}
+
+/// Emit an escrow refunded event
+pub fn emit_escrow_refunded(env: &Env, escrow: &LoanEscrow) {
+    env.events().publish(
+        (ESCROW_REFUNDED, escrow.merchant.clone(), escrow.loan_id),
+        (escrow.refunded_amount, escrow.released_amount),
+    );
+}
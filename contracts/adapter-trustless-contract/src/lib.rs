@@ -0,0 +1,348 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, panic_with_error, symbol_short, token, vec, Address, Env, IntoVal,
+    Symbol, Vec,
+};
+
+// Module imports
+mod access;
+mod errors;
+mod events;
+mod storage;
+mod types;
+
+// Re-export types for external use
+pub use errors::EscrowAdapterError;
+pub use types::{EscrowProperties, EscrowRoles, EscrowStatus, LoanEscrow, Milestone};
+
+/// Trustless Work escrow adapter contract structure
+/// Receives loan disbursements from the CreditLine and locks them in a milestone-based
+/// escrow; each milestone is paid out to the merchant once the borrower approves it
+#[contract]
+pub struct EscrowAdapterContract;
+
+/// Contract implementation
+#[contractimpl]
+impl EscrowAdapterContract {
+    /// Get the version of this contract
+    pub fn get_version() -> Symbol {
+        symbol_short!("v1_0_0")
+    }
+
+    /// Initialize the contract with its admin, the CreditLine, the disbursed token and
+    /// the escrow contract
+    /// Can only be called once (when admin is not set)
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        creditline: Address,
+        token: Address,
+        escrow_contract: Address,
+    ) {
+        let admin_opt: Option<Address> = env.storage().instance().get(&storage::ADMIN_KEY);
+        if admin_opt.is_some() {
+            panic!("Already initialized");
+        }
+
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+        storage::set_creditline(&env, &creditline);
+        storage::set_token(&env, &token);
+        storage::set_escrow_contract(&env, &escrow_contract);
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let old_admin = storage::get_admin(&env);
+        old_admin.require_auth();
+        access::require_admin(&env, &old_admin);
+
+        storage::set_admin(&env, &new_admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    /// Set the CreditLine contract allowed to open escrows (admin only)
+    pub fn set_creditline(env: Env, admin: Address, creditline: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_creditline(&env, &creditline);
+    }
+
+    pub fn get_creditline(env: Env) -> Option<Address> {
+        storage::get_creditline(&env)
+    }
+
+    /// Set the escrow contract used for new escrows (admin only)
+    /// Open escrows keep the contract they were created with
+    pub fn set_escrow_contract(env: Env, admin: Address, escrow_contract: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_escrow_contract(&env, &escrow_contract);
+    }
+
+    pub fn get_escrow_contract(env: Env) -> Address {
+        storage::get_escrow_contract(&env)
+    }
+
+    pub fn get_token(env: Env) -> Address {
+        storage::get_token(&env)
+    }
+
+    /// Lock a loan's disbursement in a new escrow, one milestone per amount (CreditLine only)
+    /// The CreditLine transfers the milestone total to the adapter before calling
+    pub fn open_escrow(
+        env: Env,
+        loan_id: u64,
+        borrower: Address,
+        merchant: Address,
+        milestone_amounts: Vec<i128>,
+    ) {
+        access::require_creditline(&env);
+
+        if storage::read_loan_escrow(&env, loan_id).is_some() {
+            panic_with_error!(&env, EscrowAdapterError::EscrowAlreadyExists);
+        }
+        if milestone_amounts.is_empty() {
+            panic_with_error!(&env, EscrowAdapterError::InvalidAmount);
+        }
+
+        let mut amount: i128 = 0;
+        let mut milestones = Vec::new(&env);
+        for milestone_amount in milestone_amounts.iter() {
+            if milestone_amount <= 0 {
+                panic_with_error!(&env, EscrowAdapterError::InvalidAmount);
+            }
+            amount = amount
+                .checked_add(milestone_amount)
+                .unwrap_or_else(|| panic_with_error!(&env, EscrowAdapterError::OverflowError));
+            milestones.push_back(Milestone {
+                amount: milestone_amount,
+                approved: false,
+                released: false,
+            });
+        }
+
+        let adapter = env.current_contract_address();
+        let token = storage::get_token(&env);
+        let escrow_contract = storage::get_escrow_contract(&env);
+
+        let properties = EscrowProperties {
+            engagement_id: loan_id,
+            token: token.clone(),
+            roles: EscrowRoles {
+                approver: adapter.clone(),
+                service_provider: merchant.clone(),
+                release_signer: adapter.clone(),
+                dispute_resolver: adapter.clone(),
+                receiver: merchant.clone(),
+            },
+            milestones,
+        };
+        env.invoke_contract::<()>(
+            &escrow_contract,
+            &Symbol::new(&env, "initialize_escrow"),
+            (properties,).into_val(&env),
+        );
+
+        // The escrow pulls the funds from the adapter, so authorize the nested token transfer
+        env.authorize_as_current_contract(vec![
+            &env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: token,
+                    fn_name: symbol_short!("transfer"),
+                    args: (adapter.clone(), escrow_contract.clone(), amount).into_val(&env),
+                },
+                sub_invocations: Vec::new(&env),
+            }),
+        ]);
+        env.invoke_contract::<()>(
+            &escrow_contract,
+            &Symbol::new(&env, "fund_escrow"),
+            (adapter, loan_id, amount).into_val(&env),
+        );
+
+        let escrow = LoanEscrow {
+            loan_id,
+            borrower,
+            merchant,
+            escrow_contract,
+            amount,
+            released_amount: 0,
+            refunded_amount: 0,
+            status: EscrowStatus::Funded,
+            created_at: env.ledger().timestamp(),
+        };
+        storage::write_loan_escrow(&env, &escrow);
+
+        events::emit_escrow_opened(&env, &escrow, milestone_amounts.len());
+    }
+
+    /// Approve a milestone and release its funds to the merchant (borrower only)
+    /// Returns the amount released
+    pub fn release_milestone(
+        env: Env,
+        borrower: Address,
+        loan_id: u64,
+        milestone_index: u32,
+    ) -> i128 {
+        borrower.require_auth();
+
+        let mut escrow = Self::funded_escrow(&env, loan_id);
+        if escrow.borrower != borrower {
+            panic_with_error!(&env, EscrowAdapterError::NotBorrower);
+        }
+
+        let properties = Self::escrow_properties(&env, &escrow);
+        let milestone = properties
+            .milestones
+            .get(milestone_index)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowAdapterError::InvalidMilestone));
+        if milestone.released {
+            panic_with_error!(&env, EscrowAdapterError::MilestoneAlreadyReleased);
+        }
+
+        Self::release(&env, &mut escrow, milestone_index, &milestone);
+        storage::write_loan_escrow(&env, &escrow);
+
+        milestone.amount
+    }
+
+    /// Approve and release every pending milestone to the merchant (CreditLine only)
+    /// Used once the borrower confirms delivery or the delivery window elapses.
+    /// Returns the amount released (0 if the borrower already released every milestone)
+    pub fn release_escrow(env: Env, loan_id: u64) -> i128 {
+        access::require_creditline(&env);
+
+        let mut escrow = match Self::unrefunded_escrow(&env, loan_id) {
+            Some(escrow) => escrow,
+            None => return 0,
+        };
+        let properties = Self::escrow_properties(&env, &escrow);
+
+        let mut released: i128 = 0;
+        for (index, milestone) in properties.milestones.iter().enumerate() {
+            if milestone.released {
+                continue;
+            }
+            Self::release(&env, &mut escrow, index as u32, &milestone);
+            released = released
+                .checked_add(milestone.amount)
+                .unwrap_or_else(|| panic_with_error!(&env, EscrowAdapterError::OverflowError));
+        }
+        storage::write_loan_escrow(&env, &escrow);
+
+        released
+    }
+
+    /// Return every unreleased amount of an escrow to the CreditLine (CreditLine only)
+    /// The adapter resolves the engagement as its dispute resolver, awarding the remaining
+    /// funds to itself as approver, and forwards them to the CreditLine.
+    /// Returns the amount refunded (0 if every milestone was already released)
+    pub fn refund_escrow(env: Env, loan_id: u64) -> i128 {
+        let creditline = access::require_creditline(&env);
+
+        let mut escrow = match Self::unrefunded_escrow(&env, loan_id) {
+            Some(escrow) => escrow,
+            None => return 0,
+        };
+        let remaining = escrow.amount - escrow.released_amount;
+
+        let adapter = env.current_contract_address();
+        env.invoke_contract::<()>(
+            &escrow.escrow_contract,
+            &Symbol::new(&env, "resolve_dispute"),
+            (adapter.clone(), loan_id, remaining, 0_i128).into_val(&env),
+        );
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &adapter,
+            &creditline,
+            &remaining,
+        );
+
+        escrow.refunded_amount = remaining;
+        escrow.status = EscrowStatus::Refunded;
+        storage::write_loan_escrow(&env, &escrow);
+
+        events::emit_escrow_refunded(&env, &escrow);
+
+        remaining
+    }
+
+    /// Get the escrow of a loan
+    pub fn get_loan_escrow(env: Env, loan_id: u64) -> LoanEscrow {
+        storage::read_loan_escrow(&env, loan_id)
+            .unwrap_or_else(|| panic_with_error!(&env, EscrowAdapterError::EscrowNotFound))
+    }
+
+    /// Get a loan's escrow as currently recorded by the escrow contract
+    pub fn get_escrow_properties(env: Env, loan_id: u64) -> EscrowProperties {
+        let escrow = Self::get_loan_escrow(env.clone(), loan_id);
+        Self::escrow_properties(&env, &escrow)
+    }
+
+    /// Load an escrow whose milestones can still be released or refunded
+    fn funded_escrow(env: &Env, loan_id: u64) -> LoanEscrow {
+        let escrow = storage::read_loan_escrow(env, loan_id)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowAdapterError::EscrowNotFound));
+        if escrow.status != EscrowStatus::Funded {
+            panic_with_error!(env, EscrowAdapterError::EscrowNotFunded);
+        }
+        escrow
+    }
+
+    /// Load an escrow that was not refunded; None once every milestone has been released
+    fn unrefunded_escrow(env: &Env, loan_id: u64) -> Option<LoanEscrow> {
+        let escrow = storage::read_loan_escrow(env, loan_id)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowAdapterError::EscrowNotFound));
+        match escrow.status {
+            EscrowStatus::Funded => Some(escrow),
+            EscrowStatus::Released => None,
+            EscrowStatus::Refunded => panic_with_error!(env, EscrowAdapterError::EscrowNotFunded),
+        }
+    }
+
+    /// Approve a milestone if needed and have the escrow pay it out to the merchant
+    fn release(env: &Env, escrow: &mut LoanEscrow, milestone_index: u32, milestone: &Milestone) {
+        let adapter = env.current_contract_address();
+        if !milestone.approved {
+            env.invoke_contract::<()>(
+                &escrow.escrow_contract,
+                &Symbol::new(env, "approve_milestone"),
+                (adapter.clone(), escrow.loan_id, milestone_index).into_val(env),
+            );
+        }
+        env.invoke_contract::<()>(
+            &escrow.escrow_contract,
+            &Symbol::new(env, "release_milestone_funds"),
+            (adapter, escrow.loan_id, milestone_index).into_val(env),
+        );
+
+        escrow.released_amount = escrow
+            .released_amount
+            .checked_add(milestone.amount)
+            .unwrap_or_else(|| panic_with_error!(env, EscrowAdapterError::OverflowError));
+        if escrow.released_amount == escrow.amount {
+            escrow.status = EscrowStatus::Released;
+        }
+
+        events::emit_milestone_released(env, escrow, milestone_index, milestone.amount);
+    }
+
+    fn escrow_properties(env: &Env, escrow: &LoanEscrow) -> EscrowProperties {
+        env.invoke_contract(
+            &escrow.escrow_contract,
+            &Symbol::new(env, "get_escrow"),
+            (escrow.loan_id,).into_val(env),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,84 @@
+use soroban_sdk::{symbol_short, Address, Env, Map, Symbol};
+
+use crate::types::LoanEscrow;
+
+// Storage keys for the escrow adapter contract
+pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+pub const TOKEN_KEY: Symbol = symbol_short!("TOKEN");
+pub const CREDITLINE_KEY: Symbol = symbol_short!("CREDITLN");
+pub const ESCROW_CONTRACT: Symbol = symbol_short!("ESCROW");
+pub const ESCROWS_MAP: Symbol = symbol_short!("ESCROWS");
+
+/// Get the admin address from storage
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ADMIN_KEY)
+        .unwrap_or_else(|| panic!("Admin not set"))
+}
+
+/// Set the admin address in storage
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN_KEY, admin);
+}
+
+/// Get the SEP-41 token disbursed through the escrows
+pub fn get_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&TOKEN_KEY)
+        .unwrap_or_else(|| panic!("Token not set"))
+}
+
+/// Set the SEP-41 token disbursed through the escrows
+pub fn set_token(env: &Env, token: &Address) {
+    env.storage().instance().set(&TOKEN_KEY, token);
+}
+
+/// Get the CreditLine contract allowed to open escrows
+pub fn get_creditline(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CREDITLINE_KEY)
+}
+
+/// Set the CreditLine contract allowed to open escrows
+pub fn set_creditline(env: &Env, creditline: &Address) {
+    env.storage().instance().set(&CREDITLINE_KEY, creditline);
+}
+
+/// Get the escrow contract new escrows are opened with
+pub fn get_escrow_contract(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ESCROW_CONTRACT)
+        .unwrap_or_else(|| panic!("Escrow contract not set"))
+}
+
+/// Set the escrow contract new escrows are opened with
+pub fn set_escrow_contract(env: &Env, escrow_contract: &Address) {
+    env.storage()
+        .instance()
+        .set(&ESCROW_CONTRACT, escrow_contract);
+}
+
+/// Read the escrow of a loan
+pub fn read_loan_escrow(env: &Env, loan_id: u64) -> Option<LoanEscrow> {
+    let escrows: Map<u64, LoanEscrow> = env
+        .storage()
+        .instance()
+        .get(&ESCROWS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    escrows.get(loan_id)
+}
+
+/// Write the escrow of a loan
+pub fn write_loan_escrow(env: &Env, escrow: &LoanEscrow) {
+    let mut escrows: Map<u64, LoanEscrow> = env
+        .storage()
+        .instance()
+        .get(&ESCROWS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    escrows.set(escrow.loan_id, escrow.clone());
+    env.storage().instance().set(&ESCROWS_MAP, &escrows);
+}
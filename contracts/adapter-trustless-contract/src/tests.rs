@@ -0,0 +1,387 @@
+use crate::{
+    EscrowAdapterContract, EscrowAdapterContractClient, EscrowProperties, EscrowStatus, Milestone,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events},
+    token, vec, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+
+// Local stand-in for a Trustless Work escrow: holds one engagement per loan and pays out
+// approved milestones to the receiver
+#[contract]
+pub struct MockEscrow;
+
+#[contractimpl]
+impl MockEscrow {
+    pub fn initialize_escrow(env: Env, properties: EscrowProperties) {
+        if env.storage().instance().has(&properties.engagement_id) {
+            panic!("Escrow already initialized");
+        }
+        env.storage()
+            .instance()
+            .set(&properties.engagement_id, &properties);
+    }
+    pub fn fund_escrow(env: Env, signer: Address, engagement_id: u64, amount: i128) {
+        signer.require_auth();
+        let properties = Self::get_escrow(env.clone(), engagement_id);
+        token::Client::new(&env, &properties.token).transfer(
+            &signer,
+            &env.current_contract_address(),
+            &amount,
+        );
+    }
+    pub fn approve_milestone(env: Env, approver: Address, engagement_id: u64, index: u32) {
+        approver.require_auth();
+        let mut properties = Self::get_escrow(env.clone(), engagement_id);
+        assert_eq!(approver, properties.roles.approver);
+
+        let mut milestone = properties.milestones.get(index).unwrap();
+        milestone.approved = true;
+        properties.milestones.set(index, milestone);
+        env.storage().instance().set(&engagement_id, &properties);
+    }
+    pub fn release_milestone_funds(
+        env: Env,
+        release_signer: Address,
+        engagement_id: u64,
+        index: u32,
+    ) {
+        release_signer.require_auth();
+        let mut properties = Self::get_escrow(env.clone(), engagement_id);
+        assert_eq!(release_signer, properties.roles.release_signer);
+
+        let mut milestone = properties.milestones.get(index).unwrap();
+        assert!(milestone.approved && !milestone.released);
+        token::Client::new(&env, &properties.token).transfer(
+            &env.current_contract_address(),
+            &properties.roles.receiver,
+            &milestone.amount,
+        );
+        milestone.released = true;
+        properties.milestones.set(index, milestone);
+        env.storage().instance().set(&engagement_id, &properties);
+    }
+    pub fn resolve_dispute(
+        env: Env,
+        dispute_resolver: Address,
+        engagement_id: u64,
+        approver_funds: i128,
+        receiver_funds: i128,
+    ) {
+        dispute_resolver.require_auth();
+        let properties = Self::get_escrow(env.clone(), engagement_id);
+        assert_eq!(dispute_resolver, properties.roles.dispute_resolver);
+
+        let token = token::Client::new(&env, &properties.token);
+        let escrow = env.current_contract_address();
+        if approver_funds > 0 {
+            token.transfer(&escrow, &properties.roles.approver, &approver_funds);
+        }
+        if receiver_funds > 0 {
+            token.transfer(&escrow, &properties.roles.receiver, &receiver_funds);
+        }
+    }
+    pub fn get_escrow(env: Env, engagement_id: u64) -> EscrowProperties {
+        env.storage().instance().get(&engagement_id).unwrap()
+    }
+}
+
+struct AdapterSetup<'a> {
+    client: EscrowAdapterContractClient<'a>,
+    admin: Address,
+    creditline: Address,
+    escrow: Address,
+    token: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+}
+
+fn setup(env: &Env) -> AdapterSetup<'_> {
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    let escrow = env.register(MockEscrow, ());
+
+    let contract_id = env.register(EscrowAdapterContract, ());
+    let client = EscrowAdapterContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    let creditline = Address::generate(env);
+    client.initialize(&admin, &creditline, &token_id, &escrow);
+
+    AdapterSetup {
+        client,
+        admin,
+        creditline,
+        escrow,
+        token: token::Client::new(env, &token_id),
+        token_admin: token::StellarAssetClient::new(env, &token_id),
+    }
+}
+
+/// Disburse `milestones` into a new escrow for loan 1, as the CreditLine would
+fn open_escrow(env: &Env, s: &AdapterSetup, milestones: Vec<i128>) -> (Address, Address) {
+    let borrower = Address::generate(env);
+    let merchant = Address::generate(env);
+
+    let amount: i128 = milestones.iter().sum();
+    s.token_admin.mint(&s.creditline, &amount);
+    s.token.transfer(&s.creditline, &s.client.address, &amount);
+    s.client.open_escrow(&1, &borrower, &merchant, &milestones);
+
+    (borrower, merchant)
+}
+
+#[test]
+fn test_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    assert_eq!(s.client.get_admin(), s.admin);
+    assert_eq!(s.client.get_creditline(), Some(s.creditline.clone()));
+    assert_eq!(s.client.get_escrow_contract(), s.escrow);
+    assert_eq!(s.client.get_version(), symbol_short!("v1_0_0"));
+}
+
+#[test]
+#[should_panic(expected = "Already initialized")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client
+        .initialize(&s.admin, &s.creditline, &s.token.address, &s.escrow);
+}
+
+#[test]
+fn test_open_escrow_funds_escrow_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("ESCOPEN"));
+
+    assert_eq!(s.token.balance(&s.escrow), 800);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+
+    let escrow = s.client.get_loan_escrow(&1);
+    assert_eq!(escrow.borrower, borrower);
+    assert_eq!(escrow.amount, 800);
+    assert_eq!(escrow.status, EscrowStatus::Funded);
+
+    let properties = s.client.get_escrow_properties(&1);
+    assert_eq!(properties.roles.receiver, merchant);
+    assert_eq!(properties.roles.release_signer, s.client.address);
+    assert_eq!(
+        properties.milestones.get(1).unwrap(),
+        Milestone {
+            amount: 500,
+            approved: false,
+            released: false,
+        }
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_open_escrow_requires_creditline_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    env.set_auths(&[]);
+
+    s.client.open_escrow(
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &vec![&env, 100],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_open_escrow_with_zero_milestone_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.open_escrow(
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &vec![&env, 100, 0],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_open_escrow_twice_for_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    open_escrow(&env, &s, vec![&env, 100]);
+
+    s.client.open_escrow(
+        &1,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &vec![&env, 100],
+    );
+}
+
+#[test]
+fn test_release_milestones_pays_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+
+    assert_eq!(s.client.release_milestone(&borrower, &1, &1), 500);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("MSRELEAS"));
+
+    assert_eq!(s.token.balance(&merchant), 500);
+    assert_eq!(s.client.get_loan_escrow(&1).status, EscrowStatus::Funded);
+
+    s.client.release_milestone(&borrower, &1, &0);
+
+    assert_eq!(s.token.balance(&merchant), 800);
+    assert_eq!(s.token.balance(&s.escrow), 0);
+    let escrow = s.client.get_loan_escrow(&1);
+    assert_eq!(escrow.released_amount, 800);
+    assert_eq!(escrow.status, EscrowStatus::Released);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_release_milestone_by_other_user_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    open_escrow(&env, &s, vec![&env, 300]);
+
+    s.client.release_milestone(&Address::generate(&env), &1, &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_release_unknown_milestone_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, _merchant) = open_escrow(&env, &s, vec![&env, 300]);
+
+    s.client.release_milestone(&borrower, &1, &1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_release_milestone_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, _merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+    s.client.release_milestone(&borrower, &1, &0);
+
+    s.client.release_milestone(&borrower, &1, &0);
+}
+
+#[test]
+fn test_release_escrow_pays_remaining_milestones() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+    s.client.release_milestone(&borrower, &1, &0);
+
+    assert_eq!(s.client.release_escrow(&1), 500);
+
+    assert_eq!(s.token.balance(&merchant), 800);
+    assert_eq!(s.token.balance(&s.escrow), 0);
+    assert_eq!(s.client.get_loan_escrow(&1).status, EscrowStatus::Released);
+}
+
+#[test]
+fn test_refund_escrow_returns_unreleased_funds_to_creditline() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+    s.client.release_milestone(&borrower, &1, &0);
+
+    assert_eq!(s.client.refund_escrow(&1), 500);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("ESCRFND"));
+
+    assert_eq!(s.token.balance(&s.creditline), 500);
+    assert_eq!(s.token.balance(&merchant), 300);
+    assert_eq!(s.token.balance(&s.escrow), 0);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    let escrow = s.client.get_loan_escrow(&1);
+    assert_eq!(escrow.refunded_amount, 500);
+    assert_eq!(escrow.status, EscrowStatus::Refunded);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_release_after_refund_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, _merchant) = open_escrow(&env, &s, vec![&env, 300, 500]);
+    s.client.refund_escrow(&1);
+
+    s.client.release_milestone(&borrower, &1, &1);
+}
+
+#[test]
+fn test_refund_released_escrow_returns_nothing() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let (borrower, _merchant) = open_escrow(&env, &s, vec![&env, 300]);
+    s.client.release_milestone(&borrower, &1, &0);
+
+    assert_eq!(s.client.refund_escrow(&1), 0);
+    assert_eq!(s.client.release_escrow(&1), 0);
+    assert_eq!(s.client.get_loan_escrow(&1).status, EscrowStatus::Released);
+}
+
+#[test]
+#[should_panic]
+fn test_refund_escrow_requires_creditline_auth() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    open_escrow(&env, &s, vec![&env, 300]);
+    env.set_auths(&[]);
+
+    s.client.refund_escrow(&1);
+}
@@ -0,0 +1,56 @@
+use soroban_sdk::{contracttype, Address, Vec};
+
+// Roles of an escrow, following the Trustless Work escrow model
+// The adapter approves, releases and resolves disputes; the merchant delivers and receives the funds
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowRoles {
+    pub approver: Address,
+    pub service_provider: Address,
+    pub release_signer: Address,
+    pub dispute_resolver: Address,
+    pub receiver: Address,
+}
+
+// A tranche of the escrowed amount, released to the receiver once approved
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Milestone {
+    pub amount: i128,
+    pub approved: bool,
+    pub released: bool,
+}
+
+// Escrow as registered with the escrow contract; one engagement per loan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowProperties {
+    pub engagement_id: u64, // The loan ID
+    pub token: Address,
+    pub roles: EscrowRoles,
+    pub milestones: Vec<Milestone>,
+}
+
+// Escrow lifecycle as tracked by the adapter
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Funded,   // Milestones still pending
+    Released, // Every milestone paid out to the merchant
+    Refunded, // Unreleased funds returned to the CreditLine
+}
+
+// Escrowed disbursement of a loan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanEscrow {
+    pub loan_id: u64,
+    pub borrower: Address,
+    pub merchant: Address,
+    pub escrow_contract: Address,
+    pub amount: i128,
+    pub released_amount: i128,
+    pub refunded_amount: i128,
+    pub status: EscrowStatus,
+    pub created_at: u64,
+}
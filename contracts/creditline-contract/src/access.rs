@@ -11,3 +11,24 @@ pub fn require_admin(env: &Env, caller: &Address) {
         panic_with_error!(env, CreditLineError::NotAdmin);
     }
 }
+
+/// Require that the given address is the arbiter, otherwise panic with NotArbiter error
+pub fn require_arbiter(env: &Env, caller: &Address) {
+    match storage::get_arbiter(env) {
+        Some(arbiter) if &arbiter == caller => {}
+        _ => panic_with_error!(env, CreditLineError::NotArbiter),
+    }
+}
+
+/// Require that the given address is the admin or the risk manager, otherwise panic with
+/// NotRiskManager error
+pub fn require_risk_manager(env: &Env, caller: &Address) {
+    if caller == &storage::get_admin(env) {
+        return;
+    }
+
+    match storage::get_risk_manager(env) {
+        Some(risk_manager) if &risk_manager == caller => {}
+        _ => panic_with_error!(env, CreditLineError::NotRiskManager),
+    }
+}
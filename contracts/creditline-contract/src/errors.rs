@@ -17,4 +17,57 @@ pub enum CreditLineError {
     Overflow = 10,
     Underflow = 11,
     LoanNotOverdue = 12,
+    InvalidFeeSplit = 13,
+    NotMerchant = 14,
+    LoanNotDefaulted = 15,
+    SettlementNotFound = 16,
+    SettlementNotHeld = 17,
+    DeliveryWindowOpen = 18,
+    NotArbiter = 20,
+    DisputeAlreadyOpened = 21,
+    LoanNotDisputed = 22,
+    LoanNotPending = 23,
+    RequestExpired = 24,
+    RequestNotExpired = 25,
+    OrderAlreadyFinanced = 26,
+    OrderSignatureRequired = 27,
+    OrderSignatureExpired = 28,
+    SigningKeyNotSet = 29,
+    InvalidTerms = 30,
+    NotRiskManager = 31,
+    InvalidSchedule = 32,
+    PayerNotAllowed = 33,
+    FeeExceedsFinancing = 34,
+    ConcentrationLimitExceeded = 35,
+    DisputeWindowClosed = 36,
+    DisputeNotExpired = 37,
+}
+
+// Native builds of `#[contracttype]` structs need these for the `LoanQuote::error` field
+#[cfg(not(target_family = "wasm"))]
+mod testutils {
+    use super::CreditLineError;
+    use soroban_sdk::{
+        testutils::arbitrary::SorobanArbitrary,
+        xdr::{ScError, ScVal},
+        ConversionError, Env, Error, TryFromVal,
+    };
+
+    impl From<CreditLineError> for ScVal {
+        fn from(error: CreditLineError) -> Self {
+            ScVal::Error(ScError::Contract(error as u32))
+        }
+    }
+
+    impl TryFromVal<Env, Error> for CreditLineError {
+        type Error = ConversionError;
+
+        fn try_from_val(_env: &Env, error: &Error) -> Result<Self, Self::Error> {
+            CreditLineError::try_from(*error).map_err(|_| ConversionError)
+        }
+    }
+
+    impl SorobanArbitrary for CreditLineError {
+        type Prototype = Error;
+    }
 }
@@ -1,28 +1,37 @@
-use soroban_sdk::{symbol_short, Address, Env, Symbol, Vec};
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
 
-use crate::types::RepaymentInstallment;
+use crate::types::{Dispute, Loan, LoanRequest, Restructuring, WriteOff};
 
 // Event topics
 const LOAN_CREATED: Symbol = symbol_short!("LOANCRTD");
 const LOAN_DEFAULTED: Symbol = symbol_short!("LOANDFLT");
+const LOAN_REFUNDED: Symbol = symbol_short!("LOANRFND");
+const LOAN_REPAID: Symbol = symbol_short!("LOANPAID");
+const LOAN_WRITTEN_OFF: Symbol = symbol_short!("LOANWOFF");
+const LOAN_RECOVERED: Symbol = symbol_short!("LOANRCVR");
+const SETTLEMENT_RELEASED: Symbol = symbol_short!("STLRELS");
+const LOAN_DISPUTE_OPENED: Symbol = symbol_short!("DSPOPEN");
+const LOAN_DISPUTE_RESOLVED: Symbol = symbol_short!("DSPRSLV");
+const LOAN_DISPUTE_EXPIRED: Symbol = symbol_short!("DSPEXPD");
+const LOAN_REQUESTED: Symbol = symbol_short!("LOANREQ");
+const LOAN_ACCEPTED: Symbol = symbol_short!("LOANACPT");
+const LOAN_EXPIRED: Symbol = symbol_short!("LOANEXPD");
+const LOAN_PAID_OFF: Symbol = symbol_short!("LOANPYOF");
+const LOAN_RESTRUCTURED: Symbol = symbol_short!("LOANRSTR");
+const LOAN_FULLY_RECOVERED: Symbol = symbol_short!("LOANRCVD");
+const PAYER_UPDATED: Symbol = symbol_short!("PAYERUPD");
+const PAYERS_CLEARED: Symbol = symbol_short!("PAYERCLR");
 
 /// Emit a loan created event
-pub fn emit_loan_created(
-    env: &Env,
-    user: &Address,
-    merchant: &Address,
-    loan_id: u64,
-    total_amount: i128,
-    guarantee_amount: i128,
-    repayment_schedule: &Vec<RepaymentInstallment>,
-) {
+pub fn emit_loan_created(env: &Env, loan: &Loan) {
     env.events().publish(
-        (LOAN_CREATED, user, merchant),
+        (LOAN_CREATED, loan.borrower.clone(), loan.merchant.clone()),
         (
-            loan_id,
-            total_amount,
-            guarantee_amount,
-            repayment_schedule.clone(),
+            loan.loan_id,
+            loan.total_amount,
+            loan.guarantee_amount,
+            loan.repayment_schedule.clone(),
+            loan.merchant_fee.clone(),
         ),
     );
 }
@@ -45,3 +54,175 @@ pub fn emit_loan_defaulted(
         ),
     );
 }
+
+/// Emit a loan refunded event
+pub fn emit_loan_refunded(
+    env: &Env,
+    merchant: &Address,
+    loan_id: u64,
+    refund_amount: i128,
+    remaining_balance: i128,
+    guarantee_released: i128,
+) {
+    env.events().publish(
+        (LOAN_REFUNDED, merchant, loan_id),
+        (refund_amount, remaining_balance, guarantee_released),
+    );
+}
+
+/// Emit a loan repayment event, credited to the borrower whoever paid
+pub fn emit_loan_repaid(
+    env: &Env,
+    borrower: &Address,
+    payer: &Address,
+    loan_id: u64,
+    amount: i128,
+    remaining_balance: i128,
+) {
+    env.events().publish(
+        (LOAN_REPAID, borrower, loan_id),
+        (
+            amount,
+            remaining_balance,
+            env.ledger().timestamp(),
+            payer.clone(),
+        ),
+    );
+}
+
+/// Emit a loan written off event
+pub fn emit_loan_written_off(env: &Env, borrower: &Address, write_off: &WriteOff) {
+    env.events().publish(
+        (LOAN_WRITTEN_OFF, borrower, write_off.loan_id),
+        (
+            write_off.principal_written_off,
+            write_off.guarantee_forfeited,
+        ),
+    );
+}
+
+/// Emit a recovery on a defaulted loan event
+pub fn emit_loan_recovered(
+    env: &Env,
+    payer: &Address,
+    loan_id: u64,
+    amount: i128,
+    recovered_amount: i128,
+) {
+    env.events().publish(
+        (LOAN_RECOVERED, payer, loan_id),
+        (amount, recovered_amount, env.ledger().timestamp()),
+    );
+}
+
+/// Emit a settlement released to the merchant event
+pub fn emit_settlement_released(
+    env: &Env,
+    merchant: &Address,
+    loan_id: u64,
+    amount: i128,
+    confirmed: bool,
+) {
+    env.events().publish(
+        (SETTLEMENT_RELEASED, merchant, loan_id),
+        (amount, confirmed),
+    );
+}
+
+/// Emit a loan dispute opened event
+pub fn emit_loan_dispute_opened(env: &Env, borrower: &Address, dispute: &Dispute) {
+    env.events().publish(
+        (LOAN_DISPUTE_OPENED, borrower, dispute.loan_id),
+        (dispute.evidence_hash.clone(), dispute.opened_at),
+    );
+}
+
+/// Emit a loan dispute resolved event
+pub fn emit_loan_dispute_resolved(env: &Env, arbiter: &Address, dispute: &Dispute) {
+    env.events().publish(
+        (LOAN_DISPUTE_RESOLVED, arbiter, dispute.loan_id),
+        (dispute.outcome.clone(), dispute.resolved_at),
+    );
+}
+
+/// Emit a loan dispute expired event, resolved for the merchant after the arbiter timed out
+pub fn emit_loan_dispute_expired(env: &Env, dispute: &Dispute) {
+    env.events().publish(
+        (LOAN_DISPUTE_EXPIRED, dispute.loan_id),
+        (dispute.outcome.clone(), dispute.resolved_at),
+    );
+}
+
+/// Emit a loan requested event
+pub fn emit_loan_requested(env: &Env, loan: &Loan, request: &LoanRequest) {
+    env.events().publish(
+        (LOAN_REQUESTED, loan.borrower.clone(), loan.merchant.clone()),
+        (
+            loan.loan_id,
+            loan.total_amount,
+            loan.guarantee_amount,
+            request.expires_at,
+        ),
+    );
+}
+
+/// Emit a loan request accepted by the merchant event
+pub fn emit_loan_accepted(env: &Env, loan: &Loan, request: &LoanRequest) {
+    env.events().publish(
+        (LOAN_ACCEPTED, loan.merchant.clone(), loan.loan_id),
+        (loan.order_id.clone(), request.accepted_at),
+    );
+}
+
+/// Emit a loan request expired event
+pub fn emit_loan_expired(env: &Env, borrower: &Address, loan_id: u64, guarantee_released: i128) {
+    env.events().publish(
+        (LOAN_EXPIRED, borrower, loan_id),
+        (guarantee_released, env.ledger().timestamp()),
+    );
+}
+
+/// Emit a loan paid off event
+pub fn emit_loan_paid_off(env: &Env, loan: &Loan, amount: i128, interest_rebated: i128) {
+    env.events().publish(
+        (LOAN_PAID_OFF, loan.borrower.clone(), loan.loan_id),
+        (amount, interest_rebated, loan.paid_early),
+    );
+}
+
+/// Emit a loan restructured event
+pub fn emit_loan_restructured(env: &Env, borrower: &Address, restructuring: &Restructuring) {
+    env.events().publish(
+        (LOAN_RESTRUCTURED, borrower, restructuring.loan_id),
+        (
+            restructuring.reason.clone(),
+            restructuring.restructured_by.clone(),
+            restructuring.new_schedule.clone(),
+        ),
+    );
+}
+
+/// Emit a defaulted loan fully recovered event
+pub fn emit_loan_fully_recovered(
+    env: &Env,
+    borrower: &Address,
+    write_off: &WriteOff,
+    reputation_restored: u32,
+) {
+    env.events().publish(
+        (LOAN_FULLY_RECOVERED, borrower, write_off.loan_id),
+        (write_off.recovered_amount, reputation_restored),
+    );
+}
+
+/// Emit a borrower's payer allowlist updated event
+pub fn emit_payer_updated(env: &Env, borrower: &Address, payer: &Address, allowed: bool) {
+    env.events()
+        .publish((PAYER_UPDATED, borrower, payer), allowed);
+}
+
+/// Emit a borrower's payer allowlist cleared event
+pub fn emit_payers_cleared(env: &Env, borrower: &Address) {
+    env.events()
+        .publish((PAYERS_CLEARED, borrower), env.ledger().timestamp());
+}
@@ -1,6 +1,10 @@
 #![no_std]
+// The helpers `contractimpl` generates for entry points such as `create_loan_with_terms` repeat
+// every parameter, so the lint cannot be silenced on the function itself
+#![allow(clippy::too_many_arguments)]
 use soroban_sdk::{
-    contract, contractimpl, panic_with_error, symbol_short, Address, Env, IntoVal, Symbol, Vec,
+    contract, contractimpl, panic_with_error, symbol_short, token, vec, xdr::ToXdr, Address,
+    BytesN, Env, IntoVal, String, Symbol, Vec,
 };
 
 // Module imports
@@ -12,7 +16,11 @@ mod types;
 
 // Re-export types for external use
 pub use errors::CreditLineError;
-pub use types::{Loan, LoanStatus, RepaymentInstallment};
+pub use types::{
+    Dispute, DisputeOutcome, FeeSplit, Loan, LoanQuote, LoanRequest, LoanStatus, LoanTerms,
+    MerchantFee, Order, RepaymentInstallment, Restructuring, Settlement, SettlementStatus,
+    WriteOff,
+};
 
 /// CreditLine contract structure
 #[contract]
@@ -49,8 +57,10 @@ impl CreditLineContract {
         storage::set_liquidity_pool(&env, &liquidity_pool);
     }
 
-    /// Create a new loan
+    /// Create a new loan for a merchant order
     /// Validates all requirements and creates an active loan
+    /// Each order can only be financed once; merchants with a registered signing key must
+    /// sign the checkout
     pub fn create_loan(
         env: Env,
         user: Address,
@@ -58,47 +68,292 @@ impl CreditLineContract {
         total_amount: i128,
         guarantee_amount: i128,
         repayment_schedule: Vec<RepaymentInstallment>,
+        order: Order,
     ) -> u64 {
         user.require_auth();
 
-        Self::validate_guarantee(&env, total_amount, guarantee_amount);
+        Self::validate_guarantee(total_amount, guarantee_amount)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        Self::validate_merchant(&env, &merchant)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
 
-        Self::validate_merchant(&env, &merchant);
+        Self::validate_order(&env, &user, &merchant, total_amount, &order)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
 
-        Self::validate_reputation(&env, &user);
+        Self::validate_reputation(&env, &user)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
 
-        Self::validate_liquidity(&env, total_amount, guarantee_amount);
+        let required_from_pool = Self::validate_liquidity(&env, total_amount)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
 
         let loan_id = storage::increment_loan_counter(&env);
 
-        // Create loan record
-        let loan = Loan {
+        Self::escrow_guarantee(&env, &user, guarantee_amount);
+
+        storage::set_order_loan(&env, &merchant, &order.order_id, loan_id);
+
+        let mut loan = Self::pending_loan(
+            &env,
             loan_id,
-            borrower: user.clone(),
-            merchant: merchant.clone(),
+            user,
+            merchant,
             total_amount,
             guarantee_amount,
-            remaining_balance: total_amount,
-            repayment_schedule: repayment_schedule.clone(),
-            status: LoanStatus::Active,
-            created_at: env.ledger().timestamp(),
+            repayment_schedule,
+        );
+        loan.order_id = Some(order.order_id);
+        loan.invoice_hash = Some(order.invoice_hash);
+        let loan = Self::originate_loan(&env, loan, required_from_pool);
+
+        events::emit_loan_created(&env, &loan);
+
+        loan_id
+    }
+
+    /// Create a new loan repaid in `installments` equal installments, `period_seconds` apart
+    /// from `first_due`, instead of an explicit schedule
+    /// Runs the same validations as `create_loan`
+    pub fn create_loan_with_terms(
+        env: Env,
+        user: Address,
+        merchant: Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+        installments: u32,
+        period_seconds: u64,
+        first_due: u64,
+        order: Order,
+    ) -> u64 {
+        let terms = LoanTerms {
+            installments,
+            period_seconds,
+            first_due,
         };
+        let repayment_schedule = Self::build_schedule(&env, total_amount, &terms)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
 
-        storage::write_loan(&env, &loan);
+        Self::create_loan(
+            env,
+            user,
+            merchant,
+            total_amount,
+            guarantee_amount,
+            repayment_schedule,
+            order,
+        )
+    }
+
+    /// Preview the equal-installment schedule `create_loan_with_terms` would build
+    /// Amounts exclude interest, which is added once the pool prices the loan
+    pub fn preview_schedule(
+        env: Env,
+        total_amount: i128,
+        terms: LoanTerms,
+    ) -> Result<Vec<RepaymentInstallment>, CreditLineError> {
+        Self::build_schedule(&env, total_amount, &terms)
+    }
+
+    /// Quote a prospective loan without creating it
+    /// Runs the same validations as `create_loan` and reports the first failing one, along
+    /// with the pricing the loan would get if it were created now over the default terms:
+    /// `DEFAULT_QUOTE_INSTALLMENTS` monthly installments, the first one due in a month
+    pub fn quote_loan(
+        env: Env,
+        user: Address,
+        merchant: Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+    ) -> LoanQuote {
+        let terms = LoanTerms {
+            installments: types::DEFAULT_QUOTE_INSTALLMENTS,
+            period_seconds: types::DEFAULT_QUOTE_PERIOD_SECONDS,
+            first_due: env
+                .ledger()
+                .timestamp()
+                .saturating_add(types::DEFAULT_QUOTE_PERIOD_SECONDS),
+        };
+        Self::quote_loan_with_terms(env, user, merchant, total_amount, guarantee_amount, terms)
+    }
+
+    /// Quote a prospective loan over custom equal-installment terms without creating it
+    pub fn quote_loan_with_terms(
+        env: Env,
+        user: Address,
+        merchant: Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+        terms: LoanTerms,
+    ) -> LoanQuote {
+        let mut quote = LoanQuote {
+            eligible: false,
+            error: None,
+            required_guarantee: Self::min_guarantee(total_amount).unwrap_or(0),
+            interest_rate_bps: 0,
+            interest_amount: 0,
+            merchant_fee: MerchantFee {
+                total: 0,
+                liquidity_pool: 0,
+                treasury: 0,
+                incentive_fund: 0,
+            },
+            repayment_schedule: Vec::new(&env),
+            available_credit: Self::available_liquidity(&env).unwrap_or(0),
+        };
 
-        events::emit_loan_created(
+        match Self::price_quote(
             &env,
             &user,
             &merchant,
+            total_amount,
+            guarantee_amount,
+            &terms,
+            &mut quote,
+        ) {
+            Ok(()) => quote.eligible = true,
+            Err(error) => quote.error = Some(error),
+        }
+
+        quote
+    }
+
+    /// Request a loan that stays Pending until the merchant accepts it
+    /// The guarantee is escrowed immediately; the pool only funds the loan on acceptance.
+    /// Returns the loan ID
+    pub fn request_loan(
+        env: Env,
+        user: Address,
+        merchant: Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+        repayment_schedule: Vec<RepaymentInstallment>,
+    ) -> u64 {
+        user.require_auth();
+
+        Self::validate_guarantee(total_amount, guarantee_amount)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        Self::validate_merchant(&env, &merchant)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        Self::validate_reputation(&env, &user)
+            .unwrap_or_else(|error| panic_with_error!(&env, error));
+
+        let loan_id = storage::increment_loan_counter(&env);
+
+        Self::escrow_guarantee(&env, &user, guarantee_amount);
+
+        let loan = Self::pending_loan(
+            &env,
             loan_id,
+            user,
+            merchant,
             total_amount,
             guarantee_amount,
-            &repayment_schedule,
+            repayment_schedule,
         );
+        storage::write_loan(&env, &loan);
+
+        let now = env.ledger().timestamp();
+
+        let request = LoanRequest {
+            loan_id,
+            requested_at: now,
+            expires_at: now.saturating_add(storage::get_request_ttl(&env)),
+            accepted_at: 0,
+        };
+        storage::write_loan_request(&env, &request);
+
+        events::emit_loan_requested(&env, &loan, &request);
 
         loan_id
     }
 
+    /// Accept a pending loan request, binding it to one of the merchant's orders (merchant only)
+    /// The pool funds the loan and it becomes Active
+    pub fn accept_loan(
+        env: Env,
+        merchant: Address,
+        loan_id: u64,
+        order_id: String,
+    ) -> Result<(), CreditLineError> {
+        merchant.require_auth();
+
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.merchant != merchant {
+            return Err(CreditLineError::NotMerchant);
+        }
+        if loan.status != LoanStatus::Pending {
+            return Err(CreditLineError::LoanNotPending);
+        }
+        if storage::get_order_loan(&env, &merchant, &order_id).is_some() {
+            return Err(CreditLineError::OrderAlreadyFinanced);
+        }
+
+        let mut request =
+            storage::read_loan_request(&env, loan_id).ok_or(CreditLineError::LoanNotPending)?;
+        let now = env.ledger().timestamp();
+        if now >= request.expires_at {
+            return Err(CreditLineError::RequestExpired);
+        }
+
+        // The request may have sat for a while; the loan must still pass the checks
+        // `create_loan` runs before it is funded
+        Self::validate_guarantee(loan.total_amount, loan.guarantee_amount)?;
+        Self::validate_merchant(&env, &merchant)?;
+        Self::validate_reputation(&env, &loan.borrower)?;
+
+        let required_from_pool = Self::validate_liquidity(&env, loan.total_amount)?;
+
+        storage::set_order_loan(&env, &merchant, &order_id, loan_id);
+        loan.order_id = Some(order_id);
+        let loan = Self::originate_loan(&env, loan, required_from_pool);
+
+        request.accepted_at = now;
+        storage::write_loan_request(&env, &request);
+
+        events::emit_loan_accepted(&env, &loan, &request);
+        events::emit_loan_created(&env, &loan);
+
+        Ok(())
+    }
+
+    /// Expire a loan request the merchant did not accept in time, returning the guarantee
+    /// to the borrower. Callable by anyone.
+    pub fn expire_loan_request(env: Env, loan_id: u64) -> Result<(), CreditLineError> {
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.status != LoanStatus::Pending {
+            return Err(CreditLineError::LoanNotPending);
+        }
+
+        let request =
+            storage::read_loan_request(&env, loan_id).ok_or(CreditLineError::LoanNotPending)?;
+        if env.ledger().timestamp() < request.expires_at {
+            return Err(CreditLineError::RequestNotExpired);
+        }
+
+        if let Some(token) = storage::get_token(&env) {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &loan.borrower,
+                &loan.guarantee_amount,
+            );
+        }
+
+        loan.remaining_balance = 0;
+        loan.status = LoanStatus::Expired;
+        storage::write_loan(&env, &loan);
+
+        events::emit_loan_expired(&env, &loan.borrower, loan_id, loan.guarantee_amount);
+
+        Ok(())
+    }
+
+    /// Get the request a loan was opened from, if it went through `request_loan`
+    pub fn get_loan_request(env: Env, loan_id: u64) -> Option<LoanRequest> {
+        storage::read_loan_request(&env, loan_id)
+    }
+
     /// Get a loan by ID
     pub fn get_loan(env: Env, loan_id: u64) -> Loan {
         storage::read_loan(&env, loan_id)
@@ -141,43 +396,312 @@ impl CreditLineContract {
         storage::set_liquidity_pool(&env, &address);
     }
 
+    /// Set the escrow adapter that holds merchant disbursements until milestones are approved (admin only)
+    /// Without an adapter, financed amounts are paid to the merchant directly
+    pub fn set_escrow_adapter(env: Env, admin: Address, address: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_escrow_adapter(&env, &address);
+    }
+
+    /// Set how long disbursements are held before they can be released to the merchant
+    /// without the borrower's delivery confirmation (admin only)
+    pub fn set_delivery_window(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_delivery_window(&env, seconds);
+    }
+
+    /// Set the arbiter resolving borrower–merchant disputes (admin only)
+    pub fn set_arbiter(env: Env, admin: Address, arbiter: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_arbiter(&env, &arbiter);
+    }
+
+    pub fn get_arbiter(env: Env) -> Option<Address> {
+        storage::get_arbiter(&env)
+    }
+
+    /// Set the risk manager allowed to restructure loans alongside the admin (admin only)
+    pub fn set_risk_manager(env: Env, admin: Address, risk_manager: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_risk_manager(&env, &risk_manager);
+    }
+
+    pub fn get_risk_manager(env: Env) -> Option<Address> {
+        storage::get_risk_manager(&env)
+    }
+
+    /// Get the delivery window, in seconds
+    pub fn get_delivery_window(env: Env) -> u64 {
+        storage::get_delivery_window(&env)
+    }
+
+    /// Set how long a loan request can wait for the merchant's acceptance before it expires (admin only)
+    pub fn set_request_ttl(env: Env, admin: Address, seconds: u64) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_request_ttl(&env, seconds);
+    }
+
+    /// Get the loan request time-to-live, in seconds
+    pub fn get_request_ttl(env: Env) -> u64 {
+        storage::get_request_ttl(&env)
+    }
+
+    /// Set the reputation points restored when a defaulted loan is fully recovered (admin only)
+    /// Zero, the default, restores nothing.
+    pub fn set_recovery_restore(env: Env, admin: Address, points: u32) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_recovery_restore(&env, points);
+    }
+
+    /// Get the reputation points restored when a defaulted loan is fully recovered
+    pub fn get_recovery_restore(env: Env) -> u32 {
+        storage::get_recovery_restore(&env)
+    }
+
+    /// Set the token used for guarantees, disbursements and repayments (admin only)
+    pub fn set_token(env: Env, admin: Address, address: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_token(&env, &address);
+    }
+
+    /// Set how merchant fees are split between pool, treasury and incentive fund (admin only)
+    pub fn set_fee_split(env: Env, admin: Address, split: FeeSplit) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let total_bps = split.liquidity_pool_bps as i128
+            + split.treasury_bps as i128
+            + split.incentive_fund_bps as i128;
+        if total_bps != types::BPS_DENOMINATOR {
+            panic_with_error!(&env, CreditLineError::InvalidFeeSplit);
+        }
+
+        storage::set_fee_split(&env, &split);
+    }
+
+    /// Get the current merchant fee split
+    pub fn get_fee_split(env: Env) -> FeeSplit {
+        storage::get_fee_split(&env)
+    }
+
+    /// Set the treasury receiving its share of merchant fees (admin only)
+    /// Until a treasury is set, its share goes to the liquidity pool
+    pub fn set_treasury(env: Env, admin: Address, address: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_treasury(&env, &address);
+    }
+
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        storage::get_treasury(&env)
+    }
+
+    /// Set the incentive fund receiving its share of merchant fees (admin only)
+    /// Until an incentive fund is set, its share goes to the liquidity pool
+    pub fn set_incentive_fund(env: Env, admin: Address, address: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_incentive_fund(&env, &address);
+    }
+
+    pub fn get_incentive_fund(env: Env) -> Option<Address> {
+        storage::get_incentive_fund(&env)
+    }
+
     /// Validate guarantee amount is at least 20% of total amount
-    fn validate_guarantee(env: &Env, total_amount: i128, guarantee_amount: i128) {
-        if total_amount <= 0 || guarantee_amount <= 0 {
-            panic_with_error!(env, CreditLineError::InvalidAmount);
+    fn validate_guarantee(
+        total_amount: i128,
+        guarantee_amount: i128,
+    ) -> Result<(), CreditLineError> {
+        if total_amount <= 0 || guarantee_amount <= 0 || guarantee_amount > total_amount {
+            return Err(CreditLineError::InvalidAmount);
+        }
+
+        if guarantee_amount < Self::min_guarantee(total_amount)? {
+            return Err(CreditLineError::InsufficientGuarantee);
         }
 
-        // Calculate minimum guarantee (20% of total)
-        let min_guarantee = total_amount
+        Ok(())
+    }
+
+    /// Calculate minimum guarantee (20% of total)
+    fn min_guarantee(total_amount: i128) -> Result<i128, CreditLineError> {
+        total_amount
             .checked_mul(types::MIN_GUARANTEE_PERCENT)
             .and_then(|v| v.checked_div(100))
-            .unwrap_or_else(|| panic_with_error!(env, CreditLineError::Overflow));
+            .ok_or(CreditLineError::Overflow)
+    }
 
-        if guarantee_amount < min_guarantee {
-            panic_with_error!(env, CreditLineError::InsufficientGuarantee);
+    /// Validate merchant is registered and active
+    fn validate_merchant(env: &Env, merchant: &Address) -> Result<(), CreditLineError> {
+        let merchant_registry = match storage::get_merchant_registry(env) {
+            Some(address) => address,
+            // Merchant registry not configured, nothing to validate against
+            None => return Ok(()),
+        };
+
+        let is_active: bool = env.invoke_contract(
+            &merchant_registry,
+            &Symbol::new(env, "is_active_merchant"),
+            (merchant,).into_val(env),
+        );
+
+        if !is_active {
+            return Err(CreditLineError::MerchantNotActive);
         }
+
+        Ok(())
     }
 
-    /// Validate merchant is registered and active
-    /// TODO: Implement when Merchant Registry contract is available (Phase 5)
-    fn validate_merchant(env: &Env, merchant: &Address) {
-        let merchant_registry = storage::get_merchant_registry(env);
-
-        if merchant_registry.is_none() {
-            // Merchant registry not configured yet
-            // For now, we'll skip this validation
-            // TODO: Remove this when merchant registry is implemented
-            return;
+    /// Validate that an order has not been financed yet and, when the merchant signs its
+    /// checkouts, that it carries an unexpired signature over the loan terms
+    fn validate_order(
+        env: &Env,
+        user: &Address,
+        merchant: &Address,
+        total_amount: i128,
+        order: &Order,
+    ) -> Result<(), CreditLineError> {
+        if storage::get_order_loan(env, merchant, &order.order_id).is_some() {
+            return Err(CreditLineError::OrderAlreadyFinanced);
+        }
+
+        let signing_key: Option<BytesN<32>> = match storage::get_merchant_registry(env) {
+            Some(merchant_registry) => env.invoke_contract(
+                &merchant_registry,
+                &Symbol::new(env, "get_signing_key"),
+                (merchant,).into_val(env),
+            ),
+            None => None,
+        };
+
+        match (signing_key, &order.signature) {
+            (None, None) => {}
+            (Some(_), None) => return Err(CreditLineError::OrderSignatureRequired),
+            (None, Some(_)) => return Err(CreditLineError::SigningKeyNotSet),
+            (Some(signing_key), Some(signature)) => {
+                if env.ledger().timestamp() >= order.expires_at {
+                    return Err(CreditLineError::OrderSignatureExpired);
+                }
+
+                // Bound to this contract and merchant so a signature cannot be replayed
+                // elsewhere; fails the transaction if the signature does not match
+                let payload = (
+                    env.current_contract_address(),
+                    merchant.clone(),
+                    user.clone(),
+                    total_amount,
+                    order.order_id.clone(),
+                    order.invoice_hash.clone(),
+                    order.expires_at,
+                )
+                    .to_xdr(env);
+                env.crypto()
+                    .ed25519_verify(&signing_key, &payload, signature);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the merchant's category from the merchant registry, used by the liquidity pool's
+    /// concentration limits
+    fn merchant_category(env: &Env, merchant: &Address) -> Symbol {
+        match storage::get_merchant_registry(env) {
+            Some(merchant_registry) => env.invoke_contract(
+                &merchant_registry,
+                &Symbol::new(env, "get_merchant_category"),
+                (merchant,).into_val(env),
+            ),
+            None => symbol_short!("general"),
+        }
+    }
+
+    /// Calculate the merchant fee for a loan from the merchant's registry fee rate
+    /// The fee is withheld from the merchant's disbursement and split per the fee split
+    fn calculate_merchant_fee(env: &Env, merchant: &Address, total_amount: i128) -> MerchantFee {
+        let fee_rate_bps: u32 = match storage::get_merchant_registry(env) {
+            Some(merchant_registry) => env.invoke_contract(
+                &merchant_registry,
+                &Symbol::new(env, "get_merchant_fee"),
+                (merchant,).into_val(env),
+            ),
+            None => 0,
+        };
+
+        let total = Self::apply_bps(env, total_amount, fee_rate_bps);
+
+        let split = storage::get_fee_split(env);
+        let treasury = Self::apply_bps(env, total, split.treasury_bps);
+        let incentive_fund = Self::apply_bps(env, total, split.incentive_fund_bps);
+
+        // The liquidity pool receives the remainder so rounding never loses funds
+        let liquidity_pool = total
+            .checked_sub(treasury)
+            .and_then(|v| v.checked_sub(incentive_fund))
+            .unwrap_or_else(|| panic_with_error!(env, CreditLineError::Underflow));
+
+        MerchantFee {
+            total,
+            liquidity_pool,
+            treasury,
+            incentive_fund,
+        }
+    }
+
+    /// Simple interest on the financed principal from now until the last installment is due
+    fn calculate_interest(
+        env: &Env,
+        principal: i128,
+        rate_bps: u32,
+        schedule: &Vec<RepaymentInstallment>,
+    ) -> i128 {
+        if principal <= 0 || rate_bps == 0 {
+            return 0;
         }
 
-        // TODO: Query merchant registry contract
-        // Example: merchant_registry_client.is_active(merchant)
-        // For now, we assume merchant is valid
-        let _ = merchant;
+        let term_seconds = schedule
+            .last()
+            .map(|installment| {
+                installment
+                    .due_date
+                    .saturating_sub(env.ledger().timestamp())
+            })
+            .unwrap_or(0);
+
+        principal
+            .checked_mul(rate_bps as i128)
+            .and_then(|v| v.checked_mul(term_seconds as i128))
+            .and_then(|v| v.checked_div(types::BPS_DENOMINATOR * types::SECONDS_PER_YEAR))
+            .unwrap_or_else(|| panic_with_error!(env, CreditLineError::Overflow))
+    }
+
+    /// Apply a basis point rate to an amount, rounding down
+    fn apply_bps(env: &Env, amount: i128, bps: u32) -> i128 {
+        amount
+            .checked_mul(bps as i128)
+            .and_then(|v| v.checked_div(types::BPS_DENOMINATOR))
+            .unwrap_or_else(|| panic_with_error!(env, CreditLineError::Overflow))
     }
 
     /// Validate user has sufficient reputation
-    fn validate_reputation(env: &Env, user: &Address) {
+    fn validate_reputation(env: &Env, user: &Address) -> Result<(), CreditLineError> {
         let reputation_contract = storage::get_reputation_contract(env)
             .unwrap_or_else(|| panic!("Reputation contract not configured"));
 
@@ -192,87 +716,1623 @@ impl CreditLineContract {
         );
 
         if score < types::MIN_REPUTATION_THRESHOLD {
-            panic_with_error!(env, CreditLineError::InsufficientReputation);
+            return Err(CreditLineError::InsufficientReputation);
         }
+
+        Ok(())
     }
 
-    /// Validate liquidity pool has sufficient funds
-    /// TODO: Implement when Liquidity Pool contract is available (Phase 6)
-    fn validate_liquidity(env: &Env, total_amount: i128, guarantee_amount: i128) {
-        let liquidity_pool = storage::get_liquidity_pool(env);
+    /// Validate liquidity pool has sufficient funds to finance the purchase
+    /// The pool pays the full purchase price so the merchant receives `total_amount` less the
+    /// merchant fee; the guarantee stays in escrow as collateral for the financed amount.
+    /// Returns the amount required from the pool (total_amount)
+    fn validate_liquidity(env: &Env, total_amount: i128) -> Result<i128, CreditLineError> {
+        let required_from_pool = total_amount;
 
-        if liquidity_pool.is_none() {
-            // Liquidity pool not configured yet
-            // For now, we'll skip this validation
-            // TODO: Remove this when liquidity pool is implemented
-            return;
+        if let Some(available) = Self::available_liquidity(env) {
+            if available < required_from_pool {
+                return Err(CreditLineError::InsufficientLiquidity);
+            }
         }
 
-        // The loan requires (total_amount - guarantee_amount) from the pool
-        let required_from_pool = total_amount
-            .checked_sub(guarantee_amount)
-            .unwrap_or_else(|| panic_with_error!(env, CreditLineError::Underflow));
+        Ok(required_from_pool)
+    }
 
-        // TODO: Query liquidity pool contract
-        // Example: liquidity_pool_client.get_available_liquidity()
-        // For now, we assume liquidity is sufficient
-        let _ = required_from_pool;
+    /// Validate the pool could fund `amount` within its concentration limits for the
+    /// borrower, merchant and merchant category
+    fn validate_concentration(
+        env: &Env,
+        liquidity_pool: &Address,
+        user: &Address,
+        merchant: &Address,
+        amount: i128,
+    ) -> Result<(), CreditLineError> {
+        let within_limits: bool = env.invoke_contract(
+            liquidity_pool,
+            &Symbol::new(env, "check_concentration"),
+            (
+                user.clone(),
+                merchant.clone(),
+                Self::merchant_category(env, merchant),
+                amount,
+            )
+                .into_val(env),
+        );
+        if !within_limits {
+            return Err(CreditLineError::ConcentrationLimitExceeded);
+        }
+        Ok(())
     }
 
-    pub fn mark_defaulted(env: Env, loan_id: u64) -> Result<(), CreditLineError> {
-        // 1. Validation: Loan must exist
-        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+    /// Get the liquidity the pool can currently lend
+    /// None when loans are not pool-funded (no token or liquidity pool configured)
+    fn available_liquidity(env: &Env) -> Option<i128> {
+        // Loans are only disbursed by the pool once a token is configured
+        storage::get_token(env)?;
 
-        // 2. Validation: Loan must be Active
-        if loan.status != LoanStatus::Active {
-            return Err(CreditLineError::LoanNotActive);
-        }
+        let liquidity_pool = storage::get_liquidity_pool(env)?;
+        Some(env.invoke_contract(
+            &liquidity_pool,
+            &Symbol::new(env, "get_available_liquidity"),
+            ().into_val(env),
+        ))
+    }
 
-        // 3. Validation: Check if past final payment date
-        // We look at the last installment in the schedule
-        let last_installment = loan
-            .repayment_schedule
-            .last()
-            .ok_or(CreditLineError::Overflow)?; // Should never happen with valid loans
+    /// Get the annual rate the liquidity pool currently charges, in basis points
+    fn current_rate(env: &Env, liquidity_pool: &Address) -> u32 {
+        env.invoke_contract(
+            liquidity_pool,
+            &Symbol::new(env, "get_current_rate"),
+            ().into_val(env),
+        )
+    }
 
-        if env.ledger().timestamp() <= last_installment.due_date {
-            return Err(CreditLineError::LoanNotOverdue);
+    /// Compute a loan's balance, the financed principal plus interest, and scale the
+    /// installments from the purchase total to that balance in proportion to their amounts
+    /// The guarantee stays in escrow as collateral and is not applied to the balance.
+    /// Returns the balance and the repayment schedule including interest
+    fn apply_interest(
+        env: &Env,
+        total_amount: i128,
+        financed_amount: i128,
+        interest_amount: i128,
+        repayment_schedule: Vec<RepaymentInstallment>,
+    ) -> Result<(i128, Vec<RepaymentInstallment>), CreditLineError> {
+        let balance = financed_amount
+            .checked_add(interest_amount)
+            .ok_or(CreditLineError::Overflow)?;
+        if balance == total_amount {
+            return Ok((balance, repayment_schedule));
         }
 
-        // 4. Transfer guarantee to Liquidity Pool
-        let _lp_address =
-            storage::get_liquidity_pool(&env).ok_or(CreditLineError::InsufficientLiquidity)?;
+        let repayment_schedule =
+            Self::scale_schedule(env, &repayment_schedule, balance, total_amount)?;
+        Ok((balance, repayment_schedule))
+    }
 
-        // Note: In a real Soroban contract, you'd use a token client to move funds here.
-        // For Phase 3, we logic-gate the state change.
+    /// Repay part or all of a loan's remaining balance
+    /// The payer may be the borrower or anyone paying on their behalf, unless the borrower
+    /// restricted payers with an allowlist. Repayments are forwarded to the liquidity pool
+    /// and credited to the borrower; once the balance reaches zero the loan is Paid, the
+    /// guarantee is returned to the borrower and the borrower's reputation rises.
+    pub fn repay_loan(
+        env: Env,
+        payer: Address,
+        loan_id: u64,
+        amount: i128,
+    ) -> Result<LoanStatus, CreditLineError> {
+        payer.require_auth();
 
-        // 5. Update Status
-        loan.status = LoanStatus::Defaulted;
-        storage::write_loan(&env, &loan);
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
 
-        // 6. Emit Event
-        events::emit_loan_defaulted(
-            &env,
-            loan.borrower.clone(),
+        Self::validate_payer(&env, &loan.borrower, &payer)?;
+
+        // Repayments after default are collected as recoveries
+        if loan.status == LoanStatus::Defaulted {
+            let loan = Self::collect_recovery(&env, &payer, loan, amount)?;
+            return Ok(loan.status);
+        }
+
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        if amount <= 0 || amount > loan.remaining_balance {
+            return Err(CreditLineError::InvalidAmount);
+        }
+
+        let token = storage::get_token(&env).unwrap_or_else(|| panic!("Token not configured"));
+        let lp_address =
+            storage::get_liquidity_pool(&env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        Self::return_to_pool(&env, &token, &lp_address, &payer, &mut loan, amount)?;
+
+        loan.remaining_balance = loan
+            .remaining_balance
+            .checked_sub(amount)
+            .ok_or(CreditLineError::Underflow)?;
+
+        let guarantee_released = if loan.remaining_balance == 0 {
+            loan.status = LoanStatus::Paid;
+            loan.guarantee_amount
+        } else {
+            0
+        };
+        loan.guarantee_amount = loan
+            .guarantee_amount
+            .checked_sub(guarantee_released)
+            .ok_or(CreditLineError::Underflow)?;
+
+        storage::write_loan(&env, &loan);
+
+        if guarantee_released > 0 {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &loan.borrower,
+                &guarantee_released,
+            );
+        }
+
+        // Whoever paid, the repayment counts towards the borrower's reputation
+        if loan.status == LoanStatus::Paid {
+            Self::apply_reputation_reward(&env, &loan.borrower, types::REPAYMENT_REPUTATION_REWARD);
+        }
+
+        events::emit_loan_repaid(
+            &env,
+            &loan.borrower,
+            &payer,
+            loan_id,
+            amount,
+            loan.remaining_balance,
+        );
+
+        Ok(loan.status)
+    }
+
+    /// Allow a payer to repay the borrower's loans (borrower only)
+    /// Once a borrower allows a payer, only the borrower and allowed payers may repay.
+    pub fn allow_payer(env: Env, borrower: Address, payer: Address) {
+        borrower.require_auth();
+
+        let mut payers =
+            storage::read_allowed_payers(&env, &borrower).unwrap_or_else(|| vec![&env]);
+        if !payers.contains(&payer) {
+            payers.push_back(payer.clone());
+        }
+        storage::write_allowed_payers(&env, &borrower, Some(payers));
+
+        events::emit_payer_updated(&env, &borrower, &payer, true);
+    }
+
+    /// Remove a payer from the borrower's allowlist (borrower only)
+    /// The allowlist stays in force, even when it becomes empty.
+    pub fn revoke_payer(env: Env, borrower: Address, payer: Address) {
+        borrower.require_auth();
+
+        let Some(mut payers) = storage::read_allowed_payers(&env, &borrower) else {
+            return;
+        };
+        if let Some(index) = payers.first_index_of(&payer) {
+            payers.remove(index);
+        }
+        storage::write_allowed_payers(&env, &borrower, Some(payers));
+
+        events::emit_payer_updated(&env, &borrower, &payer, false);
+    }
+
+    /// Drop the borrower's allowlist so anyone may repay again (borrower only)
+    pub fn clear_payer_allowlist(env: Env, borrower: Address) {
+        borrower.require_auth();
+
+        storage::write_allowed_payers(&env, &borrower, None);
+
+        events::emit_payers_cleared(&env, &borrower);
+    }
+
+    /// Get the payers a borrower allows, None when anyone may repay
+    pub fn get_allowed_payers(env: Env, borrower: Address) -> Option<Vec<Address>> {
+        storage::read_allowed_payers(&env, &borrower)
+    }
+
+    /// Pay off a loan in full before its last installment is due (borrower only)
+    /// The borrower pays the remaining balance less the interest not yet earned; the loan
+    /// is Paid, flagged as paid early, the guarantee is returned and the borrower's
+    /// reputation rises.
+    /// Returns the amount paid
+    pub fn payoff_loan(env: Env, borrower: Address, loan_id: u64) -> Result<i128, CreditLineError> {
+        borrower.require_auth();
+
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        if loan.borrower != borrower {
+            return Err(CreditLineError::NotBorrower);
+        }
+
+        let (payoff_amount, rebate) = Self::payoff_terms(&env, &loan)?;
+
+        let token = storage::get_token(&env).unwrap_or_else(|| panic!("Token not configured"));
+        let lp_address =
+            storage::get_liquidity_pool(&env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        Self::return_to_pool(
+            &env,
+            &token,
+            &lp_address,
+            &borrower,
+            &mut loan,
+            payoff_amount,
+        )?;
+
+        let guarantee_released = loan.guarantee_amount;
+        loan.guarantee_amount = 0;
+        loan.remaining_balance = 0;
+        loan.interest_amount = loan
+            .interest_amount
+            .checked_sub(rebate)
+            .ok_or(CreditLineError::Underflow)?;
+        loan.paid_early = Self::before_last_due_date(&env, &loan);
+        loan.status = LoanStatus::Paid;
+
+        storage::write_loan(&env, &loan);
+
+        if guarantee_released > 0 {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &borrower,
+                &guarantee_released,
+            );
+        }
+
+        Self::apply_reputation_reward(&env, &borrower, types::REPAYMENT_REPUTATION_REWARD);
+
+        events::emit_loan_paid_off(&env, &loan, payoff_amount, rebate);
+
+        Ok(payoff_amount)
+    }
+
+    /// Get the amount that would pay off an active loan now: its remaining balance less the
+    /// interest not yet earned
+    pub fn get_payoff_amount(env: Env, loan_id: u64) -> Result<i128, CreditLineError> {
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        let (payoff_amount, _rebate) = Self::payoff_terms(&env, &loan)?;
+        Ok(payoff_amount)
+    }
+
+    /// Replace the repayment schedule of a borrower in hardship (admin or risk manager only)
+    /// The new schedule must cover exactly the remaining balance with future, ordered due dates;
+    /// it is then scaled to the balance re-priced for the new term.
+    /// The previous schedule is kept as an audit trail and the borrower's reputation takes a
+    /// smaller penalty than a default.
+    pub fn restructure_loan(
+        env: Env,
+        caller: Address,
+        loan_id: u64,
+        new_schedule: Vec<RepaymentInstallment>,
+        reason: Symbol,
+    ) -> Result<(), CreditLineError> {
+        caller.require_auth();
+        access::require_risk_manager(&env, &caller);
+
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        Self::validate_restructured_schedule(&env, &new_schedule, loan.remaining_balance)?;
+
+        // Interest not earned yet is replaced by interest on the outstanding principal over
+        // the new term, and the schedule is scaled to the re-priced balance
+        let (_payoff_amount, unearned_interest) = Self::payoff_terms(&env, &loan)?;
+        let interest_amount = Self::calculate_interest(
+            &env,
+            loan.outstanding_principal,
+            loan.interest_rate_bps,
+            &new_schedule,
+        );
+        let new_balance = loan
+            .remaining_balance
+            .checked_sub(unearned_interest)
+            .and_then(|v| v.checked_add(interest_amount))
+            .ok_or(CreditLineError::Overflow)?;
+        let new_schedule = if new_balance == loan.remaining_balance {
+            new_schedule
+        } else {
+            Self::scale_schedule(&env, &new_schedule, new_balance, loan.remaining_balance)?
+        };
+
+        let restructuring = Restructuring {
+            loan_id,
+            previous_schedule: loan.repayment_schedule.clone(),
+            new_schedule: new_schedule.clone(),
+            remaining_balance: new_balance,
+            interest_amount,
+            reason,
+            restructured_by: caller,
+            restructured_at: env.ledger().timestamp(),
+        };
+        storage::add_restructuring(&env, &restructuring);
+
+        loan.interest_amount = loan
+            .interest_amount
+            .checked_sub(unearned_interest)
+            .and_then(|v| v.checked_add(interest_amount))
+            .ok_or(CreditLineError::Overflow)?;
+        loan.remaining_balance = new_balance;
+        loan.repayment_schedule = new_schedule;
+        storage::write_loan(&env, &loan);
+
+        Self::apply_reputation_penalty(&env, &loan.borrower, types::RESTRUCTURE_REPUTATION_PENALTY);
+
+        events::emit_loan_restructured(&env, &loan.borrower, &restructuring);
+
+        Ok(())
+    }
+
+    /// Get the restructurings of a loan, oldest first
+    pub fn get_restructurings(env: Env, loan_id: u64) -> Vec<Restructuring> {
+        storage::read_restructurings(&env, loan_id)
+    }
+
+    pub fn mark_defaulted(env: Env, loan_id: u64) -> Result<(), CreditLineError> {
+        // 1. Validation: Loan must exist
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        // 2. Validation: Loan must be Active
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        // 3. Validation: Check if past final payment date
+        // We look at the last installment in the schedule
+        let last_installment = loan
+            .repayment_schedule
+            .last()
+            .ok_or(CreditLineError::Overflow)?; // Should never happen with valid loans
+
+        if env.ledger().timestamp() <= last_installment.due_date {
+            return Err(CreditLineError::LoanNotOverdue);
+        }
+
+        // Principal the borrower failed to repay, reported to the merchant registry
+        let defaulted_principal = loan.outstanding_principal;
+
+        // 4. Transfer guarantee to Liquidity Pool
+        let lp_address =
+            storage::get_liquidity_pool(&env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        if let Some(token) = storage::get_token(&env) {
+            let from = env.current_contract_address();
+
+            // A disbursement never paid to the merchant goes back to the pool first
+            let held = Self::take_held_settlement(&env, loan_id, i128::MAX);
+            Self::return_to_pool(&env, &token, &lp_address, &from, &mut loan, held)?;
+            loan.remaining_balance = (loan.remaining_balance - held).max(0);
+
+            // The guarantee is forfeited only up to the balance still unpaid; the rest is the borrower's
+            let forfeited = loan.guarantee_amount.min(loan.remaining_balance);
+            Self::return_to_pool(&env, &token, &lp_address, &from, &mut loan, forfeited)?;
+            loan.remaining_balance -= forfeited;
+
+            let guarantee_released = loan.guarantee_amount - forfeited;
+            loan.guarantee_amount = forfeited;
+            if guarantee_released > 0 {
+                token::Client::new(&env, &token).transfer(
+                    &from,
+                    &loan.borrower,
+                    &guarantee_released,
+                );
+            }
+        }
+
+        // 5. Write off the principal the guarantee did not cover as a pool loss
+        let write_off = WriteOff {
+            loan_id,
+            principal_written_off: loan.outstanding_principal,
+            guarantee_forfeited: loan.guarantee_amount,
+            recovered_amount: 0,
+            written_off_at: env.ledger().timestamp(),
+        };
+        if write_off.principal_written_off > 0 {
+            env.invoke_contract::<()>(
+                &lp_address,
+                &Symbol::new(&env, "recognize_loss"),
+                (loan_id, write_off.principal_written_off).into_val(&env),
+            );
+            loan.outstanding_principal = 0;
+        }
+        storage::write_write_off(&env, &write_off);
+        events::emit_loan_written_off(&env, &loan.borrower, &write_off);
+
+        // 6. Update Status: a balance already cleared by the guarantee and held funds
+        // leaves nothing to recover
+        loan.status = if loan.remaining_balance == 0 {
+            LoanStatus::Recovered
+        } else {
+            LoanStatus::Defaulted
+        };
+        storage::write_loan(&env, &loan);
+
+        // 7. Emit Event
+        events::emit_loan_defaulted(
+            &env,
+            loan.borrower.clone(),
             loan_id,
             loan.total_amount,
             loan.remaining_balance,
             loan.guarantee_amount,
         );
 
-        // 7. Trigger reputation decrease (Phase 4 placeholder)
-        if let Some(reputation_contract) = storage::get_reputation_contract(&env) {
-            // Only attempt the call if we aren't in a test or if you've set up a mock
-            // For now, let's just make sure the call is reachable
+        Self::report_merchant_outcome(
+            &env,
+            "record_loan_defaulted",
+            &loan.merchant,
+            defaulted_principal,
+        );
+
+        // 8. Lower the borrower's reputation
+        Self::apply_reputation_penalty(&env, &loan.borrower, types::DEFAULT_REPUTATION_PENALTY);
+
+        if loan.status == LoanStatus::Recovered {
+            let restored = Self::restore_reputation(&env, &loan.borrower);
+            events::emit_loan_fully_recovered(&env, &loan.borrower, &write_off, restored);
+        }
+
+        Ok(())
+    }
+
+    /// Collect funds on a defaulted loan
+    /// Recoveries reduce the unpaid balance and are returned to the liquidity pool as recovered income.
+    /// Once the balance is cleared the loan moves to Recovered.
+    pub fn recover_loan(
+        env: Env,
+        payer: Address,
+        loan_id: u64,
+        amount: i128,
+    ) -> Result<i128, CreditLineError> {
+        payer.require_auth();
+
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        Self::validate_payer(&env, &loan.borrower, &payer)?;
+        let loan = Self::collect_recovery(&env, &payer, loan, amount)?;
+
+        Ok(loan.remaining_balance)
+    }
+
+    /// Get the write-off recorded when a loan defaulted
+    pub fn get_write_off(env: Env, loan_id: u64) -> Option<WriteOff> {
+        storage::read_write_off(&env, loan_id)
+    }
+
+    /// Refund part or all of a purchase on behalf of the merchant
+    /// `amount` is the financed principal being refunded, at most the principal not refunded
+    /// yet. Funds are taken from the disbursement still held for the merchant first, then from
+    /// the merchant. The refunded share of the principal the borrower still owes goes back to
+    /// the liquidity pool and the share the borrower already repaid goes back to the borrower;
+    /// the balance and schedule shrink proportionally and the guarantee is released pro-rata.
+    /// A full refund closes the loan as Refunded. Reputation is not affected.
+    pub fn refund_loan(
+        env: Env,
+        merchant: Address,
+        loan_id: u64,
+        amount: i128,
+    ) -> Result<(), CreditLineError> {
+        merchant.require_auth();
+
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+
+        if loan.merchant != merchant {
+            return Err(CreditLineError::NotMerchant);
+        }
+
+        let refundable = loan
+            .financed_amount
+            .checked_sub(loan.refunded_amount)
+            .ok_or(CreditLineError::Underflow)?;
+        if amount <= 0 || amount > refundable {
+            return Err(CreditLineError::InvalidAmount);
+        }
+
+        let token = storage::get_token(&env).unwrap_or_else(|| panic!("Token not configured"));
+        let lp_address =
+            storage::get_liquidity_pool(&env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        let full_refund = amount == refundable;
+        let refunded_share = |value: i128| -> Result<i128, CreditLineError> {
+            if full_refund {
+                return Ok(value);
+            }
+            value
+                .checked_mul(amount)
+                .and_then(|v| v.checked_div(refundable))
+                .ok_or(CreditLineError::Overflow)
+        };
+
+        let to_pool = refunded_share(loan.outstanding_principal)?;
+        let repaid_principal = amount
+            .checked_sub(to_pool)
+            .ok_or(CreditLineError::Underflow)?;
+        let guarantee_released = refunded_share(loan.guarantee_amount)?;
+
+        let old_balance = loan.remaining_balance;
+        let new_balance = old_balance
+            .checked_sub(refunded_share(old_balance)?)
+            .ok_or(CreditLineError::Underflow)?;
+
+        if old_balance > 0 {
+            loan.repayment_schedule =
+                Self::scale_schedule(&env, &loan.repayment_schedule, new_balance, old_balance)?;
+        }
+        loan.remaining_balance = new_balance;
+        loan.guarantee_amount = loan
+            .guarantee_amount
+            .checked_sub(guarantee_released)
+            .ok_or(CreditLineError::Underflow)?;
+        let first_refund = loan.refunded_amount == 0;
+        loan.refunded_amount = loan
+            .refunded_amount
+            .checked_add(amount)
+            .ok_or(CreditLineError::Overflow)?;
+
+        if full_refund {
+            loan.status = LoanStatus::Refunded;
+        }
+
+        // Refund from the disbursement still held for the merchant first
+        let from_held = Self::take_held_settlement(&env, loan_id, amount);
+        let from = env.current_contract_address();
+        if amount > from_held {
+            token::Client::new(&env, &token).transfer(&merchant, &from, &(amount - from_held));
+        }
+        Self::return_to_pool(&env, &token, &lp_address, &from, &mut loan, to_pool)?;
+
+        storage::write_loan(&env, &loan);
+
+        let to_borrower = repaid_principal
+            .checked_add(guarantee_released)
+            .ok_or(CreditLineError::Overflow)?;
+        if to_borrower > 0 {
+            token::Client::new(&env, &token).transfer(&from, &loan.borrower, &to_borrower);
+        }
+
+        Self::report_merchant_refund(&env, &merchant, amount, first_refund);
+
+        events::emit_loan_refunded(
+            &env,
+            &merchant,
+            loan_id,
+            amount,
+            new_balance,
+            guarantee_released,
+        );
+
+        Ok(())
+    }
+
+    /// Confirm delivery and release the held disbursement to the merchant (borrower only)
+    /// Returns the amount released
+    pub fn confirm_delivery(
+        env: Env,
+        borrower: Address,
+        loan_id: u64,
+    ) -> Result<i128, CreditLineError> {
+        borrower.require_auth();
+
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.borrower != borrower {
+            return Err(CreditLineError::NotBorrower);
+        }
+
+        let settlement = Self::held_settlement(&env, loan_id)?;
+
+        Ok(Self::release_to_merchant(&env, &loan, settlement, true))
+    }
+
+    /// Release a held disbursement to the merchant once the delivery window has elapsed
+    /// without a confirmation or dispute. Callable by anyone.
+    /// Returns the amount released
+    pub fn release_settlement(env: Env, loan_id: u64) -> Result<i128, CreditLineError> {
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+
+        let settlement = Self::held_settlement(&env, loan_id)?;
+        if env.ledger().timestamp() < settlement.release_after {
+            return Err(CreditLineError::DeliveryWindowOpen);
+        }
+
+        Ok(Self::release_to_merchant(&env, &loan, settlement, false))
+    }
+
+    /// Contest a purchase (borrower only), within `DISPUTE_WINDOW_SECONDS` of origination
+    /// The loan is Disputed until the arbiter resolves it: installments stop coming due,
+    /// the loan cannot be defaulted, and a disbursement still held for the merchant is frozen.
+    /// A dispute the arbiter leaves pending past the resolution timeout can be expired
+    /// in the merchant's favour with `expire_dispute`
+    pub fn open_dispute(
+        env: Env,
+        borrower: Address,
+        loan_id: u64,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), CreditLineError> {
+        borrower.require_auth();
+
+        let mut loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.status != LoanStatus::Active {
+            return Err(CreditLineError::LoanNotActive);
+        }
+        if loan.borrower != borrower {
+            return Err(CreditLineError::NotBorrower);
+        }
+        if storage::read_dispute(&env, loan_id).is_some() {
+            return Err(CreditLineError::DisputeAlreadyOpened);
+        }
+        if env.ledger().timestamp()
+            > loan
+                .created_at
+                .saturating_add(types::DISPUTE_WINDOW_SECONDS)
+        {
+            return Err(CreditLineError::DisputeWindowClosed);
+        }
+
+        if let Ok(mut settlement) = Self::held_settlement(&env, loan_id) {
+            settlement.status = SettlementStatus::Disputed;
+            storage::write_settlement(&env, &settlement);
+        }
+
+        loan.status = LoanStatus::Disputed;
+        storage::write_loan(&env, &loan);
+
+        let dispute = Dispute {
+            loan_id,
+            evidence_hash,
+            opened_at: env.ledger().timestamp(),
+            resolved_at: 0,
+            outcome: DisputeOutcome::Pending,
+        };
+        storage::write_dispute(&env, &dispute);
+
+        events::emit_loan_dispute_opened(&env, &borrower, &dispute);
+
+        Ok(())
+    }
+
+    /// Resolve a dispute (arbiter only)
+    /// In the borrower's favour the merchant refunds the financed principal, from the held
+    /// disbursement and then its bond, and the loan closes as Refunded; in the merchant's favour
+    /// the schedule resumes with every due date pushed back by the time spent in dispute,
+    /// and a frozen disbursement is released to the merchant
+    pub fn resolve_dispute(
+        env: Env,
+        arbiter: Address,
+        loan_id: u64,
+        in_favor_of_borrower: bool,
+    ) -> Result<(), CreditLineError> {
+        arbiter.require_auth();
+        access::require_arbiter(&env, &arbiter);
+
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.status != LoanStatus::Disputed {
+            return Err(CreditLineError::LoanNotDisputed);
+        }
+        let mut dispute =
+            storage::read_dispute(&env, loan_id).ok_or(CreditLineError::LoanNotDisputed)?;
+
+        let now = env.ledger().timestamp();
+        dispute.resolved_at = now;
+
+        if in_favor_of_borrower {
+            dispute.outcome = DisputeOutcome::BorrowerFavored;
+            let refunded = loan.financed_amount - loan.refunded_amount;
+            let first_refund = loan.refunded_amount == 0;
+            let merchant = loan.merchant.clone();
+            Self::refund_disputed_loan(&env, loan)?;
+            Self::report_merchant_refund(&env, &merchant, refunded, first_refund);
+        } else {
+            dispute.outcome = DisputeOutcome::MerchantFavored;
+            Self::resume_disputed_loan(&env, loan, &dispute);
+        }
+
+        storage::write_dispute(&env, &dispute);
+        events::emit_loan_dispute_resolved(&env, &arbiter, &dispute);
+
+        Ok(())
+    }
+
+    /// Resolve a dispute in the merchant's favour once the arbiter has left it pending for
+    /// `DISPUTE_RESOLUTION_TIMEOUT_SECONDS`, so a dispute cannot hold off default indefinitely.
+    /// Callable by anyone.
+    pub fn expire_dispute(env: Env, loan_id: u64) -> Result<(), CreditLineError> {
+        let loan = storage::read_loan(&env, loan_id).ok_or(CreditLineError::LoanNotFound)?;
+        if loan.status != LoanStatus::Disputed {
+            return Err(CreditLineError::LoanNotDisputed);
+        }
+        let mut dispute =
+            storage::read_dispute(&env, loan_id).ok_or(CreditLineError::LoanNotDisputed)?;
+
+        let now = env.ledger().timestamp();
+        if now
+            < dispute
+                .opened_at
+                .saturating_add(types::DISPUTE_RESOLUTION_TIMEOUT_SECONDS)
+        {
+            return Err(CreditLineError::DisputeNotExpired);
+        }
+
+        dispute.resolved_at = now;
+        dispute.outcome = DisputeOutcome::MerchantFavored;
+        Self::resume_disputed_loan(&env, loan, &dispute);
+
+        storage::write_dispute(&env, &dispute);
+        events::emit_loan_dispute_expired(&env, &dispute);
+
+        Ok(())
+    }
+
+    /// Get the dispute opened on a loan
+    pub fn get_dispute(env: Env, loan_id: u64) -> Option<Dispute> {
+        storage::read_dispute(&env, loan_id)
+    }
+
+    /// Get the settlement of a loan's merchant disbursement
+    pub fn get_settlement(env: Env, loan_id: u64) -> Option<Settlement> {
+        storage::read_settlement(&env, loan_id)
+    }
+
+    /// Reactivate a loan whose dispute went the merchant's way: every due date is pushed back
+    /// by the time spent in dispute and a frozen disbursement is released to the merchant
+    fn resume_disputed_loan(env: &Env, mut loan: Loan, dispute: &Dispute) {
+        let paused = dispute.resolved_at.saturating_sub(dispute.opened_at);
+        let mut schedule = Vec::new(env);
+        for installment in loan.repayment_schedule.iter() {
+            schedule.push_back(RepaymentInstallment {
+                due_date: installment.due_date.saturating_add(paused),
+                amount: installment.amount,
+            });
+        }
+        loan.repayment_schedule = schedule;
+        loan.status = LoanStatus::Active;
+        storage::write_loan(env, &loan);
+
+        if let Some(settlement) = storage::read_settlement(env, loan.loan_id) {
+            if settlement.status == SettlementStatus::Disputed {
+                Self::release_to_merchant(env, &loan, settlement, false);
+            }
+        }
+    }
+
+    /// Transfer funds to the liquidity pool and report them as repaid principal (up to the
+    /// loan's outstanding principal) and income (the rest)
+    fn return_to_pool(
+        env: &Env,
+        token: &Address,
+        lp_address: &Address,
+        from: &Address,
+        loan: &mut Loan,
+        amount: i128,
+    ) -> Result<(), CreditLineError> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        let principal = amount.min(loan.outstanding_principal);
+        let income = amount
+            .checked_sub(principal)
+            .ok_or(CreditLineError::Underflow)?;
+        loan.outstanding_principal = loan
+            .outstanding_principal
+            .checked_sub(principal)
+            .ok_or(CreditLineError::Underflow)?;
+
+        token::Client::new(env, token).transfer(from, lp_address, &amount);
+
+        env.invoke_contract::<()>(
+            lp_address,
+            &Symbol::new(env, "receive_repayment"),
+            (loan.loan_id, principal, income).into_val(env),
+        );
+
+        Ok(())
+    }
+
+    /// Compute the payoff amount of a loan and the unearned interest rebated from its balance
+    /// Interest accrues linearly from origination, or from the latest restructuring for the
+    /// interest it charged, to the last due date; the pool always gets at least its
+    /// outstanding principal back
+    fn payoff_terms(env: &Env, loan: &Loan) -> Result<(i128, i128), CreditLineError> {
+        let (accrual_start, accruing_interest) =
+            match storage::read_restructurings(env, loan.loan_id).last() {
+                Some(restructuring) => {
+                    (restructuring.restructured_at, restructuring.interest_amount)
+                }
+                None => (loan.created_at, loan.interest_amount),
+            };
+        let last_due_date = loan
+            .repayment_schedule
+            .last()
+            .map(|installment| installment.due_date)
+            .unwrap_or(accrual_start);
+        let term_seconds = last_due_date.saturating_sub(accrual_start);
+        let remaining_seconds = last_due_date.saturating_sub(env.ledger().timestamp());
+
+        let unearned_interest = if term_seconds == 0 {
+            0
+        } else {
+            accruing_interest
+                .checked_mul(remaining_seconds.min(term_seconds) as i128)
+                .and_then(|v| v.checked_div(term_seconds as i128))
+                .ok_or(CreditLineError::Overflow)?
+        };
+        let rebate = unearned_interest.min(loan.remaining_balance);
+
+        let payoff_amount = loan
+            .remaining_balance
+            .checked_sub(rebate)
+            .ok_or(CreditLineError::Underflow)?
+            .max(loan.outstanding_principal);
+
+        Ok((
+            payoff_amount,
+            loan.remaining_balance.saturating_sub(payoff_amount),
+        ))
+    }
+
+    /// Whether the loan's last installment is not due yet
+    fn before_last_due_date(env: &Env, loan: &Loan) -> bool {
+        loan.repayment_schedule
+            .last()
+            .is_some_and(|installment| env.ledger().timestamp() < installment.due_date)
+    }
+
+    /// Validate that the payer may repay on the borrower's behalf
+    fn validate_payer(
+        env: &Env,
+        borrower: &Address,
+        payer: &Address,
+    ) -> Result<(), CreditLineError> {
+        if payer == borrower {
+            return Ok(());
+        }
+
+        match storage::read_allowed_payers(env, borrower) {
+            Some(payers) if !payers.contains(payer) => Err(CreditLineError::PayerNotAllowed),
+            _ => Ok(()),
+        }
+    }
+
+    /// Collect a recovery on a defaulted loan and return it to the liquidity pool
+    /// Clearing the unpaid balance moves the loan to Recovered and restores part of the
+    /// borrower's reputation when configured.
+    fn collect_recovery(
+        env: &Env,
+        payer: &Address,
+        mut loan: Loan,
+        amount: i128,
+    ) -> Result<Loan, CreditLineError> {
+        if loan.status != LoanStatus::Defaulted {
+            return Err(CreditLineError::LoanNotDefaulted);
+        }
+
+        if amount <= 0 || amount > loan.remaining_balance {
+            return Err(CreditLineError::InvalidAmount);
+        }
+
+        let token = storage::get_token(env).unwrap_or_else(|| panic!("Token not configured"));
+        let lp_address =
+            storage::get_liquidity_pool(env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        token::Client::new(env, &token).transfer(payer, &lp_address, &amount);
+        env.invoke_contract::<()>(
+            &lp_address,
+            &Symbol::new(env, "receive_recovery"),
+            (amount,).into_val(env),
+        );
+
+        loan.remaining_balance = loan
+            .remaining_balance
+            .checked_sub(amount)
+            .ok_or(CreditLineError::Underflow)?;
+        if loan.remaining_balance == 0 {
+            loan.status = LoanStatus::Recovered;
+        }
+        storage::write_loan(env, &loan);
+
+        let mut write_off =
+            storage::read_write_off(env, loan.loan_id).ok_or(CreditLineError::LoanNotDefaulted)?;
+        write_off.recovered_amount = write_off
+            .recovered_amount
+            .checked_add(amount)
+            .ok_or(CreditLineError::Overflow)?;
+        storage::write_write_off(env, &write_off);
+
+        events::emit_loan_recovered(env, payer, loan.loan_id, amount, write_off.recovered_amount);
+
+        if loan.status == LoanStatus::Recovered {
+            let restored = Self::restore_reputation(env, &loan.borrower);
+            events::emit_loan_fully_recovered(env, &loan.borrower, &write_off, restored);
+        }
+
+        Ok(loan)
+    }
+
+    /// Give back the configured reputation points to a borrower whose default was recovered
+    /// Returns the points restored
+    fn restore_reputation(env: &Env, borrower: &Address) -> u32 {
+        Self::apply_reputation_reward(env, borrower, storage::get_recovery_restore(env))
+    }
+
+    /// Raise the borrower's reputation by `points`, never above the maximum score
+    /// Returns the points added
+    fn apply_reputation_reward(env: &Env, borrower: &Address, points: u32) -> u32 {
+        if points == 0 {
+            return 0;
+        }
+
+        let Some(reputation_contract) = storage::get_reputation_contract(env) else {
+            return 0;
+        };
+
+        let score: u32 = env.invoke_contract(
+            &reputation_contract,
+            &symbol_short!("get_score"),
+            (borrower.clone(),).into_val(env),
+        );
+        let points = points.min(types::MAX_REPUTATION_SCORE.saturating_sub(score));
+        if points == 0 {
+            return 0;
+        }
+
+        env.invoke_contract::<()>(
+            &reputation_contract,
+            &Symbol::new(env, "increase_score"),
+            (env.current_contract_address(), borrower.clone(), points).into_val(env),
+        );
+
+        points
+    }
+
+    /// Validate that a restructured schedule has future, ordered due dates and positive
+    /// installments covering exactly the remaining balance
+    fn validate_restructured_schedule(
+        env: &Env,
+        schedule: &Vec<RepaymentInstallment>,
+        remaining_balance: i128,
+    ) -> Result<(), CreditLineError> {
+        if schedule.is_empty() {
+            return Err(CreditLineError::InvalidSchedule);
+        }
+
+        let mut previous_due_date = env.ledger().timestamp();
+        let mut total: i128 = 0;
+        for installment in schedule.iter() {
+            if installment.amount <= 0 || installment.due_date <= previous_due_date {
+                return Err(CreditLineError::InvalidSchedule);
+            }
+            previous_due_date = installment.due_date;
+            total = total
+                .checked_add(installment.amount)
+                .ok_or(CreditLineError::Overflow)?;
+        }
+
+        if total != remaining_balance {
+            return Err(CreditLineError::InvalidSchedule);
+        }
+
+        Ok(())
+    }
+
+    /// Lower the borrower's reputation by `points`, never below zero
+    fn apply_reputation_penalty(env: &Env, borrower: &Address, points: u32) {
+        let Some(reputation_contract) = storage::get_reputation_contract(env) else {
+            return;
+        };
+
+        let score: u32 = env.invoke_contract(
+            &reputation_contract,
+            &symbol_short!("get_score"),
+            (borrower.clone(),).into_val(env),
+        );
+        let penalty = score.min(points);
+        if penalty == 0 {
+            return;
+        }
+
+        env.invoke_contract::<()>(
+            &reputation_contract,
+            &Symbol::new(env, "decrease_score"),
+            (env.current_contract_address(), borrower.clone(), penalty).into_val(env),
+        );
+    }
+
+    /// Validate a prospective loan and fill in its pricing
+    fn price_quote(
+        env: &Env,
+        user: &Address,
+        merchant: &Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+        terms: &LoanTerms,
+        quote: &mut LoanQuote,
+    ) -> Result<(), CreditLineError> {
+        Self::validate_guarantee(total_amount, guarantee_amount)?;
+        Self::validate_merchant(env, merchant)?;
+        Self::validate_reputation(env, user)?;
+        let repayment_schedule = Self::build_schedule(env, total_amount, terms)?;
+        let required_from_pool = Self::validate_liquidity(env, total_amount)?;
+
+        quote.merchant_fee = Self::calculate_merchant_fee(env, merchant, total_amount);
+
+        let mut financed = 0;
+        if Self::available_liquidity(env).is_some() && required_from_pool > 0 {
+            if let Some(liquidity_pool) = storage::get_liquidity_pool(env) {
+                Self::validate_concentration(
+                    env,
+                    &liquidity_pool,
+                    user,
+                    merchant,
+                    required_from_pool,
+                )?;
+                quote.interest_rate_bps = Self::current_rate(env, &liquidity_pool);
+                financed = required_from_pool;
+            }
+        }
+
+        quote.interest_amount =
+            Self::calculate_interest(env, financed, quote.interest_rate_bps, &repayment_schedule);
+        let (_balance, repayment_schedule) = Self::apply_interest(
+            env,
+            total_amount,
+            required_from_pool,
+            quote.interest_amount,
+            repayment_schedule,
+        )?;
+        quote.repayment_schedule = repayment_schedule;
+
+        Ok(())
+    }
+
+    /// Hold the borrower's guarantee in escrow until the loan is closed
+    /// The CreditLine keeps it as collateral; the merchant is paid from the pool alone
+    fn escrow_guarantee(env: &Env, user: &Address, guarantee_amount: i128) {
+        if let Some(token) = storage::get_token(env) {
+            token::Client::new(env, &token).transfer(
+                user,
+                &env.current_contract_address(),
+                &guarantee_amount,
+            );
+        }
+    }
+
+    /// Build the record of a loan that has not been funded yet
+    fn pending_loan(
+        env: &Env,
+        loan_id: u64,
+        user: Address,
+        merchant: Address,
+        total_amount: i128,
+        guarantee_amount: i128,
+        repayment_schedule: Vec<RepaymentInstallment>,
+    ) -> Loan {
+        Loan {
+            loan_id,
+            borrower: user,
+            merchant,
+            total_amount,
+            guarantee_amount,
+            remaining_balance: total_amount,
+            repayment_schedule,
+            status: LoanStatus::Pending,
+            created_at: env.ledger().timestamp(),
+            merchant_fee: MerchantFee {
+                total: 0,
+                liquidity_pool: 0,
+                treasury: 0,
+                incentive_fund: 0,
+            },
+            outstanding_principal: 0,
+            financed_amount: 0,
+            refunded_amount: 0,
+            interest_rate_bps: 0,
+            interest_amount: 0,
+            order_id: None,
+            invoice_hash: None,
+            paid_early: false,
+        }
+    }
+
+    /// Have the liquidity pool price and fund a pending loan whose guarantee is already escrowed,
+    /// then record it as Active
+    fn originate_loan(env: &Env, loan: Loan, required_from_pool: i128) -> Loan {
+        let Loan {
+            loan_id,
+            borrower: user,
+            merchant,
+            total_amount,
+            guarantee_amount,
+            repayment_schedule,
+            order_id,
+            invoice_hash,
+            ..
+        } = loan;
+
+        let merchant_fee = Self::calculate_merchant_fee(env, &merchant, total_amount);
+
+        // Have the liquidity pool price the loan and finance the purchase; the merchant is paid
+        // the purchase price less the merchant fee
+        let mut outstanding_principal = 0;
+        let mut interest_rate_bps = 0;
+        if let Some(token) = storage::get_token(env) {
+            if let Some(liquidity_pool) = storage::get_liquidity_pool(env) {
+                if required_from_pool > 0 {
+                    interest_rate_bps = Self::current_rate(env, &liquidity_pool);
+
+                    env.invoke_contract::<()>(
+                        &liquidity_pool,
+                        &symbol_short!("fund_loan"),
+                        (
+                            loan_id,
+                            required_from_pool,
+                            user.clone(),
+                            merchant.clone(),
+                            Self::merchant_category(env, &merchant),
+                        )
+                            .into_val(env),
+                    );
+                    let disbursement = required_from_pool
+                        .checked_sub(merchant_fee.total)
+                        .filter(|amount| *amount >= 0)
+                        .unwrap_or_else(|| {
+                            panic_with_error!(env, CreditLineError::FeeExceedsFinancing)
+                        });
+                    Self::collect_merchant_fee(
+                        env,
+                        &token,
+                        &liquidity_pool,
+                        loan_id,
+                        &merchant_fee,
+                    );
+                    Self::disburse_to_merchant(
+                        env,
+                        &token,
+                        loan_id,
+                        &user,
+                        &merchant,
+                        disbursement,
+                    );
+                    outstanding_principal = required_from_pool;
+                }
+            }
+        }
+
+        let interest_amount = Self::calculate_interest(
+            env,
+            outstanding_principal,
+            interest_rate_bps,
+            &repayment_schedule,
+        );
+
+        let (balance, repayment_schedule) = Self::apply_interest(
+            env,
+            total_amount,
+            required_from_pool,
+            interest_amount,
+            repayment_schedule,
+        )
+        .unwrap_or_else(|error| panic_with_error!(env, error));
+
+        // Create loan record
+        let loan = Loan {
+            loan_id,
+            borrower: user,
+            merchant,
+            total_amount,
+            guarantee_amount,
+            remaining_balance: balance,
+            repayment_schedule,
+            status: LoanStatus::Active,
+            created_at: env.ledger().timestamp(),
+            merchant_fee,
+            outstanding_principal,
+            financed_amount: outstanding_principal,
+            refunded_amount: 0,
+            interest_rate_bps,
+            interest_amount,
+            order_id,
+            invoice_hash,
+            paid_early: false,
+        };
+
+        storage::write_loan(env, &loan);
+
+        Self::report_merchant_outcome(
+            env,
+            "record_loan_originated",
+            &loan.merchant,
+            loan.financed_amount,
+        );
+
+        loan
+    }
+
+    /// Pay out a merchant fee withheld from a disbursement
+    /// The pool's share is booked as pool income; the treasury and incentive fund shares go to
+    /// their configured addresses, or to the pool as well when not configured
+    fn collect_merchant_fee(
+        env: &Env,
+        token: &Address,
+        liquidity_pool: &Address,
+        loan_id: u64,
+        merchant_fee: &MerchantFee,
+    ) {
+        let token_client = token::Client::new(env, token);
+        let from = env.current_contract_address();
+        let mut pool_share = merchant_fee.liquidity_pool;
+
+        for (recipient, share) in [
+            (storage::get_treasury(env), merchant_fee.treasury),
+            (
+                storage::get_incentive_fund(env),
+                merchant_fee.incentive_fund,
+            ),
+        ] {
+            match recipient {
+                Some(recipient) if share > 0 => token_client.transfer(&from, &recipient, &share),
+                Some(_) => {}
+                None => pool_share += share,
+            }
+        }
+
+        if pool_share > 0 {
+            token_client.transfer(&from, liquidity_pool, &pool_share);
+            env.invoke_contract::<()>(
+                liquidity_pool,
+                &Symbol::new(env, "receive_repayment"),
+                (loan_id, 0_i128, pool_share).into_val(env),
+            );
+        }
+    }
+
+    /// Hold the amount funded by the liquidity pool until the merchant's delivery is confirmed,
+    /// or lock it in an escrow with a single delivery milestone when an escrow adapter is configured
+    /// Either way the settlement follows the same delivery window, confirmation and refund flows
+    fn disburse_to_merchant(
+        env: &Env,
+        token: &Address,
+        loan_id: u64,
+        borrower: &Address,
+        merchant: &Address,
+        amount: i128,
+    ) {
+        let escrow_adapter = storage::get_escrow_adapter(env);
+        if let Some(escrow_adapter) = &escrow_adapter {
+            token::Client::new(env, token).transfer(
+                &env.current_contract_address(),
+                escrow_adapter,
+                &amount,
+            );
+            env.invoke_contract::<()>(
+                escrow_adapter,
+                &Symbol::new(env, "open_escrow"),
+                (
+                    loan_id,
+                    borrower.clone(),
+                    merchant.clone(),
+                    vec![env, amount],
+                )
+                    .into_val(env),
+            );
+        }
+
+        storage::write_settlement(
+            env,
+            &Settlement {
+                loan_id,
+                amount,
+                release_after: env
+                    .ledger()
+                    .timestamp()
+                    .saturating_add(storage::get_delivery_window(env)),
+                status: SettlementStatus::Held,
+                escrow_adapter,
+            },
+        );
+    }
+
+    /// Get a settlement that is still held for the merchant
+    fn held_settlement(env: &Env, loan_id: u64) -> Result<Settlement, CreditLineError> {
+        let settlement =
+            storage::read_settlement(env, loan_id).ok_or(CreditLineError::SettlementNotFound)?;
+        if settlement.status != SettlementStatus::Held {
+            return Err(CreditLineError::SettlementNotHeld);
+        }
+        Ok(settlement)
+    }
+
+    /// Pay a held settlement out to the merchant
+    /// Returns the amount released
+    fn release_to_merchant(
+        env: &Env,
+        loan: &Loan,
+        mut settlement: Settlement,
+        confirmed: bool,
+    ) -> i128 {
+        let released = match &settlement.escrow_adapter {
+            Some(escrow_adapter) => env.invoke_contract::<i128>(
+                escrow_adapter,
+                &Symbol::new(env, "release_escrow"),
+                (loan.loan_id,).into_val(env),
+            ),
+            None => {
+                let token =
+                    storage::get_token(env).unwrap_or_else(|| panic!("Token not configured"));
+                token::Client::new(env, &token).transfer(
+                    &env.current_contract_address(),
+                    &loan.merchant,
+                    &settlement.amount,
+                );
+                settlement.amount
+            }
+        };
+
+        settlement.status = SettlementStatus::Released;
+        storage::write_settlement(env, &settlement);
+
+        events::emit_settlement_released(env, &loan.merchant, loan.loan_id, released, confirmed);
+
+        released
+    }
+
+    /// Take up to `max_amount` from a settlement not yet paid to the merchant
+    /// Escrowed funds are first refunded by the adapter; whatever is not taken stays held by
+    /// the CreditLine. Returns the amount taken; the settlement is Returned once nothing is left
+    fn take_held_settlement(env: &Env, loan_id: u64, max_amount: i128) -> i128 {
+        let mut settlement = match storage::read_settlement(env, loan_id) {
+            Some(settlement)
+                if settlement.status == SettlementStatus::Held
+                    || settlement.status == SettlementStatus::Disputed =>
+            {
+                settlement
+            }
+            _ => return 0,
+        };
+
+        if let Some(escrow_adapter) = settlement.escrow_adapter.take() {
+            settlement.amount = env.invoke_contract::<i128>(
+                &escrow_adapter,
+                &Symbol::new(env, "refund_escrow"),
+                (loan_id,).into_val(env),
+            );
+            // The borrower may have released every milestone through the adapter directly
+            if settlement.amount == 0 {
+                settlement.status = SettlementStatus::Released;
+                storage::write_settlement(env, &settlement);
+                return 0;
+            }
+        }
+
+        let taken = settlement.amount.min(max_amount);
+        settlement.amount -= taken;
+        if settlement.amount == 0 {
+            settlement.status = SettlementStatus::Returned;
+        }
+        storage::write_settlement(env, &settlement);
+
+        taken
+    }
+
+    /// Close a loan lost by the merchant in dispute, releasing the borrower from the balance
+    /// The merchant owes the financed principal not refunded yet: it is taken from the
+    /// disbursement still held for the merchant, then from the merchant's bond. The funds
+    /// repay the pool's outstanding principal first; the rest (principal the borrower already
+    /// repaid) and the guarantee go back to the borrower. Only principal the merchant cannot
+    /// cover is written off as a pool loss
+    fn refund_disputed_loan(env: &Env, mut loan: Loan) -> Result<(), CreditLineError> {
+        let token = storage::get_token(env).unwrap_or_else(|| panic!("Token not configured"));
+        let lp_address =
+            storage::get_liquidity_pool(env).ok_or(CreditLineError::InsufficientLiquidity)?;
+
+        let refundable = loan
+            .financed_amount
+            .checked_sub(loan.refunded_amount)
+            .ok_or(CreditLineError::Underflow)?;
+        let from_held = Self::take_held_settlement(env, loan.loan_id, refundable);
+        let from_bond = Self::claim_merchant_bond(env, &loan.merchant, refundable - from_held);
+        let refunded = from_held
+            .checked_add(from_bond)
+            .ok_or(CreditLineError::Overflow)?;
+
+        let to_pool = refunded.min(loan.outstanding_principal);
+        let from = env.current_contract_address();
+        Self::return_to_pool(env, &token, &lp_address, &from, &mut loan, to_pool)?;
+
+        if loan.outstanding_principal > 0 {
+            let write_off = WriteOff {
+                loan_id: loan.loan_id,
+                principal_written_off: loan.outstanding_principal,
+                guarantee_forfeited: 0,
+                recovered_amount: 0,
+                written_off_at: env.ledger().timestamp(),
+            };
             env.invoke_contract::<()>(
-                &reputation_contract,
-                &symbol_short!("slash"),
-                (loan.borrower,).into_val(&env),
+                &lp_address,
+                &Symbol::new(env, "recognize_loss"),
+                (loan.loan_id, write_off.principal_written_off).into_val(env),
             );
+            loan.outstanding_principal = 0;
+            storage::write_write_off(env, &write_off);
+            events::emit_loan_written_off(env, &loan.borrower, &write_off);
+        }
+
+        let to_borrower = (refunded - to_pool)
+            .checked_add(loan.guarantee_amount)
+            .ok_or(CreditLineError::Overflow)?;
+
+        loan.guarantee_amount = 0;
+        loan.remaining_balance = 0;
+        loan.refunded_amount = loan
+            .refunded_amount
+            .checked_add(refunded)
+            .ok_or(CreditLineError::Overflow)?;
+        loan.status = LoanStatus::Refunded;
+        storage::write_loan(env, &loan);
+
+        if to_borrower > 0 {
+            token::Client::new(env, &token).transfer(&from, &loan.borrower, &to_borrower);
         }
 
         Ok(())
     }
+
+    /// Claim up to `amount` of a merchant's bond from the merchant registry, if configured
+    /// Returns the amount received
+    fn claim_merchant_bond(env: &Env, merchant: &Address, amount: i128) -> i128 {
+        if amount <= 0 {
+            return 0;
+        }
+        match storage::get_merchant_registry(env) {
+            Some(merchant_registry) => env.invoke_contract::<i128>(
+                &merchant_registry,
+                &Symbol::new(env, "claim_bond"),
+                (env.current_contract_address(), merchant.clone(), amount).into_val(env),
+            ),
+            None => 0,
+        }
+    }
+
+    /// Report a loan outcome for a merchant to the merchant registry, if configured
+    fn report_merchant_outcome(env: &Env, function: &str, merchant: &Address, amount: i128) {
+        if let Some(merchant_registry) = storage::get_merchant_registry(env) {
+            env.invoke_contract::<()>(
+                &merchant_registry,
+                &Symbol::new(env, function),
+                (env.current_contract_address(), merchant.clone(), amount).into_val(env),
+            );
+        }
+    }
+
+    /// Report a refund for a merchant to the merchant registry, if configured
+    fn report_merchant_refund(env: &Env, merchant: &Address, amount: i128, first_refund: bool) {
+        if let Some(merchant_registry) = storage::get_merchant_registry(env) {
+            env.invoke_contract::<()>(
+                &merchant_registry,
+                &Symbol::new(env, "record_loan_refunded"),
+                (
+                    env.current_contract_address(),
+                    merchant.clone(),
+                    amount,
+                    first_refund,
+                )
+                    .into_val(env),
+            );
+        }
+    }
+
+    /// Split `total_amount` into equal installments, putting the rounding remainder in the
+    /// last one
+    fn build_schedule(
+        env: &Env,
+        total_amount: i128,
+        terms: &LoanTerms,
+    ) -> Result<Vec<RepaymentInstallment>, CreditLineError> {
+        let installments = terms.installments;
+        let period_seconds = terms.period_seconds;
+
+        if total_amount <= 0 {
+            return Err(CreditLineError::InvalidAmount);
+        }
+        if installments == 0
+            || installments > types::MAX_INSTALLMENTS
+            || (installments > 1 && period_seconds == 0)
+            || terms.first_due <= env.ledger().timestamp()
+        {
+            return Err(CreditLineError::InvalidTerms);
+        }
+
+        let amount = total_amount / installments as i128;
+        if amount == 0 {
+            return Err(CreditLineError::InvalidTerms);
+        }
+        let remainder = total_amount % installments as i128;
+
+        let mut schedule = Vec::new(env);
+        let mut due_date = terms.first_due;
+        for index in 0..installments {
+            if index > 0 {
+                due_date = due_date
+                    .checked_add(period_seconds)
+                    .ok_or(CreditLineError::Overflow)?;
+            }
+            let amount = if index == installments - 1 {
+                amount + remainder
+            } else {
+                amount
+            };
+            schedule.push_back(RepaymentInstallment { due_date, amount });
+        }
+
+        Ok(schedule)
+    }
+
+    /// Scale every installment by numerator / denominator
+    /// Rounding remainders are kept on the last installment so the schedule total scales exactly
+    fn scale_schedule(
+        env: &Env,
+        schedule: &Vec<RepaymentInstallment>,
+        numerator: i128,
+        denominator: i128,
+    ) -> Result<Vec<RepaymentInstallment>, CreditLineError> {
+        let mut schedule_total: i128 = 0;
+        for installment in schedule.iter() {
+            schedule_total = schedule_total
+                .checked_add(installment.amount)
+                .ok_or(CreditLineError::Overflow)?;
+        }
+
+        let target_total = schedule_total
+            .checked_mul(numerator)
+            .and_then(|v| v.checked_div(denominator))
+            .ok_or(CreditLineError::Overflow)?;
+
+        let mut scaled = Vec::new(env);
+        let mut scaled_total: i128 = 0;
+        let last_index = schedule.len().saturating_sub(1);
+
+        for (index, installment) in schedule.iter().enumerate() {
+            let amount = if index as u32 == last_index {
+                target_total
+                    .checked_sub(scaled_total)
+                    .ok_or(CreditLineError::Underflow)?
+            } else {
+                installment
+                    .amount
+                    .checked_mul(numerator)
+                    .and_then(|v| v.checked_div(denominator))
+                    .ok_or(CreditLineError::Overflow)?
+            };
+
+            scaled_total = scaled_total
+                .checked_add(amount)
+                .ok_or(CreditLineError::Overflow)?;
+            scaled.push_back(RepaymentInstallment {
+                due_date: installment.due_date,
+                amount,
+            });
+        }
+
+        Ok(scaled)
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,10 @@
-use soroban_sdk::{symbol_short, Address, Env, Map, Symbol};
+use soroban_sdk::{symbol_short, Address, Env, Map, String, Symbol, Vec};
 
-use crate::types::Loan;
+use crate::types::{
+    Dispute, FeeSplit, Loan, LoanRequest, Restructuring, Settlement, WriteOff,
+    DEFAULT_DELIVERY_WINDOW_SECONDS, DEFAULT_INCENTIVE_FEE_BPS, DEFAULT_POOL_FEE_BPS,
+    DEFAULT_REQUEST_TTL_SECONDS, DEFAULT_TREASURY_FEE_BPS,
+};
 
 // Storage keys
 pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
@@ -9,6 +13,23 @@ pub const LOANS_MAP: Symbol = symbol_short!("LOANS");
 pub const REPUTATION_CONTRACT: Symbol = symbol_short!("REPCONT");
 pub const MERCHANT_REGISTRY: Symbol = symbol_short!("MERCHANT");
 pub const LIQUIDITY_POOL: Symbol = symbol_short!("LIQPOOL");
+pub const FEE_SPLIT: Symbol = symbol_short!("FEESPLIT");
+pub const TOKEN: Symbol = symbol_short!("TOKEN");
+pub const WRITE_OFFS: Symbol = symbol_short!("WRITEOFF");
+pub const ESCROW_ADAPTER: Symbol = symbol_short!("ESCADPT");
+pub const DELIVERY_WINDOW: Symbol = symbol_short!("DLVWIN");
+pub const SETTLEMENTS: Symbol = symbol_short!("SETTLES");
+pub const ARBITER: Symbol = symbol_short!("ARBITER");
+pub const DISPUTES: Symbol = symbol_short!("DISPUTES");
+pub const REQUEST_TTL: Symbol = symbol_short!("REQTTL");
+pub const LOAN_REQUESTS: Symbol = symbol_short!("LOANREQS");
+pub const FINANCED_ORDERS: Symbol = symbol_short!("ORDERS");
+pub const RISK_MANAGER: Symbol = symbol_short!("RISKMGR");
+pub const RESTRUCTURINGS: Symbol = symbol_short!("RESTRUCT");
+pub const RECOVERY_RESTORE: Symbol = symbol_short!("RCVREST");
+pub const ALLOWED_PAYERS: Symbol = symbol_short!("PAYERS");
+pub const TREASURY: Symbol = symbol_short!("TREASURY");
+pub const INCENTIVE_FUND: Symbol = symbol_short!("INCFUND");
 
 /// Get the admin address from storage
 pub fn get_admin(env: &Env) -> Address {
@@ -88,3 +109,288 @@ pub fn get_liquidity_pool(env: &Env) -> Option<Address> {
 pub fn set_liquidity_pool(env: &Env, address: &Address) {
     env.storage().instance().set(&LIQUIDITY_POOL, address);
 }
+
+/// Get the escrow adapter that holds merchant disbursements, if configured
+pub fn get_escrow_adapter(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ESCROW_ADAPTER)
+}
+
+/// Set the escrow adapter that holds merchant disbursements
+pub fn set_escrow_adapter(env: &Env, address: &Address) {
+    env.storage().instance().set(&ESCROW_ADAPTER, address);
+}
+
+/// Get the settlement token address
+pub fn get_token(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&TOKEN)
+}
+
+/// Set the settlement token address
+pub fn set_token(env: &Env, address: &Address) {
+    env.storage().instance().set(&TOKEN, address);
+}
+
+/// Get the merchant fee split, falling back to the default split
+pub fn get_fee_split(env: &Env) -> FeeSplit {
+    env.storage()
+        .instance()
+        .get(&FEE_SPLIT)
+        .unwrap_or(FeeSplit {
+            liquidity_pool_bps: DEFAULT_POOL_FEE_BPS,
+            treasury_bps: DEFAULT_TREASURY_FEE_BPS,
+            incentive_fund_bps: DEFAULT_INCENTIVE_FEE_BPS,
+        })
+}
+
+/// Set the merchant fee split
+pub fn set_fee_split(env: &Env, split: &FeeSplit) {
+    env.storage().instance().set(&FEE_SPLIT, split);
+}
+
+/// Get the treasury receiving its share of merchant fees, if configured
+pub fn get_treasury(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&TREASURY)
+}
+
+/// Set the treasury receiving its share of merchant fees
+pub fn set_treasury(env: &Env, address: &Address) {
+    env.storage().instance().set(&TREASURY, address);
+}
+
+/// Get the incentive fund receiving its share of merchant fees, if configured
+pub fn get_incentive_fund(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&INCENTIVE_FUND)
+}
+
+/// Set the incentive fund receiving its share of merchant fees
+pub fn set_incentive_fund(env: &Env, address: &Address) {
+    env.storage().instance().set(&INCENTIVE_FUND, address);
+}
+
+/// Read the write-off of a defaulted loan
+pub fn read_write_off(env: &Env, loan_id: u64) -> Option<WriteOff> {
+    let write_offs: Map<u64, WriteOff> = env
+        .storage()
+        .instance()
+        .get(&WRITE_OFFS)
+        .unwrap_or_else(|| Map::new(env));
+
+    write_offs.get(loan_id)
+}
+
+/// Write the write-off of a defaulted loan
+pub fn write_write_off(env: &Env, write_off: &WriteOff) {
+    let mut write_offs: Map<u64, WriteOff> = env
+        .storage()
+        .instance()
+        .get(&WRITE_OFFS)
+        .unwrap_or_else(|| Map::new(env));
+
+    write_offs.set(write_off.loan_id, write_off.clone());
+    env.storage().instance().set(&WRITE_OFFS, &write_offs);
+}
+
+/// Get how long a disbursement is held before it can be released without confirmation
+pub fn get_delivery_window(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&DELIVERY_WINDOW)
+        .unwrap_or(DEFAULT_DELIVERY_WINDOW_SECONDS)
+}
+
+/// Set how long a disbursement is held before it can be released without confirmation
+pub fn set_delivery_window(env: &Env, seconds: u64) {
+    env.storage().instance().set(&DELIVERY_WINDOW, &seconds);
+}
+
+/// Read the settlement of a loan's merchant disbursement
+pub fn read_settlement(env: &Env, loan_id: u64) -> Option<Settlement> {
+    let settlements: Map<u64, Settlement> = env
+        .storage()
+        .instance()
+        .get(&SETTLEMENTS)
+        .unwrap_or_else(|| Map::new(env));
+
+    settlements.get(loan_id)
+}
+
+/// Write the settlement of a loan's merchant disbursement
+pub fn write_settlement(env: &Env, settlement: &Settlement) {
+    let mut settlements: Map<u64, Settlement> = env
+        .storage()
+        .instance()
+        .get(&SETTLEMENTS)
+        .unwrap_or_else(|| Map::new(env));
+
+    settlements.set(settlement.loan_id, settlement.clone());
+    env.storage().instance().set(&SETTLEMENTS, &settlements);
+}
+
+/// Get the arbiter resolving borrower–merchant disputes
+pub fn get_arbiter(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&ARBITER)
+}
+
+/// Set the arbiter resolving borrower–merchant disputes
+pub fn set_arbiter(env: &Env, arbiter: &Address) {
+    env.storage().instance().set(&ARBITER, arbiter);
+}
+
+/// Read the dispute opened on a loan
+pub fn read_dispute(env: &Env, loan_id: u64) -> Option<Dispute> {
+    let disputes: Map<u64, Dispute> = env
+        .storage()
+        .instance()
+        .get(&DISPUTES)
+        .unwrap_or_else(|| Map::new(env));
+
+    disputes.get(loan_id)
+}
+
+/// Write the dispute opened on a loan
+pub fn write_dispute(env: &Env, dispute: &Dispute) {
+    let mut disputes: Map<u64, Dispute> = env
+        .storage()
+        .instance()
+        .get(&DISPUTES)
+        .unwrap_or_else(|| Map::new(env));
+
+    disputes.set(dispute.loan_id, dispute.clone());
+    env.storage().instance().set(&DISPUTES, &disputes);
+}
+
+/// Get how long a loan request can wait for the merchant's acceptance
+pub fn get_request_ttl(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&REQUEST_TTL)
+        .unwrap_or(DEFAULT_REQUEST_TTL_SECONDS)
+}
+
+/// Set how long a loan request can wait for the merchant's acceptance
+pub fn set_request_ttl(env: &Env, seconds: u64) {
+    env.storage().instance().set(&REQUEST_TTL, &seconds);
+}
+
+/// Read the request a loan was opened from
+pub fn read_loan_request(env: &Env, loan_id: u64) -> Option<LoanRequest> {
+    let requests: Map<u64, LoanRequest> = env
+        .storage()
+        .instance()
+        .get(&LOAN_REQUESTS)
+        .unwrap_or_else(|| Map::new(env));
+
+    requests.get(loan_id)
+}
+
+/// Write the request a loan was opened from
+pub fn write_loan_request(env: &Env, request: &LoanRequest) {
+    let mut requests: Map<u64, LoanRequest> = env
+        .storage()
+        .instance()
+        .get(&LOAN_REQUESTS)
+        .unwrap_or_else(|| Map::new(env));
+
+    requests.set(request.loan_id, request.clone());
+    env.storage().instance().set(&LOAN_REQUESTS, &requests);
+}
+
+/// Get the loan financing a merchant's order, if any
+pub fn get_order_loan(env: &Env, merchant: &Address, order_id: &String) -> Option<u64> {
+    let orders: Map<(Address, String), u64> = env
+        .storage()
+        .instance()
+        .get(&FINANCED_ORDERS)
+        .unwrap_or_else(|| Map::new(env));
+
+    orders.get((merchant.clone(), order_id.clone()))
+}
+
+/// Record the loan financing a merchant's order
+pub fn set_order_loan(env: &Env, merchant: &Address, order_id: &String, loan_id: u64) {
+    let mut orders: Map<(Address, String), u64> = env
+        .storage()
+        .instance()
+        .get(&FINANCED_ORDERS)
+        .unwrap_or_else(|| Map::new(env));
+
+    orders.set((merchant.clone(), order_id.clone()), loan_id);
+    env.storage().instance().set(&FINANCED_ORDERS, &orders);
+}
+
+/// Get the risk manager allowed to restructure loans alongside the admin
+pub fn get_risk_manager(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&RISK_MANAGER)
+}
+
+/// Set the risk manager allowed to restructure loans alongside the admin
+pub fn set_risk_manager(env: &Env, risk_manager: &Address) {
+    env.storage().instance().set(&RISK_MANAGER, risk_manager);
+}
+
+/// Read the restructurings of a loan, oldest first
+pub fn read_restructurings(env: &Env, loan_id: u64) -> Vec<Restructuring> {
+    let restructurings: Map<u64, Vec<Restructuring>> = env
+        .storage()
+        .instance()
+        .get(&RESTRUCTURINGS)
+        .unwrap_or_else(|| Map::new(env));
+
+    restructurings.get(loan_id).unwrap_or_else(|| Vec::new(env))
+}
+
+/// Append a restructuring to a loan's audit trail
+pub fn add_restructuring(env: &Env, restructuring: &Restructuring) {
+    let mut restructurings: Map<u64, Vec<Restructuring>> = env
+        .storage()
+        .instance()
+        .get(&RESTRUCTURINGS)
+        .unwrap_or_else(|| Map::new(env));
+
+    let mut history = restructurings
+        .get(restructuring.loan_id)
+        .unwrap_or_else(|| Vec::new(env));
+    history.push_back(restructuring.clone());
+    restructurings.set(restructuring.loan_id, history);
+    env.storage()
+        .instance()
+        .set(&RESTRUCTURINGS, &restructurings);
+}
+
+/// Get the reputation points restored to a borrower whose defaulted loan is fully recovered
+pub fn get_recovery_restore(env: &Env) -> u32 {
+    env.storage().instance().get(&RECOVERY_RESTORE).unwrap_or(0)
+}
+
+/// Set the reputation points restored to a borrower whose defaulted loan is fully recovered
+pub fn set_recovery_restore(env: &Env, points: u32) {
+    env.storage().instance().set(&RECOVERY_RESTORE, &points);
+}
+
+/// Read the payers a borrower allows to repay on their behalf, None when anyone may pay
+pub fn read_allowed_payers(env: &Env, borrower: &Address) -> Option<Vec<Address>> {
+    let allowlists: Map<Address, Vec<Address>> = env
+        .storage()
+        .instance()
+        .get(&ALLOWED_PAYERS)
+        .unwrap_or_else(|| Map::new(env));
+
+    allowlists.get(borrower.clone())
+}
+
+/// Write a borrower's payer allowlist, None to let anyone pay
+pub fn write_allowed_payers(env: &Env, borrower: &Address, payers: Option<Vec<Address>>) {
+    let mut allowlists: Map<Address, Vec<Address>> = env
+        .storage()
+        .instance()
+        .get(&ALLOWED_PAYERS)
+        .unwrap_or_else(|| Map::new(env));
+
+    match payers {
+        Some(payers) => allowlists.set(borrower.clone(), payers),
+        None => {
+            allowlists.remove(borrower.clone());
+        }
+    }
+    env.storage().instance().set(&ALLOWED_PAYERS, &allowlists);
+}
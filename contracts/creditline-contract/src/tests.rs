@@ -1,8 +1,20 @@
-use crate::{CreditLineContract, CreditLineContractClient, LoanStatus, RepaymentInstallment};
+use crate::{
+    types::{
+        DEFAULT_DELIVERY_WINDOW_SECONDS, DEFAULT_QUOTE_INSTALLMENTS, DEFAULT_QUOTE_PERIOD_SECONDS,
+        DEFAULT_REPUTATION_PENALTY, DEFAULT_REQUEST_TTL_SECONDS,
+        DISPUTE_RESOLUTION_TIMEOUT_SECONDS, DISPUTE_WINDOW_SECONDS, MAX_INSTALLMENTS,
+        REPAYMENT_REPUTATION_REWARD, RESTRUCTURE_REPUTATION_PENALTY, SECONDS_PER_YEAR,
+    },
+    CreditLineContract, CreditLineContractClient, CreditLineError, DisputeOutcome, FeeSplit,
+    LoanStatus, LoanTerms, MerchantFee, Order, RepaymentInstallment, SettlementStatus,
+};
+use ed25519_dalek::{Signer, SigningKey};
 use soroban_sdk::{
-    contract, contractimpl,
-    testutils::{Address as _, Ledger},
-    Address, Env,
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
 };
 
 // NOTE: Integration tests with reputation contract are skipped for now
@@ -12,11 +24,295 @@ pub struct MockReputation;
 
 #[contractimpl]
 impl MockReputation {
-    pub fn get_score(_env: Env, _user: Address) -> u32 {
-        100 // Returns 100 to pass the threshold check
+    pub fn set_score(env: Env, score: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SCORE"), &score);
+    }
+    pub fn get_score(env: Env, _user: Address) -> u32 {
+        // Defaults to 100 to pass the threshold check
+        env.storage()
+            .instance()
+            .get(&symbol_short!("SCORE"))
+            .unwrap_or(100)
+    }
+    pub fn decrease_score(env: Env, _updater: Address, user: Address, amount: u32) {
+        let score = Self::get_score(env.clone(), user);
+        Self::set_score(env.clone(), score - amount);
+        env.storage().instance().set(
+            &symbol_short!("PENALTY"),
+            &(Self::penalty(env.clone()) + amount),
+        );
+    }
+    pub fn increase_score(env: Env, _updater: Address, user: Address, amount: u32) {
+        // Scores cannot exceed 100, as in the reputation contract
+        let score = Self::get_score(env.clone(), user) + amount;
+        assert!(score <= 100, "score above maximum");
+        Self::set_score(env.clone(), score);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RESTORED"), &amount);
+    }
+    pub fn restored(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("RESTORED"))
+            .unwrap_or(0)
+    }
+    pub fn penalty(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("PENALTY"))
+            .unwrap_or(0)
+    }
+}
+
+#[contract]
+pub struct MockMerchantRegistry;
+
+#[contractimpl]
+impl MockMerchantRegistry {
+    pub fn set_fee(env: Env, fee_rate_bps: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("FEE"), &fee_rate_bps);
+    }
+    pub fn set_active(env: Env, active: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("INACTIVE"), &!active);
+    }
+    pub fn is_active_merchant(env: Env, _merchant: Address) -> bool {
+        !env.storage()
+            .instance()
+            .get(&symbol_short!("INACTIVE"))
+            .unwrap_or(false)
+    }
+    pub fn get_merchant_fee(env: Env, _merchant: Address) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("FEE"))
+            .unwrap_or(0)
+    }
+    pub fn get_merchant_category(_env: Env, _merchant: Address) -> Symbol {
+        symbol_short!("general")
+    }
+    pub fn set_signing_key(env: Env, signing_key: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("SIGNKEY"), &signing_key);
+    }
+    pub fn get_signing_key(env: Env, _merchant: Address) -> Option<BytesN<32>> {
+        env.storage().instance().get(&symbol_short!("SIGNKEY"))
+    }
+    pub fn record_loan_originated(
+        env: Env,
+        _creditline: Address,
+        _merchant: Address,
+        amount: i128,
+    ) {
+        Self::record(&env, symbol_short!("ORIG"), amount);
+    }
+    pub fn record_loan_defaulted(env: Env, _creditline: Address, _merchant: Address, amount: i128) {
+        Self::record(&env, symbol_short!("DFLT"), amount);
+    }
+    pub fn record_loan_refunded(
+        env: Env,
+        _creditline: Address,
+        _merchant: Address,
+        amount: i128,
+        first_refund: bool,
+    ) {
+        Self::record(&env, symbol_short!("RFND"), amount);
+        if first_refund {
+            Self::record(&env, symbol_short!("RFNDLOAN"), 1);
+        }
+    }
+    pub fn set_bond(env: Env, token: Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("BOND"), &(token, amount));
+    }
+    pub fn claim_bond(env: Env, creditline: Address, _merchant: Address, amount: i128) -> i128 {
+        let (token, bond): (Address, i128) =
+            match env.storage().instance().get(&symbol_short!("BOND")) {
+                Some(bond) => bond,
+                None => return 0,
+            };
+        let claimed = amount.min(bond);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &creditline,
+            &claimed,
+        );
+        env.storage()
+            .instance()
+            .set(&symbol_short!("BOND"), &(token, bond - claimed));
+        claimed
+    }
+    pub fn recorded(env: Env, outcome: Symbol) -> i128 {
+        env.storage().instance().get(&outcome).unwrap_or(0)
+    }
+    fn record(env: &Env, outcome: Symbol, amount: i128) {
+        let total: i128 = env.storage().instance().get(&outcome).unwrap_or(0);
+        env.storage().instance().set(&outcome, &(total + amount));
+    }
+}
+
+#[contract]
+pub struct MockEscrowAdapter;
+
+#[contractimpl]
+impl MockEscrowAdapter {
+    pub fn init(env: Env, token: Address, creditline: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOKEN"), &token);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CREDITLN"), &creditline);
+    }
+    pub fn open_escrow(
+        env: Env,
+        loan_id: u64,
+        _borrower: Address,
+        merchant: Address,
+        milestone_amounts: Vec<i128>,
+    ) {
+        env.storage().instance().set(&loan_id, &milestone_amounts);
+        env.storage()
+            .instance()
+            .set(&(loan_id, symbol_short!("MERCHANT")), &merchant);
     }
-    pub fn slash(_env: Env, _user: Address) {
-        // Does nothing, just needs to exist for the call to succeed
+    pub fn milestones(env: Env, loan_id: u64) -> Vec<i128> {
+        env.storage().instance().get(&loan_id).unwrap()
+    }
+    pub fn release_escrow(env: Env, loan_id: u64) -> i128 {
+        let merchant: Address = env
+            .storage()
+            .instance()
+            .get(&(loan_id, symbol_short!("MERCHANT")))
+            .unwrap();
+        Self::pay_out(&env, loan_id, &merchant)
+    }
+    pub fn refund_escrow(env: Env, loan_id: u64) -> i128 {
+        let creditline: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("CREDITLN"))
+            .unwrap();
+        Self::pay_out(&env, loan_id, &creditline)
+    }
+    fn pay_out(env: &Env, loan_id: u64, to: &Address) -> i128 {
+        let amount: i128 = Self::milestones(env.clone(), loan_id).iter().sum();
+        env.storage()
+            .instance()
+            .set(&loan_id, &Vec::<i128>::new(env));
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOKEN"))
+            .unwrap();
+        if amount > 0 {
+            token::Client::new(env, &token).transfer(&env.current_contract_address(), to, &amount);
+        }
+        amount
+    }
+}
+
+#[contract]
+pub struct MockLiquidityPool;
+
+#[contractimpl]
+impl MockLiquidityPool {
+    pub fn set_token(env: Env, token: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOKEN"), &token);
+    }
+    pub fn set_rate(env: Env, rate_bps: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("RATE"), &rate_bps);
+    }
+    pub fn get_current_rate(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("RATE"))
+            .unwrap_or(0)
+    }
+    pub fn get_available_liquidity(env: Env) -> i128 {
+        token::Client::new(&env, &Self::token(&env)).balance(&env.current_contract_address())
+    }
+    pub fn set_creditline(env: Env, creditline: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("CREDITLN"), &creditline);
+    }
+    pub fn set_max_exposure(env: Env, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("MAXEXPOS"), &amount);
+    }
+    pub fn check_concentration(
+        env: Env,
+        _borrower: Address,
+        _merchant: Address,
+        _category: Symbol,
+        amount: i128,
+    ) -> bool {
+        let max_exposure: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("MAXEXPOS"))
+            .unwrap_or(i128::MAX);
+        Self::tracked(env, symbol_short!("FUNDED")) + amount <= max_exposure
+    }
+    pub fn fund_loan(
+        env: Env,
+        _loan_id: u64,
+        amount: i128,
+        borrower: Address,
+        merchant: Address,
+        category: Symbol,
+    ) {
+        if !Self::check_concentration(env.clone(), borrower, merchant, category, amount) {
+            panic!("concentration limit exceeded");
+        }
+        let creditline: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("CREDITLN"))
+            .unwrap();
+        token::Client::new(&env, &Self::token(&env)).transfer(
+            &env.current_contract_address(),
+            &creditline,
+            &amount,
+        );
+        Self::record(&env, symbol_short!("FUNDED"), amount);
+    }
+    pub fn receive_repayment(env: Env, _loan_id: u64, principal: i128, income: i128) {
+        Self::record(&env, symbol_short!("PRINC"), principal);
+        Self::record(&env, symbol_short!("INCOME"), income);
+    }
+    pub fn recognize_loss(env: Env, _loan_id: u64, amount: i128) {
+        Self::record(&env, symbol_short!("LOSS"), amount);
+    }
+    pub fn receive_recovery(env: Env, amount: i128) {
+        Self::record(&env, symbol_short!("RECOVERY"), amount);
+    }
+    pub fn tracked(env: Env, key: Symbol) -> i128 {
+        env.storage().instance().get(&key).unwrap_or(0)
+    }
+    fn token(env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("TOKEN"))
+            .unwrap()
+    }
+    fn record(env: &Env, key: Symbol, amount: i128) {
+        let total: i128 = env.storage().instance().get(&key).unwrap_or(0);
+        env.storage().instance().set(&key, &(total + amount));
     }
 }
 
@@ -214,6 +510,23 @@ fn test_set_liquidity_pool() {
     // Verify it was updated (we can't directly query, but no panic means success)
 }
 
+/// Assert that the last event of the latest invocation has the given type
+fn assert_last_event(env: &Env, event_type: Symbol) {
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let last_type: Symbol = last.1.get(0).unwrap().into_val(env);
+    assert_eq!(last_type, event_type);
+}
+
+fn order(env: &Env, order_id: &str) -> Order {
+    Order {
+        order_id: String::from_str(env, order_id),
+        invoice_hash: BytesN::from_array(env, &[1; 32]),
+        expires_at: 0,
+        signature: None,
+    }
+}
+
 // Tests for validate_guarantee logic (tested indirectly through create_loan)
 
 #[test]
@@ -242,7 +555,14 @@ fn test_create_loan_with_zero_total_amount() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // This should panic with InvalidAmount (error code 9)
-    client.create_loan(&user, &merchant, &0, &0, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &0,
+        &0,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -271,7 +591,14 @@ fn test_create_loan_with_negative_total_amount() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // This should panic with InvalidAmount (error code 9)
-    client.create_loan(&user, &merchant, &-1000, &-200, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &-1000,
+        &-200,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -300,7 +627,14 @@ fn test_create_loan_with_zero_guarantee_amount() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // This should panic with InvalidAmount (error code 9)
-    client.create_loan(&user, &merchant, &1000, &0, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &0,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -329,7 +663,14 @@ fn test_create_loan_with_insufficient_guarantee_19_percent() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // 190 is 19% of 1000, should fail with InsufficientGuarantee (error code 2)
-    client.create_loan(&user, &merchant, &1000, &190, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &190,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -358,7 +699,14 @@ fn test_create_loan_with_insufficient_guarantee_10_percent() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // 100 is 10% of 1000, should fail with InsufficientGuarantee (error code 2)
-    client.create_loan(&user, &merchant, &1000, &100, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &100,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 // Additional edge case tests
@@ -441,7 +789,14 @@ fn test_create_loan_with_one_less_than_minimum_guarantee() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // 199 is 1 less than 20% of 1000, should fail with InsufficientGuarantee (error code 2)
-    client.create_loan(&user, &merchant, &1000, &199, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &199,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -499,7 +854,14 @@ fn test_create_loan_with_positive_total_negative_guarantee() {
     let repayment_schedule = soroban_sdk::Vec::new(&env);
 
     // Positive total but negative guarantee should fail with InvalidAmount (error code 9)
-    client.create_loan(&user, &merchant, &1000, &-200, &repayment_schedule);
+    client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &-200,
+        &repayment_schedule,
+        &order(&env, "ORDER-1"),
+    );
 }
 
 #[test]
@@ -510,8 +872,9 @@ fn test_mark_defaulted_success() {
     let contract_id = env.register(CreditLineContract, ());
     let client = CreditLineContractClient::new(&env, &contract_id);
 
-    // Register our Mock Reputation contract
+    // Register our Mock Reputation and Merchant Registry contracts
     let rep_id = env.register(MockReputation, ());
+    let registry_id = env.register(MockMerchantRegistry, ());
 
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
@@ -521,7 +884,7 @@ fn test_mark_defaulted_success() {
     client.initialize(
         &admin,
         &rep_id, // Pass the Mock ID
-        &Address::generate(&env),
+        &registry_id,
         &liquidity_pool,
     );
 
@@ -536,16 +899,26 @@ fn test_mark_defaulted_success() {
     });
 
     // Create loan (calls MockReputation::get_score)
-    let loan_id = client.create_loan(&user, &merchant, &1000, &200, &schedule);
+    let loan_id = client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
 
     // Time Travel past the due date
     env.ledger().set_timestamp(12000);
 
-    // This calls mark_defaulted which internally calls MockReputation::slash
     client.mark_defaulted(&loan_id);
 
     let updated_loan = client.get_loan(&loan_id);
     assert_eq!(updated_loan.status, LoanStatus::Defaulted);
+    assert_eq!(
+        MockReputationClient::new(&env, &rep_id).penalty(),
+        DEFAULT_REPUTATION_PENALTY
+    );
 }
 
 #[test]
@@ -558,16 +931,12 @@ fn test_mark_defaulted_too_early_fails() {
     let client = CreditLineContractClient::new(&env, &contract_id);
 
     let rep_id = env.register(MockReputation, ());
+    let registry_id = env.register(MockMerchantRegistry, ());
 
     let admin = Address::generate(&env);
     let user = Address::generate(&env);
 
-    client.initialize(
-        &admin,
-        &rep_id,
-        &Address::generate(&env),
-        &Address::generate(&env),
-    );
+    client.initialize(&admin, &rep_id, &registry_id, &Address::generate(&env));
 
     let current_time = 10000;
     env.ledger().set_timestamp(current_time);
@@ -578,8 +947,2914 @@ fn test_mark_defaulted_too_early_fails() {
         due_date: 20000,
     });
 
-    let loan_id = client.create_loan(&user, &Address::generate(&env), &1000, &200, &schedule);
+    let loan_id = client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
 
     // This should fail because 10000 < 20000
     client.mark_defaulted(&loan_id);
 }
+
+// Merchant fee tests
+
+fn setup_with_merchant_registry(env: &Env) -> (CreditLineContractClient<'_>, Address, Address) {
+    let contract_id = env.register(CreditLineContract, ());
+    let client = CreditLineContractClient::new(env, &contract_id);
+
+    let rep_id = env.register(MockReputation, ());
+    let registry_id = env.register(MockMerchantRegistry, ());
+
+    let admin = Address::generate(env);
+    client.initialize(&admin, &rep_id, &registry_id, &Address::generate(env));
+
+    (client, admin, registry_id)
+}
+
+fn single_installment(env: &Env, amount: i128, due_date: u64) -> Vec<RepaymentInstallment> {
+    let mut schedule = Vec::new(env);
+    schedule.push_back(RepaymentInstallment { amount, due_date });
+    schedule
+}
+
+#[test]
+fn test_create_loan_applies_merchant_fee_with_default_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, registry_id) = setup_with_merchant_registry(&env);
+    MockMerchantRegistryClient::new(&env, &registry_id).set_fee(&300);
+
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let schedule = single_installment(&env, 1000, 1000);
+
+    let loan_id = client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
+
+    // 3% of 1000 = 30, split 85/10/5 with the pool taking the rounding remainder
+    let loan = client.get_loan(&loan_id);
+    assert_eq!(
+        loan.merchant_fee,
+        MerchantFee {
+            total: 30,
+            liquidity_pool: 26,
+            treasury: 3,
+            incentive_fund: 1,
+        }
+    );
+}
+
+#[test]
+fn test_create_loan_uses_custom_fee_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, registry_id) = setup_with_merchant_registry(&env);
+    MockMerchantRegistryClient::new(&env, &registry_id).set_fee(&500);
+
+    client.set_fee_split(
+        &admin,
+        &FeeSplit {
+            liquidity_pool_bps: 5_000,
+            treasury_bps: 3_000,
+            incentive_fund_bps: 2_000,
+        },
+    );
+
+    let schedule = single_installment(&env, 10_000, 1000);
+    let loan_id = client.create_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &10_000,
+        &2_000,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
+
+    let fee = client.get_loan(&loan_id).merchant_fee;
+    assert_eq!(fee.total, 500);
+    assert_eq!(fee.liquidity_pool, 250);
+    assert_eq!(fee.treasury, 150);
+    assert_eq!(fee.incentive_fund, 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_set_fee_split_not_summing_to_100_percent_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _registry_id) = setup_with_merchant_registry(&env);
+
+    client.set_fee_split(
+        &admin,
+        &FeeSplit {
+            liquidity_pool_bps: 8_000,
+            treasury_bps: 1_000,
+            incentive_fund_bps: 500,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_create_loan_with_inactive_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, registry_id) = setup_with_merchant_registry(&env);
+    MockMerchantRegistryClient::new(&env, &registry_id).set_active(&false);
+
+    let schedule = single_installment(&env, 1000, 1000);
+    client.create_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
+}
+
+#[test]
+fn test_loan_created_event_includes_merchant_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, registry_id) = setup_with_merchant_registry(&env);
+    MockMerchantRegistryClient::new(&env, &registry_id).set_fee(&200);
+
+    let schedule = single_installment(&env, 1000, 1000);
+    client.create_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let event = events
+        .iter()
+        .find(|event| {
+            let event_type: Symbol = event.1.get(0).unwrap().into_val(&env);
+            event_type == symbol_short!("LOANCRTD")
+        })
+        .expect("LOANCRTD event not found");
+
+    let data: (u64, i128, i128, Vec<RepaymentInstallment>, MerchantFee) = event.2.into_val(&env);
+    assert_eq!(data.4.total, 20);
+    assert_eq!(
+        data.4.liquidity_pool + data.4.treasury + data.4.incentive_fund,
+        20
+    );
+}
+
+// Refund tests
+
+const POOL_LIQUIDITY: i128 = 10_000;
+
+struct TokenSetup<'a> {
+    client: CreditLineContractClient<'a>,
+    token: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+    liquidity_pool: Address,
+    pool: MockLiquidityPoolClient<'a>,
+    registry: MockMerchantRegistryClient<'a>,
+}
+
+fn setup_with_token(env: &Env) -> TokenSetup<'_> {
+    let (client, admin, registry_id) = setup_with_merchant_registry(env);
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    client.set_token(&admin, &token_id);
+
+    let liquidity_pool = env.register(MockLiquidityPool, ());
+    let pool = MockLiquidityPoolClient::new(env, &liquidity_pool);
+    pool.set_token(&token_id);
+    pool.set_creditline(&client.address);
+    client.set_liquidity_pool(&admin, &liquidity_pool);
+
+    let token_admin = token::StellarAssetClient::new(env, &token_id);
+    token_admin.mint(&liquidity_pool, &POOL_LIQUIDITY);
+
+    TokenSetup {
+        client,
+        token: token::Client::new(env, &token_id),
+        token_admin,
+        liquidity_pool,
+        pool,
+        registry: MockMerchantRegistryClient::new(env, &registry_id),
+    }
+}
+
+fn two_installments(env: &Env) -> Vec<RepaymentInstallment> {
+    let mut schedule = Vec::new(env);
+    schedule.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: 1000,
+    });
+    schedule.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: 2000,
+    });
+    schedule
+}
+
+/// Loan created by `setup_loan`
+struct LoanSetup {
+    user: Address,
+    merchant: Address,
+    loan_id: u64,
+}
+
+/// Create a loan of 1,000 with a 200 guarantee over `two_installments` and bring it to `status`
+/// Active loans keep the disbursement held for the merchant; Defaulted loans have it
+/// released before defaulting; Disputed loans are contested at 500 with an arbiter set
+fn setup_loan(env: &Env, setup: &TokenSetup, status: LoanStatus) -> LoanSetup {
+    let user = Address::generate(env);
+    let merchant = Address::generate(env);
+    setup.token_admin.mint(&user, &200);
+
+    if status == LoanStatus::Pending {
+        let loan_id =
+            setup
+                .client
+                .request_loan(&user, &merchant, &1000, &200, &two_installments(env));
+        return LoanSetup {
+            user,
+            merchant,
+            loan_id,
+        };
+    }
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(env),
+        &order(env, "ORDER-1"),
+    );
+
+    match status {
+        LoanStatus::Active => {}
+        LoanStatus::Disputed => {
+            setup
+                .client
+                .set_arbiter(&setup.client.get_admin(), &Address::generate(env));
+            env.ledger().set_timestamp(500);
+            setup
+                .client
+                .open_dispute(&user, &loan_id, &BytesN::from_array(env, &[7; 32]));
+        }
+        LoanStatus::Defaulted => {
+            setup.client.confirm_delivery(&user, &loan_id);
+            env.ledger().set_timestamp(2001);
+            setup.client.mark_defaulted(&loan_id);
+        }
+        _ => panic!("unsupported loan status"),
+    }
+
+    LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    }
+}
+
+#[test]
+fn test_create_loan_escrows_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    // The guarantee is held alongside the disbursement awaiting delivery
+    assert_eq!(setup.token.balance(&user), 0);
+    assert_eq!(setup.token.balance(&setup.client.address), 200 + 1000);
+}
+
+#[test]
+fn test_partial_refund_reduces_balance_schedule_and_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+    setup.token_admin.mint(&merchant, &1000);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup.client.refund_loan(&merchant, &loan_id, &200);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.remaining_balance, 800);
+    assert_eq!(loan.repayment_schedule.get(0).unwrap().amount, 400);
+    assert_eq!(loan.repayment_schedule.get(1).unwrap().amount, 400);
+
+    // A fifth of the balance was refunded, so a fifth of the guarantee is released
+    assert_eq!(loan.guarantee_amount, 160);
+    assert_eq!(setup.token.balance(&user), 40);
+
+    // The refund is taken from the disbursement still held for the merchant
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(setup.client.get_settlement(&loan_id).unwrap().amount, 800);
+    assert_eq!(
+        setup.token.balance(&setup.liquidity_pool),
+        POOL_LIQUIDITY - 1000 + 200
+    );
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 200);
+}
+
+#[test]
+fn test_full_refund_closes_loan_and_returns_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+    setup.token_admin.mint(&merchant, &1000);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup.client.refund_loan(&merchant, &loan_id, &1000);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Refunded);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(loan.guarantee_amount, 0);
+    assert_eq!(setup.token.balance(&user), 200);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+}
+
+#[test]
+fn test_refund_is_measured_against_principal() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (_user, loan_id) = interest_loan(&env, &setup);
+    let merchant = setup.client.get_loan(&loan_id).merchant;
+    assert_eq!(setup.client.get_loan(&loan_id).remaining_balance, 1100);
+
+    // The balance includes interest, but only the 1,000 financed can be refunded
+    assert_eq!(
+        setup.client.try_refund_loan(&merchant, &loan_id, &1001),
+        Err(Ok(CreditLineError::InvalidAmount))
+    );
+    setup.client.refund_loan(&merchant, &loan_id, &1000);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Refunded);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 0);
+}
+
+#[test]
+fn test_full_refund_returns_earlier_repayments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.token_admin.mint(&user, &300);
+    setup.client.repay_loan(&user, &loan_id, &300);
+
+    setup.client.refund_loan(&merchant, &loan_id, &1000);
+
+    // The pool gets back the principal still owed, the borrower what they repaid
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Refunded);
+    assert_eq!(loan.outstanding_principal, 0);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 300 + 700);
+    assert_eq!(setup.token.balance(&user), 300 + 200);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+}
+
+#[test]
+fn test_partial_refunds_count_loan_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    setup.client.refund_loan(&merchant, &loan_id, &200);
+    setup.client.refund_loan(&merchant, &loan_id, &400);
+    assert_eq!(
+        setup.client.try_refund_loan(&merchant, &loan_id, &401),
+        Err(Ok(CreditLineError::InvalidAmount))
+    );
+    setup.client.refund_loan(&merchant, &loan_id, &400);
+
+    assert_eq!(setup.client.get_loan(&loan_id).status, LoanStatus::Refunded);
+    assert_eq!(setup.registry.recorded(&symbol_short!("RFND")), 1000);
+    assert_eq!(setup.registry.recorded(&symbol_short!("RFNDLOAN")), 1);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_refund_by_other_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup
+        .client
+        .refund_loan(&Address::generate(&env), &loan_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_refund_more_than_balance_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup.client.refund_loan(&merchant, &loan_id, &1001);
+}
+
+#[test]
+fn test_mark_defaulted_forfeits_guarantee_to_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+    setup.client.confirm_delivery(&user, &loan_id);
+
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+
+    assert_eq!(
+        setup.token.balance(&setup.liquidity_pool),
+        POOL_LIQUIDITY - 1000 + 200
+    );
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+
+    // The forfeited guarantee is returned to the pool as principal
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 200);
+
+    // The rest of the principal is written off as a pool loss
+    assert_eq!(setup.pool.tracked(&symbol_short!("LOSS")), 800);
+    assert_eq!(setup.client.get_loan(&loan_id).outstanding_principal, 0);
+
+    let write_off = setup.client.get_write_off(&loan_id).unwrap();
+    assert_eq!(write_off.principal_written_off, 800);
+    assert_eq!(write_off.guarantee_forfeited, 200);
+    assert_eq!(write_off.recovered_amount, 0);
+    assert_eq!(write_off.written_off_at, 2001);
+}
+
+#[test]
+fn test_recovery_on_defaulted_loan_returns_to_pool_as_income() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+    setup.client.confirm_delivery(&user, &loan_id);
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+
+    let collector = Address::generate(&env);
+    setup.token_admin.mint(&collector, &300);
+    // The forfeited guarantee already covers 200 of the 1,000 balance
+    assert_eq!(setup.client.recover_loan(&collector, &loan_id, &300), 500);
+
+    assert_last_event(&env, symbol_short!("LOANRCVR"));
+
+    assert_eq!(setup.pool.tracked(&symbol_short!("RECOVERY")), 300);
+    assert_eq!(
+        setup.token.balance(&setup.liquidity_pool),
+        POOL_LIQUIDITY - 1000 + 200 + 300
+    );
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Defaulted);
+    assert_eq!(loan.remaining_balance, 500);
+    assert_eq!(
+        setup
+            .client
+            .get_write_off(&loan_id)
+            .unwrap()
+            .recovered_amount,
+        300
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")] // LoanNotDefaulted
+fn test_recovery_on_active_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &500);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup.client.recover_loan(&user, &loan_id, &300);
+}
+
+// Merchant statistics reporting tests
+
+#[test]
+fn test_loan_outcomes_reported_to_merchant_registry() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&merchant, &1000);
+
+    let refunded_user = Address::generate(&env);
+    setup.token_admin.mint(&refunded_user, &200);
+    let refunded_loan = setup.client.create_loan(
+        &refunded_user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+    setup.client.refund_loan(&merchant, &refunded_loan, &300);
+
+    let defaulted_user = Address::generate(&env);
+    setup.token_admin.mint(&defaulted_user, &400);
+    setup.pool.set_rate(&1_000);
+    let defaulted_loan = setup.client.create_loan(
+        &defaulted_user,
+        &merchant,
+        &2000,
+        &400,
+        &single_installment(&env, 2000, YEAR),
+        &order(&env, "ORDER-2"),
+    );
+    assert_eq!(
+        setup.client.get_loan(&defaulted_loan).remaining_balance,
+        2000 + 200
+    );
+    env.ledger().set_timestamp(YEAR + 1);
+    setup.client.mark_defaulted(&defaulted_loan);
+
+    // Volumes are reported as financed principal, without interest
+    assert_eq!(setup.registry.recorded(&symbol_short!("ORIG")), 1000 + 2000);
+    assert_eq!(setup.registry.recorded(&symbol_short!("RFND")), 300);
+    assert_eq!(setup.registry.recorded(&symbol_short!("DFLT")), 2000);
+}
+
+// Pool funding and repayment tests
+
+#[test]
+fn test_create_loan_disburses_from_pool_to_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 1000);
+    assert_eq!(setup.client.get_loan(&loan_id).outstanding_principal, 1000);
+
+    // The disbursement is held until the borrower confirms delivery
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.client.confirm_delivery(&user, &loan_id), 1000);
+
+    assert_last_event(&env, symbol_short!("STLRELS"));
+
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Released
+    );
+}
+
+#[test]
+fn test_merchant_receives_purchase_price_less_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.registry.set_fee(&300);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.client.confirm_delivery(&user, &loan_id);
+
+    // The pool pays the whole price; the guarantee stays with the CreditLine as collateral
+    assert_eq!(setup.token.balance(&merchant), 1000 - 30);
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 1000);
+    assert_eq!(setup.token.balance(&setup.client.address), 200);
+    assert_eq!(setup.client.get_loan(&loan_id).guarantee_amount, 200);
+}
+
+#[test]
+fn test_merchant_fee_withheld_and_paid_to_recipients() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let admin = setup.client.get_admin();
+    let treasury = Address::generate(&env);
+    let incentive_fund = Address::generate(&env);
+    setup.client.set_treasury(&admin, &treasury);
+    setup.client.set_incentive_fund(&admin, &incentive_fund);
+    setup.registry.set_fee(&300);
+
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    // 3% of 1000 = 30: 26 to the pool, 3 to the treasury, 1 to the incentive fund
+    assert_eq!(setup.client.confirm_delivery(&user, &loan_id), 970);
+    assert_eq!(setup.token.balance(&merchant), 970);
+    assert_eq!(setup.token.balance(&treasury), 3);
+    assert_eq!(setup.token.balance(&incentive_fund), 1);
+    assert_eq!(
+        setup.token.balance(&setup.liquidity_pool),
+        POOL_LIQUIDITY - 1000 + 26
+    );
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 26);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 0);
+}
+
+#[test]
+fn test_merchant_fee_shares_without_recipients_go_to_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.registry.set_fee(&300);
+
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    assert_eq!(setup.client.confirm_delivery(&user, &loan_id), 970);
+    assert_eq!(setup.token.balance(&merchant), 970);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 30);
+}
+
+// Delivery settlement tests
+
+#[test]
+fn test_settlement_released_after_delivery_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup
+        .client
+        .set_delivery_window(&setup.client.get_admin(), &100);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().release_after,
+        100
+    );
+
+    env.ledger().set_timestamp(100);
+    assert_eq!(setup.client.release_settlement(&loan_id), 1000);
+    assert_eq!(setup.token.balance(&merchant), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn test_settlement_release_before_delivery_window_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    env.ledger()
+        .set_timestamp(DEFAULT_DELIVERY_WINDOW_SECONDS - 1);
+    setup.client.release_settlement(&loan_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_disputed_settlement_cannot_be_released() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    env.ledger().set_timestamp(DEFAULT_DELIVERY_WINDOW_SECONDS);
+    setup.client.release_settlement(&loan_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_dispute_by_other_user_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    setup.client.open_dispute(
+        &Address::generate(&env),
+        &loan_id,
+        &BytesN::from_array(&env, &[7; 32]),
+    );
+}
+
+#[test]
+fn test_dispute_resolved_for_borrower_returns_held_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let arbiter = Address::generate(&env);
+    setup
+        .client
+        .set_arbiter(&setup.client.get_admin(), &arbiter);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.token_admin.mint(&user, &300);
+    setup.client.repay_loan(&user, &loan_id, &300);
+    setup
+        .client
+        .open_dispute(&user, &loan_id, &BytesN::from_array(&env, &[7; 32]));
+
+    setup.client.resolve_dispute(&arbiter, &loan_id, &true);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Refunded);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(loan.outstanding_principal, 0);
+    assert_eq!(loan.refunded_amount, 1000);
+
+    // The pool is made whole; the borrower gets back the guarantee and the principal repaid
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+    assert_eq!(setup.token.balance(&user), 200 + 300);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Returned
+    );
+    assert!(setup.client.get_write_off(&loan_id).is_none());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_resolve_undisputed_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let arbiter = Address::generate(&env);
+    setup
+        .client
+        .set_arbiter(&setup.client.get_admin(), &arbiter);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    setup.client.resolve_dispute(&arbiter, &loan_id, &false);
+}
+
+#[test]
+fn test_default_returns_held_settlement_to_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+
+    // The held disbursement covers the financed principal; nothing is written off
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("LOSS")), 0);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+    assert_eq!(setup.client.get_loan(&loan_id).remaining_balance, 0);
+}
+
+#[test]
+fn test_default_cleared_by_held_settlement_is_recovered() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+    let user_balance = setup.token.balance(&user);
+
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+
+    assert_last_event(&env, symbol_short!("LOANRCVD"));
+
+    // Nothing is left to collect, so the loan does not stay Defaulted
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(loan.status, LoanStatus::Recovered);
+
+    // The guarantee was not needed and goes back to the borrower
+    assert_eq!(loan.guarantee_amount, 0);
+    assert_eq!(setup.token.balance(&user), user_balance + 200);
+    let write_off = setup.client.get_write_off(&loan_id).unwrap();
+    assert_eq!(write_off.guarantee_forfeited, 0);
+    assert_eq!(
+        setup.client.try_recover_loan(&loan.borrower, &loan_id, &1),
+        Err(Ok(CreditLineError::LoanNotDefaulted))
+    );
+}
+
+fn with_escrow_adapter(env: &Env, setup: &TokenSetup) -> Address {
+    let escrow_adapter = env.register(MockEscrowAdapter, ());
+    MockEscrowAdapterClient::new(env, &escrow_adapter)
+        .init(&setup.token.address, &setup.client.address);
+    setup
+        .client
+        .set_escrow_adapter(&setup.client.get_admin(), &escrow_adapter);
+    escrow_adapter
+}
+
+#[test]
+fn test_create_loan_disburses_into_escrow_when_adapter_set() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let escrow_adapter = with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.token.balance(&escrow_adapter), 1000);
+    let adapter = MockEscrowAdapterClient::new(&env, &escrow_adapter);
+    assert_eq!(adapter.milestones(&loan_id), Vec::from_array(&env, [1000]));
+
+    let settlement = setup.client.get_settlement(&loan_id).unwrap();
+    assert_eq!(settlement.status, SettlementStatus::Held);
+    assert_eq!(settlement.escrow_adapter, Some(escrow_adapter));
+}
+
+#[test]
+fn test_confirm_delivery_releases_escrow_to_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let escrow_adapter = with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    assert_eq!(setup.client.confirm_delivery(&user, &loan_id), 1000);
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(setup.token.balance(&escrow_adapter), 0);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Released
+    );
+}
+
+#[test]
+fn test_escrow_released_after_delivery_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup
+        .client
+        .set_delivery_window(&setup.client.get_admin(), &100);
+    with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    let result = setup.client.try_release_settlement(&loan_id);
+    assert_eq!(result, Err(Ok(CreditLineError::DeliveryWindowOpen)));
+
+    env.ledger().set_timestamp(100);
+    assert_eq!(setup.client.release_settlement(&loan_id), 1000);
+    assert_eq!(setup.token.balance(&merchant), 1000);
+}
+
+#[test]
+fn test_refund_returns_escrowed_funds_through_adapter() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let escrow_adapter = with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    // The merchant never received the escrowed funds, so the refund comes out of the escrow
+    setup.client.refund_loan(&merchant, &loan_id, &1000);
+
+    assert_eq!(setup.client.get_loan(&loan_id).status, LoanStatus::Refunded);
+    assert_eq!(setup.token.balance(&escrow_adapter), 0);
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.token.balance(&user), 200);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Returned
+    );
+}
+
+#[test]
+fn test_partial_refund_keeps_escrow_remainder_held() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let escrow_adapter = with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    setup.client.refund_loan(&merchant, &loan_id, &300);
+
+    // The rest of the escrow is now held by the CreditLine and follows the usual flows
+    let settlement = setup.client.get_settlement(&loan_id).unwrap();
+    assert_eq!(settlement.amount, 700);
+    assert_eq!(settlement.escrow_adapter, None);
+    assert_eq!(setup.token.balance(&escrow_adapter), 0);
+    assert_eq!(setup.token.balance(&setup.client.address), 700 + 140);
+
+    assert_eq!(setup.client.confirm_delivery(&user, &loan_id), 700);
+    assert_eq!(setup.token.balance(&merchant), 700);
+}
+
+#[test]
+fn test_default_returns_escrowed_funds_to_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let escrow_adapter = with_escrow_adapter(&env, &setup);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+
+    assert_eq!(setup.token.balance(&merchant), 0);
+    assert_eq!(setup.token.balance(&escrow_adapter), 0);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("LOSS")), 0);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_create_loan_with_insufficient_pool_liquidity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &10_000);
+
+    // 50,000 exceeds the pool's 10,000 of liquidity
+    setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &50_000,
+        &10_000,
+        &single_installment(&env, 50_000, 1000),
+        &order(&env, "ORDER-1"),
+    );
+}
+
+#[test]
+fn test_repay_loan_partial() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &1_200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    let status = setup.client.repay_loan(&user, &loan_id, &500);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LOANPAID"));
+    let (amount, remaining, _timestamp, payer): (i128, i128, u64, Address) = last.2.into_val(&env);
+    assert_eq!((amount, remaining), (500, 500));
+    assert_eq!(payer, user);
+
+    assert_eq!(status, LoanStatus::Active);
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.remaining_balance, 500);
+    assert_eq!(loan.outstanding_principal, 500);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 500);
+}
+
+#[test]
+fn test_repay_loan_full_returns_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &1_200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+    setup.client.confirm_delivery(&user, &loan_id);
+
+    setup.client.repay_loan(&user, &loan_id, &500);
+    let status = setup.client.repay_loan(&user, &loan_id, &500);
+
+    assert_eq!(status, LoanStatus::Paid);
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(loan.guarantee_amount, 0);
+    assert_eq!(loan.outstanding_principal, 0);
+
+    // Without interest the borrower repays exactly what the pool financed
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 0);
+    assert_eq!(setup.token.balance(&user), 1_200 - 1000 + 200 - 200);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+}
+
+#[test]
+fn test_repay_loan_by_third_party_credits_borrower() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    let reputation_id = env.register(MockReputation, ());
+    let reputation = MockReputationClient::new(&env, &reputation_id);
+    setup
+        .client
+        .set_reputation_contract(&setup.client.get_admin(), &reputation_id);
+    reputation.set_score(&60);
+
+    let employer = Address::generate(&env);
+    setup.token_admin.mint(&employer, &1000);
+    setup.client.repay_loan(&employer, &loan_id, &500);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let borrower: Address = last.1.get(1).unwrap().into_val(&env);
+    let (_amount, _remaining, _timestamp, payer): (i128, i128, u64, Address) =
+        last.2.into_val(&env);
+    assert_eq!(borrower, user);
+    assert_eq!(payer, employer);
+    assert_eq!(reputation.get_score(&user), 60);
+
+    let status = setup.client.repay_loan(&employer, &loan_id, &500);
+    assert_eq!(status, LoanStatus::Paid);
+
+    // The payer funds the repayment, the guarantee and the reputation go to the borrower
+    assert_eq!(setup.token.balance(&employer), 0);
+    assert_eq!(setup.token.balance(&user), 200);
+    assert_eq!(
+        reputation.get_score(&user),
+        60 + REPAYMENT_REPUTATION_REWARD
+    );
+}
+
+#[test]
+fn test_payer_allowlist_restricts_repayments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &400);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    let family = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    setup.token_admin.mint(&family, &100);
+    setup.token_admin.mint(&stranger, &100);
+    assert_eq!(setup.client.get_allowed_payers(&user), None);
+
+    setup.client.allow_payer(&user, &family);
+    assert_eq!(
+        setup.client.get_allowed_payers(&user),
+        Some(vec![&env, family.clone()])
+    );
+
+    setup.client.repay_loan(&family, &loan_id, &100);
+    setup.client.repay_loan(&user, &loan_id, &100);
+    assert_eq!(
+        setup.client.try_repay_loan(&stranger, &loan_id, &100),
+        Err(Ok(CreditLineError::PayerNotAllowed))
+    );
+
+    // Revoking keeps the allowlist in force until it is cleared
+    setup.client.revoke_payer(&user, &family);
+    assert_eq!(setup.client.get_allowed_payers(&user), Some(vec![&env]));
+    setup.token_admin.mint(&family, &100);
+    assert_eq!(
+        setup.client.try_repay_loan(&family, &loan_id, &100),
+        Err(Ok(CreditLineError::PayerNotAllowed))
+    );
+
+    setup.client.clear_payer_allowlist(&user);
+    assert_last_event(&env, symbol_short!("PAYERCLR"));
+    assert_eq!(setup.client.get_allowed_payers(&user), None);
+
+    setup.client.repay_loan(&stranger, &loan_id, &100);
+    assert_eq!(setup.client.get_loan(&loan_id).remaining_balance, 700);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_repay_loan_overpayment_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &1_400);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+
+    setup.client.repay_loan(&user, &loan_id, &1001);
+}
+
+// Interest pricing tests
+
+const YEAR: u64 = 365 * 24 * 60 * 60;
+
+#[test]
+fn test_create_loan_priced_at_pool_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.pool.set_rate(&1_000);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut schedule = Vec::new(&env);
+    schedule.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: YEAR / 4,
+    });
+    schedule.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: YEAR / 2,
+    });
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &schedule,
+        &order(&env, "ORDER-1"),
+    );
+
+    // 1,000 financed at 10% APR for half a year
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.interest_rate_bps, 1_000);
+    assert_eq!(loan.interest_amount, 50);
+    assert_eq!(loan.remaining_balance, 1050);
+    assert_eq!(loan.repayment_schedule.get(0).unwrap().amount, 525);
+    assert_eq!(loan.repayment_schedule.get(1).unwrap().amount, 525);
+}
+
+#[test]
+fn test_repaid_interest_reported_as_pool_income() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.pool.set_rate(&1_000);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &1_250);
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &single_installment(&env, 1000, YEAR / 2),
+        &order(&env, "ORDER-1"),
+    );
+
+    let status = setup.client.repay_loan(&user, &loan_id, &1050);
+
+    assert_eq!(status, LoanStatus::Paid);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 50);
+}
+
+// Dispute resolution tests
+
+#[test]
+fn test_open_dispute_pauses_loan_and_freezes_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    assert_last_event(&env, symbol_short!("DSPOPEN"));
+
+    assert_eq!(setup.client.get_loan(&loan_id).status, LoanStatus::Disputed);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Disputed
+    );
+    let dispute = setup.client.get_dispute(&loan_id).unwrap();
+    assert_eq!(dispute.opened_at, 500);
+    assert_eq!(dispute.outcome, DisputeOutcome::Pending);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_disputed_loan_cannot_be_defaulted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    env.ledger().set_timestamp(2001);
+    setup.client.mark_defaulted(&loan_id);
+}
+
+#[test]
+fn test_dispute_resolved_for_merchant_resumes_schedule() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Disputed);
+    let arbiter = setup.client.get_arbiter().unwrap();
+
+    env.ledger().set_timestamp(800);
+    setup.client.resolve_dispute(&arbiter, &loan_id, &false);
+
+    assert_last_event(&env, symbol_short!("DSPRSLV"));
+
+    // Due dates move back by the 300 seconds spent in dispute
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.repayment_schedule.get(0).unwrap().due_date, 1300);
+    assert_eq!(loan.repayment_schedule.get(1).unwrap().due_date, 2300);
+
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(
+        setup.client.get_dispute(&loan_id).unwrap().outcome,
+        DisputeOutcome::MerchantFavored
+    );
+}
+
+#[test]
+fn test_dispute_resolved_for_borrower_claims_merchant_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let arbiter = Address::generate(&env);
+    setup
+        .client
+        .set_arbiter(&setup.client.get_admin(), &arbiter);
+    setup.registry.set_bond(&setup.token.address, &1_200);
+    setup.token_admin.mint(&setup.registry.address, &1_200);
+
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.client.confirm_delivery(&user, &loan_id);
+    setup.token_admin.mint(&user, &300);
+    setup.client.repay_loan(&user, &loan_id, &300);
+    setup
+        .client
+        .open_dispute(&user, &loan_id, &BytesN::from_array(&env, &[7; 32]));
+
+    setup.client.resolve_dispute(&arbiter, &loan_id, &true);
+
+    // The merchant's bond repays the pool and the borrower; LPs bear no loss
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(setup.token.balance(&setup.registry.address), 200);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+    assert_eq!(setup.token.balance(&user), 200 + 300);
+    assert_eq!(setup.pool.tracked(&symbol_short!("LOSS")), 0);
+    assert!(setup.client.get_write_off(&loan_id).is_none());
+    assert_eq!(setup.registry.recorded(&symbol_short!("RFND")), 1000);
+}
+
+#[test]
+fn test_dispute_resolved_for_borrower_writes_off_paid_disbursement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let arbiter = Address::generate(&env);
+    setup
+        .client
+        .set_arbiter(&setup.client.get_admin(), &arbiter);
+    let LoanSetup {
+        user,
+        merchant,
+        loan_id,
+    } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.client.confirm_delivery(&user, &loan_id);
+    setup
+        .client
+        .open_dispute(&user, &loan_id, &BytesN::from_array(&env, &[7; 32]));
+
+    setup.client.resolve_dispute(&arbiter, &loan_id, &true);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Refunded);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(setup.token.balance(&user), 200);
+
+    // The merchant kept the disbursement and has no bond, so the pool writes it off
+    assert_eq!(setup.token.balance(&merchant), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("LOSS")), 1000);
+    assert_eq!(
+        setup
+            .client
+            .get_write_off(&loan_id)
+            .unwrap()
+            .principal_written_off,
+        1000
+    );
+    assert_eq!(setup.registry.recorded(&symbol_short!("RFND")), 1000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_resolve_dispute_by_non_arbiter_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    setup
+        .client
+        .resolve_dispute(&Address::generate(&env), &loan_id, &true);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_loan_can_only_be_disputed_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+    let arbiter = setup.client.get_arbiter().unwrap();
+    setup.client.resolve_dispute(&arbiter, &loan_id, &false);
+
+    setup
+        .client
+        .open_dispute(&user, &loan_id, &BytesN::from_array(&env, &[8; 32]));
+}
+
+#[test]
+fn test_dispute_must_be_opened_within_window() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+    let created_at = setup.client.get_loan(&loan_id).created_at;
+
+    env.ledger()
+        .set_timestamp(created_at + DISPUTE_WINDOW_SECONDS + 1);
+    assert_eq!(
+        setup
+            .client
+            .try_open_dispute(&user, &loan_id, &BytesN::from_array(&env, &[7; 32])),
+        Err(Ok(CreditLineError::DisputeWindowClosed))
+    );
+}
+
+#[test]
+fn test_expire_dispute_before_timeout_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    env.ledger()
+        .set_timestamp(500 + DISPUTE_RESOLUTION_TIMEOUT_SECONDS - 1);
+    assert_eq!(
+        setup.client.try_expire_dispute(&loan_id),
+        Err(Ok(CreditLineError::DisputeNotExpired))
+    );
+}
+
+#[test]
+fn test_expired_dispute_falls_back_to_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Disputed);
+
+    // The arbiter never rules; anyone can expire the dispute once the timeout has passed
+    let expired_at = 500 + DISPUTE_RESOLUTION_TIMEOUT_SECONDS;
+    env.ledger().set_timestamp(expired_at);
+    setup.client.expire_dispute(&loan_id);
+
+    assert_last_event(&env, symbol_short!("DSPEXPD"));
+
+    let dispute = setup.client.get_dispute(&loan_id).unwrap();
+    assert_eq!(dispute.outcome, DisputeOutcome::MerchantFavored);
+    assert_eq!(dispute.resolved_at, expired_at);
+    assert_eq!(setup.token.balance(&merchant), 1000);
+
+    // The schedule resumes and the loan can default once it is overdue again
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    let last_due = loan.repayment_schedule.last().unwrap().due_date;
+    assert_eq!(last_due, 2000 + DISPUTE_RESOLUTION_TIMEOUT_SECONDS);
+
+    env.ledger().set_timestamp(last_due + 1);
+    setup.client.mark_defaulted(&loan_id);
+    assert_eq!(
+        setup.client.get_loan(&loan_id).status,
+        LoanStatus::Defaulted
+    );
+}
+
+// Loan request tests
+
+#[test]
+fn test_request_loan_escrows_guarantee_without_funding() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    assert_last_event(&env, symbol_short!("LOANREQ"));
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Pending);
+    assert_eq!(loan.outstanding_principal, 0);
+    assert_eq!(setup.token.balance(&user), 0);
+    assert_eq!(setup.token.balance(&setup.client.address), 200);
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 0);
+
+    let request = setup.client.get_loan_request(&loan_id).unwrap();
+    assert_eq!(request.expires_at, DEFAULT_REQUEST_TTL_SECONDS);
+    assert_eq!(loan.order_id, None);
+}
+
+#[test]
+fn test_accept_loan_funds_and_binds_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    env.ledger().set_timestamp(50);
+    let order_id = String::from_str(&env, "ORDER-1001");
+    setup.client.accept_loan(&merchant, &loan_id, &order_id);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.outstanding_principal, 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 1000);
+    assert_eq!(
+        setup.client.get_settlement(&loan_id).unwrap().status,
+        SettlementStatus::Held
+    );
+
+    assert_eq!(loan.order_id, Some(order_id));
+    assert_eq!(
+        setup.client.get_loan_request(&loan_id).unwrap().accepted_at,
+        50
+    );
+}
+
+#[test]
+fn test_accept_loan_rechecks_borrower_reputation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    // The borrower's score drops below the threshold while the request is pending
+    let reputation_id = env.register(MockReputation, ());
+    MockReputationClient::new(&env, &reputation_id).set_score(&10);
+    setup
+        .client
+        .set_reputation_contract(&setup.client.get_admin(), &reputation_id);
+
+    assert_eq!(
+        setup
+            .client
+            .try_accept_loan(&merchant, &loan_id, &String::from_str(&env, "ORDER-1001")),
+        Err(Ok(CreditLineError::InsufficientReputation))
+    );
+    assert_eq!(setup.client.get_loan(&loan_id).status, LoanStatus::Pending);
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_accept_loan_by_other_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    setup.client.accept_loan(
+        &Address::generate(&env),
+        &loan_id,
+        &String::from_str(&env, "ORDER-1001"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn test_accept_loan_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Pending);
+    let order_id = String::from_str(&env, "ORDER-1001");
+    setup.client.accept_loan(&merchant, &loan_id, &order_id);
+
+    setup.client.accept_loan(&merchant, &loan_id, &order_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_accept_expired_request_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup
+        .client
+        .set_request_ttl(&setup.client.get_admin(), &100);
+    let LoanSetup {
+        merchant, loan_id, ..
+    } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    env.ledger().set_timestamp(100);
+    setup
+        .client
+        .accept_loan(&merchant, &loan_id, &String::from_str(&env, "ORDER-1001"));
+}
+
+#[test]
+fn test_expired_request_returns_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup
+        .client
+        .set_request_ttl(&setup.client.get_admin(), &100);
+    assert_eq!(setup.client.get_request_ttl(), 100);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    env.ledger().set_timestamp(100);
+    setup.client.expire_loan_request(&loan_id);
+
+    assert_last_event(&env, symbol_short!("LOANEXPD"));
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Expired);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(setup.token.balance(&user), 200);
+    assert_eq!(setup.token.balance(&setup.client.address), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #25)")]
+fn test_expire_request_before_ttl_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Pending);
+
+    env.ledger().set_timestamp(DEFAULT_REQUEST_TTL_SECONDS - 1);
+    setup.client.expire_loan_request(&loan_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn test_expire_active_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    env.ledger().set_timestamp(DEFAULT_REQUEST_TTL_SECONDS);
+    setup.client.expire_loan_request(&loan_id);
+}
+
+// Order binding tests
+
+/// Sign `order` as the merchant would at checkout
+fn sign_order(
+    env: &Env,
+    setup: &TokenSetup,
+    signing_key: &SigningKey,
+    merchant: &Address,
+    borrower: &Address,
+    total: i128,
+    order: &mut Order,
+) {
+    let payload = (
+        setup.client.address.clone(),
+        merchant.clone(),
+        borrower.clone(),
+        total,
+        order.order_id.clone(),
+        order.invoice_hash.clone(),
+        order.expires_at,
+    )
+        .to_xdr(env);
+    let mut message = [0u8; 512];
+    let message = &mut message[..payload.len() as usize];
+    payload.copy_into_slice(message);
+
+    order.signature = Some(BytesN::from_array(
+        env,
+        &signing_key.sign(message).to_bytes(),
+    ));
+}
+
+fn signing_merchant(env: &Env, setup: &TokenSetup) -> SigningKey {
+    let signing_key = SigningKey::from_bytes(&[3; 32]);
+    setup.registry.set_signing_key(&BytesN::from_array(
+        env,
+        &signing_key.verifying_key().to_bytes(),
+    ));
+    signing_key
+}
+
+#[test]
+fn test_create_loan_records_order_and_invoice() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.order_id, Some(String::from_str(&env, "ORDER-1")));
+    assert_eq!(loan.invoice_hash, Some(BytesN::from_array(&env, &[1; 32])));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_order_can_only_be_financed_once() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, merchant, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.token_admin.mint(&user, &200);
+
+    setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &order(&env, "ORDER-1"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_accept_loan_for_financed_order_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, merchant, .. } = setup_loan(&env, &setup, LoanStatus::Active);
+    setup.token_admin.mint(&user, &200);
+    let loan_id = setup
+        .client
+        .request_loan(&user, &merchant, &1000, &200, &two_installments(&env));
+
+    setup
+        .client
+        .accept_loan(&merchant, &loan_id, &String::from_str(&env, "ORDER-1"));
+}
+
+#[test]
+fn test_create_loan_with_signed_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let signing_key = signing_merchant(&env, &setup);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &signing_key,
+        &merchant,
+        &user,
+        1000,
+        &mut signed,
+    );
+
+    let loan_id = setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &signed,
+    );
+
+    assert_eq!(setup.client.get_loan(&loan_id).status, LoanStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_create_loan_with_signature_over_other_terms_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let signing_key = signing_merchant(&env, &setup);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &400);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &signing_key,
+        &merchant,
+        &user,
+        1000,
+        &mut signed,
+    );
+
+    setup.client.create_loan(
+        &user,
+        &merchant,
+        &2000,
+        &400,
+        &two_installments(&env),
+        &signed,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_create_loan_with_signature_for_other_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let signing_key = signing_merchant(&env, &setup);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &signing_key,
+        &Address::generate(&env),
+        &user,
+        1000,
+        &mut signed,
+    );
+
+    setup.client.create_loan(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &two_installments(&env),
+        &signed,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Crypto, InvalidInput)")]
+fn test_create_loan_with_signature_over_other_invoice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let signing_key = signing_merchant(&env, &setup);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &signing_key,
+        &merchant,
+        &user,
+        1000,
+        &mut signed,
+    );
+    signed.invoice_hash = BytesN::from_array(&env, &[2; 32]);
+
+    setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &signed,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_create_loan_without_required_signature_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    signing_merchant(&env, &setup);
+    setup_loan(&env, &setup, LoanStatus::Active);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_create_loan_with_expired_signature_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let signing_key = signing_merchant(&env, &setup);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &signing_key,
+        &merchant,
+        &user,
+        1000,
+        &mut signed,
+    );
+
+    env.ledger().set_timestamp(100);
+    setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &signed,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #29)")]
+fn test_create_loan_signed_by_merchant_without_key_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let mut signed = order(&env, "ORDER-1");
+    signed.expires_at = 100;
+    sign_order(
+        &env,
+        &setup,
+        &SigningKey::from_bytes(&[3; 32]),
+        &merchant,
+        &user,
+        1000,
+        &mut signed,
+    );
+
+    setup.client.create_loan(
+        &user,
+        &merchant,
+        &1000,
+        &200,
+        &two_installments(&env),
+        &signed,
+    );
+}
+
+// Schedule generator tests
+
+fn terms(installments: u32) -> LoanTerms {
+    LoanTerms {
+        installments,
+        period_seconds: 100,
+        first_due: 1000,
+    }
+}
+
+#[test]
+fn test_preview_schedule_puts_remainder_in_last_installment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, _registry) = setup_with_merchant_registry(&env);
+
+    let schedule = client.preview_schedule(&1000, &terms(3));
+
+    assert_eq!(schedule.len(), 3);
+    assert_eq!(
+        schedule.get(0).unwrap(),
+        RepaymentInstallment {
+            due_date: 1000,
+            amount: 333
+        }
+    );
+    assert_eq!(schedule.get(1).unwrap().due_date, 1100);
+    assert_eq!(
+        schedule.get(2).unwrap(),
+        RepaymentInstallment {
+            due_date: 1200,
+            amount: 334
+        }
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_preview_schedule_without_installments_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, _registry) = setup_with_merchant_registry(&env);
+
+    client.preview_schedule(&1000, &terms(0));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_preview_schedule_with_more_installments_than_amount_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, _registry) = setup_with_merchant_registry(&env);
+
+    client.preview_schedule(&2, &terms(3));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_preview_schedule_above_max_installments_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin, _registry) = setup_with_merchant_registry(&env);
+
+    client.preview_schedule(&100_000, &terms(MAX_INSTALLMENTS + 1));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_create_loan_with_terms_first_due_not_in_future_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    env.ledger().set_timestamp(1000);
+    setup.client.create_loan_with_terms(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &3,
+        &100,
+        &1000,
+        &order(&env, "ORDER-1"),
+    );
+}
+
+#[test]
+fn test_create_loan_with_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    let loan_id = setup.client.create_loan_with_terms(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &3,
+        &100,
+        &1000,
+        &order(&env, "ORDER-1"),
+    );
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.repayment_schedule.len(), 3);
+    assert_eq!(loan.repayment_schedule.get(2).unwrap().due_date, 1200);
+
+    let scheduled: i128 = loan.repayment_schedule.iter().map(|i| i.amount).sum();
+    assert_eq!(scheduled, loan.remaining_balance);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_create_loan_with_terms_validates_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let user = Address::generate(&env);
+    setup.token_admin.mint(&user, &100);
+
+    setup.client.create_loan_with_terms(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &100,
+        &3,
+        &100,
+        &1000,
+        &order(&env, "ORDER-1"),
+    );
+}
+
+// Loan quote tests
+
+#[test]
+fn test_quote_loan_prices_eligible_loan_without_side_effects() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.pool.set_rate(&1_000);
+    setup.registry.set_fee(&250);
+    let user = Address::generate(&env);
+
+    // Two semi-annual installments, the last one due in a year
+    let half_year = (SECONDS_PER_YEAR / 2) as u64;
+    let quote = setup.client.quote_loan_with_terms(
+        &user,
+        &Address::generate(&env),
+        &1000,
+        &200,
+        &LoanTerms {
+            installments: 2,
+            period_seconds: half_year,
+            first_due: half_year,
+        },
+    );
+
+    assert!(quote.eligible);
+    assert_eq!(quote.error, None);
+    assert_eq!(quote.required_guarantee, 200);
+    assert_eq!(quote.interest_rate_bps, 1_000);
+    // 10% a year on the 1,000 financed by the pool
+    assert_eq!(quote.interest_amount, 100);
+    assert_eq!(quote.merchant_fee.total, 25);
+    assert_eq!(quote.available_credit, POOL_LIQUIDITY);
+    assert_eq!(quote.repayment_schedule.len(), 2);
+    assert_eq!(quote.repayment_schedule.get(1).unwrap().amount, 550);
+
+    assert_eq!(setup.pool.tracked(&symbol_short!("FUNDED")), 0);
+    assert_eq!(setup.token.balance(&setup.liquidity_pool), POOL_LIQUIDITY);
+    assert_eq!(setup.registry.recorded(&symbol_short!("ORIG")), 0);
+}
+
+#[test]
+fn test_quote_loan_uses_default_terms() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    env.ledger().set_timestamp(500);
+
+    let quote = setup.client.quote_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+        &200,
+    );
+
+    assert!(quote.eligible);
+    assert_eq!(quote.error, None);
+    assert_eq!(quote.repayment_schedule.len(), DEFAULT_QUOTE_INSTALLMENTS);
+    let first = quote.repayment_schedule.get(0).unwrap();
+    assert_eq!(first.due_date, 500 + DEFAULT_QUOTE_PERIOD_SECONDS);
+    assert_eq!(first.amount, 250);
+    assert_eq!(
+        quote.repayment_schedule.get(1).unwrap().due_date,
+        500 + 2 * DEFAULT_QUOTE_PERIOD_SECONDS
+    );
+}
+
+#[test]
+fn test_quote_loan_reports_insufficient_guarantee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+
+    let quote = setup.client.quote_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+        &100,
+    );
+
+    assert!(!quote.eligible);
+    assert_eq!(quote.error, Some(CreditLineError::InsufficientGuarantee));
+    assert_eq!(quote.required_guarantee, 200);
+    assert!(quote.repayment_schedule.is_empty());
+}
+
+#[test]
+fn test_quote_loan_reports_inactive_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.registry.set_active(&false);
+
+    let quote = setup.client.quote_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &1000,
+        &200,
+    );
+
+    assert!(!quote.eligible);
+    assert_eq!(quote.error, Some(CreditLineError::MerchantNotActive));
+}
+
+#[test]
+fn test_quote_loan_reports_insufficient_liquidity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+
+    let quote = setup.client.quote_loan(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &100_000,
+        &20_000,
+    );
+
+    assert!(!quote.eligible);
+    assert_eq!(quote.error, Some(CreditLineError::InsufficientLiquidity));
+    assert_eq!(quote.available_credit, POOL_LIQUIDITY);
+}
+
+#[test]
+fn test_quote_loan_reports_concentration_limit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    setup.pool.set_max_exposure(&999);
+    let user = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    setup.token_admin.mint(&user, &200);
+
+    // Liquidity is ample, but the pool would refuse to fund the 1,000 financed
+    let quote = setup
+        .client
+        .quote_loan_with_terms(&user, &merchant, &1000, &200, &terms(2));
+
+    assert!(!quote.eligible);
+    assert_eq!(
+        quote.error,
+        Some(CreditLineError::ConcentrationLimitExceeded)
+    );
+    assert!(setup
+        .client
+        .try_create_loan_with_terms(
+            &user,
+            &merchant,
+            &1000,
+            &200,
+            &2,
+            &100,
+            &1000,
+            &order(&env, "ORDER-1"),
+        )
+        .is_err());
+}
+
+// Early payoff tests
+
+fn interest_loan(env: &Env, setup: &TokenSetup) -> (Address, u64) {
+    setup.pool.set_rate(&1_000);
+    let user = Address::generate(env);
+    setup.token_admin.mint(&user, &1_300);
+
+    // 10% a year on the 1,000 financed by the pool: 100 interest over the one-year term
+    let loan_id = setup.client.create_loan(
+        &user,
+        &Address::generate(env),
+        &1000,
+        &200,
+        &single_installment(env, 1000, YEAR),
+        &order(env, "ORDER-1"),
+    );
+
+    (user, loan_id)
+}
+
+#[test]
+fn test_payoff_rebates_unearned_interest() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (user, loan_id) = interest_loan(&env, &setup);
+    assert_eq!(setup.client.get_payoff_amount(&loan_id), 1000);
+
+    env.ledger().set_timestamp(YEAR / 2);
+    assert_eq!(setup.client.get_payoff_amount(&loan_id), 1050);
+    assert_eq!(setup.client.payoff_loan(&user, &loan_id), 1050);
+
+    assert_last_event(&env, symbol_short!("LOANPYOF"));
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Paid);
+    assert!(loan.paid_early);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(loan.interest_amount, 50);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 50);
+
+    // The guarantee is returned along with the unspent funds
+    assert_eq!(setup.token.balance(&user), 1_300 - 1050);
+}
+
+#[test]
+fn test_payoff_after_partial_repayment() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (user, loan_id) = interest_loan(&env, &setup);
+    setup.client.repay_loan(&user, &loan_id, &550);
+
+    env.ledger().set_timestamp(YEAR / 2);
+    assert_eq!(setup.client.payoff_loan(&user, &loan_id), 500);
+    assert_eq!(setup.pool.tracked(&symbol_short!("PRINC")), 1000);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 50);
+}
+
+#[test]
+fn test_payoff_at_term_is_not_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (user, loan_id) = interest_loan(&env, &setup);
+
+    env.ledger().set_timestamp(YEAR);
+    assert_eq!(setup.client.payoff_loan(&user, &loan_id), 1100);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Paid);
+    assert!(!loan.paid_early);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_payoff_by_other_user_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (_user, loan_id) = interest_loan(&env, &setup);
+
+    setup.client.payoff_loan(&Address::generate(&env), &loan_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_payoff_of_paid_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (user, loan_id) = interest_loan(&env, &setup);
+    setup.client.payoff_loan(&user, &loan_id);
+
+    setup.client.payoff_loan(&user, &loan_id);
+}
+
+// Restructuring tests
+
+fn restructure_setup(env: &Env) -> (TokenSetup<'_>, MockReputationClient<'_>, u64) {
+    let setup = setup_with_token(env);
+    let reputation_id = env.register(MockReputation, ());
+    setup
+        .client
+        .set_reputation_contract(&setup.client.get_admin(), &reputation_id);
+
+    let LoanSetup { loan_id, .. } = setup_loan(env, &setup, LoanStatus::Active);
+
+    (
+        setup,
+        MockReputationClient::new(env, &reputation_id),
+        loan_id,
+    )
+}
+
+fn extended_schedule(env: &Env) -> Vec<RepaymentInstallment> {
+    let mut schedule = Vec::new(env);
+    for due_date in [2000, 3000, 4000, 5000] {
+        schedule.push_back(RepaymentInstallment {
+            amount: 250,
+            due_date,
+        });
+    }
+    schedule
+}
+
+#[test]
+fn test_restructure_loan_replaces_schedule() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, reputation, loan_id) = restructure_setup(&env);
+    let admin = setup.client.get_admin();
+    let previous_schedule = setup.client.get_loan(&loan_id).repayment_schedule;
+
+    env.ledger().set_timestamp(500);
+    setup.client.restructure_loan(
+        &admin,
+        &loan_id,
+        &extended_schedule(&env),
+        &symbol_short!("HARDSHIP"),
+    );
+
+    assert_last_event(&env, symbol_short!("LOANRSTR"));
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Active);
+    assert_eq!(loan.remaining_balance, 1000);
+    assert_eq!(loan.repayment_schedule, extended_schedule(&env));
+
+    let restructurings = setup.client.get_restructurings(&loan_id);
+    assert_eq!(restructurings.len(), 1);
+    let restructuring = restructurings.get(0).unwrap();
+    assert_eq!(restructuring.previous_schedule, previous_schedule);
+    assert_eq!(restructuring.new_schedule, extended_schedule(&env));
+    assert_eq!(restructuring.reason, symbol_short!("HARDSHIP"));
+    assert_eq!(restructuring.restructured_by, admin);
+    assert_eq!(restructuring.restructured_at, 500);
+
+    assert_eq!(reputation.penalty(), RESTRUCTURE_REPUTATION_PENALTY);
+}
+
+#[test]
+fn test_restructure_after_partial_repayment_keeps_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, _reputation, loan_id) = restructure_setup(&env);
+    let admin = setup.client.get_admin();
+    let risk_manager = Address::generate(&env);
+    setup.client.set_risk_manager(&admin, &risk_manager);
+    assert_eq!(setup.client.get_risk_manager(), Some(risk_manager.clone()));
+
+    setup.client.restructure_loan(
+        &admin,
+        &loan_id,
+        &extended_schedule(&env),
+        &symbol_short!("HARDSHIP"),
+    );
+
+    let borrower = setup.client.get_loan(&loan_id).borrower;
+    setup.token_admin.mint(&borrower, &200);
+    setup.client.repay_loan(&borrower, &loan_id, &200);
+    setup.client.restructure_loan(
+        &risk_manager,
+        &loan_id,
+        &single_installment(&env, 800, 6000),
+        &symbol_short!("EXTEND"),
+    );
+
+    let restructurings = setup.client.get_restructurings(&loan_id);
+    assert_eq!(restructurings.len(), 2);
+    let latest = restructurings.get(1).unwrap();
+    assert_eq!(latest.previous_schedule, extended_schedule(&env));
+    assert_eq!(latest.remaining_balance, 800);
+    assert_eq!(latest.restructured_by, risk_manager);
+}
+
+#[test]
+fn test_payoff_after_restructure_rebates_repriced_interest() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let (user, loan_id) = interest_loan(&env, &setup);
+
+    // Halfway through the year, 50 of the 100 interest is unearned; the new 18-month term
+    // charges 150 on the 1,000 outstanding instead
+    env.ledger().set_timestamp(YEAR / 2);
+    setup.client.restructure_loan(
+        &setup.client.get_admin(),
+        &loan_id,
+        &single_installment(&env, 1100, 2 * YEAR),
+        &symbol_short!("HARDSHIP"),
+    );
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.interest_amount, 200);
+    assert_eq!(loan.remaining_balance, 1200);
+    assert_eq!(loan.repayment_schedule.get(0).unwrap().amount, 1200);
+    assert_eq!(
+        setup
+            .client
+            .get_restructurings(&loan_id)
+            .get(0)
+            .unwrap()
+            .interest_amount,
+        150
+    );
+    assert_eq!(setup.client.get_payoff_amount(&loan_id), 1050);
+
+    // Half of the new term has elapsed: 75 of the 150 is still unearned
+    setup.token_admin.mint(&user, &25);
+    env.ledger().set_timestamp(YEAR / 2 + 3 * YEAR / 4);
+    assert_eq!(setup.client.get_payoff_amount(&loan_id), 1125);
+    assert_eq!(setup.client.payoff_loan(&user, &loan_id), 1125);
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Paid);
+    assert_eq!(loan.interest_amount, 125);
+    assert_eq!(setup.pool.tracked(&symbol_short!("INCOME")), 125);
+}
+
+#[test]
+fn test_restructure_penalty_never_exceeds_score() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, reputation, loan_id) = restructure_setup(&env);
+    reputation.set_score(&2);
+
+    setup.client.restructure_loan(
+        &setup.client.get_admin(),
+        &loan_id,
+        &extended_schedule(&env),
+        &symbol_short!("HARDSHIP"),
+    );
+
+    assert_eq!(reputation.penalty(), 2);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #31)")]
+fn test_restructure_by_other_caller_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, _reputation, loan_id) = restructure_setup(&env);
+
+    setup.client.restructure_loan(
+        &Address::generate(&env),
+        &loan_id,
+        &extended_schedule(&env),
+        &symbol_short!("HARDSHIP"),
+    );
+}
+
+#[test]
+fn test_restructure_with_invalid_schedule_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, _reputation, loan_id) = restructure_setup(&env);
+    let admin = setup.client.get_admin();
+    env.ledger().set_timestamp(1500);
+
+    // Does not cover the remaining balance
+    let short = single_installment(&env, 900, 3000);
+    // Due before the current time
+    let past_due = single_installment(&env, 1000, 1000);
+    let mut unordered = Vec::new(&env);
+    unordered.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: 3000,
+    });
+    unordered.push_back(RepaymentInstallment {
+        amount: 500,
+        due_date: 2000,
+    });
+
+    for schedule in [short, past_due, unordered, Vec::new(&env)] {
+        let result = setup.client.try_restructure_loan(
+            &admin,
+            &loan_id,
+            &schedule,
+            &symbol_short!("HARDSHIP"),
+        );
+        assert_eq!(result, Err(Ok(CreditLineError::InvalidSchedule)));
+    }
+    assert!(setup.client.get_restructurings(&loan_id).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_restructure_of_paid_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (setup, _reputation, loan_id) = restructure_setup(&env);
+    let borrower = setup.client.get_loan(&loan_id).borrower;
+    setup.token_admin.mint(&borrower, &1000);
+    setup.client.repay_loan(&borrower, &loan_id, &1000);
+
+    setup.client.restructure_loan(
+        &setup.client.get_admin(),
+        &loan_id,
+        &extended_schedule(&env),
+        &symbol_short!("HARDSHIP"),
+    );
+}
+
+// Post-default recovery tests
+
+#[test]
+fn test_repayment_on_defaulted_loan_is_recovered() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.token_admin.mint(&user, &400);
+
+    let status = setup.client.repay_loan(&user, &loan_id, &400);
+    assert_eq!(status, LoanStatus::Defaulted);
+
+    assert_last_event(&env, symbol_short!("LOANRCVR"));
+
+    assert_eq!(setup.pool.tracked(&symbol_short!("RECOVERY")), 400);
+    assert_eq!(setup.client.get_loan(&loan_id).remaining_balance, 400);
+    assert_eq!(
+        setup
+            .client
+            .get_write_off(&loan_id)
+            .unwrap()
+            .recovered_amount,
+        400
+    );
+}
+
+#[test]
+fn test_clearing_defaulted_balance_marks_loan_recovered() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let reputation_id = env.register(MockReputation, ());
+    let reputation = MockReputationClient::new(&env, &reputation_id);
+    let admin = setup.client.get_admin();
+    setup.client.set_reputation_contract(&admin, &reputation_id);
+    setup.client.set_recovery_restore(&admin, &10);
+    assert_eq!(setup.client.get_recovery_restore(), 10);
+
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    let collector = Address::generate(&env);
+    setup.token_admin.mint(&collector, &300);
+    setup.token_admin.mint(&user, &500);
+
+    // The forfeited guarantee already covers 200 of the 1,000 balance
+    assert_eq!(setup.client.get_loan(&loan_id).remaining_balance, 800);
+    setup.client.recover_loan(&collector, &loan_id, &300);
+    assert_eq!(reputation.restored(), 0);
+
+    let status = setup.client.repay_loan(&user, &loan_id, &500);
+    assert_eq!(status, LoanStatus::Recovered);
+
+    assert_last_event(&env, symbol_short!("LOANRCVD"));
+
+    let loan = setup.client.get_loan(&loan_id);
+    assert_eq!(loan.status, LoanStatus::Recovered);
+    assert_eq!(loan.remaining_balance, 0);
+    assert_eq!(setup.pool.tracked(&symbol_short!("RECOVERY")), 800);
+    assert_eq!(reputation.restored(), 10);
+    assert_eq!(
+        reputation.get_score(&user),
+        100 - DEFAULT_REPUTATION_PENALTY + 10
+    );
+}
+
+#[test]
+fn test_recovery_restore_never_exceeds_max_score() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let reputation_id = env.register(MockReputation, ());
+    let reputation = MockReputationClient::new(&env, &reputation_id);
+    let admin = setup.client.get_admin();
+    setup.client.set_reputation_contract(&admin, &reputation_id);
+    setup.client.set_recovery_restore(&admin, &50);
+
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.token_admin.mint(&user, &800);
+    setup.client.repay_loan(&user, &loan_id, &800);
+
+    assert_eq!(reputation.restored(), DEFAULT_REPUTATION_PENALTY);
+    assert_eq!(reputation.get_score(&user), 100);
+}
+
+#[test]
+fn test_recovery_without_restore_leaves_reputation() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let reputation_id = env.register(MockReputation, ());
+    let reputation = MockReputationClient::new(&env, &reputation_id);
+    setup
+        .client
+        .set_reputation_contract(&setup.client.get_admin(), &reputation_id);
+
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.token_admin.mint(&user, &800);
+
+    assert_eq!(
+        setup.client.repay_loan(&user, &loan_id, &800),
+        LoanStatus::Recovered
+    );
+    assert_eq!(reputation.restored(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")] // LoanNotDefaulted
+fn test_recovery_on_recovered_loan_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.token_admin.mint(&user, &900);
+    setup.client.recover_loan(&user, &loan_id, &800);
+
+    setup.client.recover_loan(&user, &loan_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn test_recover_loan_by_disallowed_payer_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.client.allow_payer(&user, &Address::generate(&env));
+    let collector = Address::generate(&env);
+    setup.token_admin.mint(&collector, &100);
+
+    setup.client.recover_loan(&collector, &loan_id, &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn test_repayment_on_defaulted_loan_by_disallowed_payer_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let setup = setup_with_token(&env);
+    let LoanSetup { user, loan_id, .. } = setup_loan(&env, &setup, LoanStatus::Defaulted);
+    setup.client.allow_payer(&user, &Address::generate(&env));
+    let other = Address::generate(&env);
+    setup.token_admin.mint(&other, &100);
+
+    setup.client.repay_loan(&other, &loan_id, &100);
+}
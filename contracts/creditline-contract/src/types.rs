@@ -1,12 +1,19 @@
-use soroban_sdk::{contracttype, Address};
+use soroban_sdk::{contracttype, Address, BytesN, String, Symbol};
+
+use crate::errors::CreditLineError;
 
 // Loan status enum
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum LoanStatus {
+    Pending, // Requested by the borrower, waiting for the merchant's acceptance
     Active,
     Paid,
     Defaulted,
+    Refunded,
+    Disputed,  // Contested by the borrower; due dates and default are paused until resolved
+    Expired,   // Request not accepted by the merchant in time; guarantee returned
+    Recovered, // Defaulted, then the unpaid balance was collected in full
 }
 
 // Repayment installment structure
@@ -17,6 +24,25 @@ pub struct RepaymentInstallment {
     pub amount: i128,  // Amount due for this installment
 }
 
+// Merchant fee withheld from a loan disbursement and its split between recipients
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerchantFee {
+    pub total: i128,
+    pub liquidity_pool: i128,
+    pub treasury: i128,
+    pub incentive_fund: i128,
+}
+
+// Split of merchant fees between recipients, in basis points (must sum to 10_000)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeSplit {
+    pub liquidity_pool_bps: u32,
+    pub treasury_bps: u32,
+    pub incentive_fund_bps: u32,
+}
+
 // Loan data structure
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -25,13 +51,149 @@ pub struct Loan {
     pub borrower: Address,
     pub merchant: Address,
     pub total_amount: i128,
-    pub guarantee_amount: i128,
-    pub remaining_balance: i128,
+    pub guarantee_amount: i128, // Collateral held by the CreditLine, never paid to the merchant
+    pub remaining_balance: i128, // Financed principal plus interest still owed; the guarantee stays in escrow
     pub repayment_schedule: soroban_sdk::Vec<RepaymentInstallment>,
     pub status: LoanStatus,
     pub created_at: u64, // Unix timestamp
+    pub merchant_fee: MerchantFee,
+    pub outstanding_principal: i128, // Principal funded by the liquidity pool not yet returned to it
+    pub financed_amount: i128,       // Principal funded by the liquidity pool at origination
+    pub refunded_amount: i128,       // Financed principal refunded by the merchant
+    pub interest_rate_bps: u32,      // Annual rate quoted by the liquidity pool at origination
+    pub interest_amount: i128,       // Interest added to the balance over the loan term
+    pub order_id: Option<String>,    // Merchant order the loan finances
+    pub invoice_hash: Option<BytesN<32>>, // Hash of the merchant's invoice for the order
+    pub paid_early: bool, // Paid off before the last installment was due, with unearned interest rebated
+}
+
+// Equal-installment repayment terms a schedule is built from
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanTerms {
+    pub installments: u32,
+    pub period_seconds: u64, // Time between due dates
+    pub first_due: u64,      // Due date of the first installment (Unix timestamp)
+}
+
+// Eligibility and pricing of a prospective loan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanQuote {
+    pub eligible: bool,
+    pub error: Option<CreditLineError>, // First failing validation, None when eligible
+    pub required_guarantee: i128,       // Minimum guarantee for the total amount
+    pub interest_rate_bps: u32,         // Annual rate the liquidity pool would charge
+    pub interest_amount: i128,
+    pub merchant_fee: MerchantFee,
+    pub repayment_schedule: soroban_sdk::Vec<RepaymentInstallment>, // Including interest
+    pub available_credit: i128, // Liquidity the pool can currently lend
+}
+
+// Merchant checkout a loan is opened for
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    pub order_id: String,
+    pub invoice_hash: BytesN<32>,
+    pub expires_at: u64, // End of the signed checkout's validity (Unix timestamp)
+    pub signature: Option<BytesN<64>>, // Merchant ed25519 signature over (CreditLine, merchant, borrower, total amount, order id, invoice hash, expires_at)
+}
+
+// Write-off recorded when a loan defaults
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteOff {
+    pub loan_id: u64,
+    pub principal_written_off: i128, // Unpaid principal net of the forfeited guarantee, recognized as a pool loss
+    pub guarantee_forfeited: i128,
+    pub recovered_amount: i128, // Collected after default and returned to the pool as income
+    pub written_off_at: u64,    // Unix timestamp
+}
+
+// Settlement status of a loan's merchant disbursement
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SettlementStatus {
+    Held,     // Waiting for delivery confirmation or the end of the delivery window
+    Disputed, // Frozen by the borrower until the dispute is resolved
+    Released, // Paid to the merchant
+    Returned, // Returned to the liquidity pool (refund, cancellation or default)
+}
+
+// Disbursement held until delivery is confirmed, by the CreditLine or an escrow adapter
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub loan_id: u64,
+    pub amount: i128,       // Still held for the merchant
+    pub release_after: u64, // End of the delivery window (Unix timestamp)
+    pub status: SettlementStatus,
+    pub escrow_adapter: Option<Address>, // Adapter holding the funds; None when the CreditLine does
+}
+
+// Outcome of a borrower–merchant dispute
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DisputeOutcome {
+    Pending,
+    BorrowerFavored, // Loan cancelled and refunded
+    MerchantFavored, // Schedule resumed
+}
+
+// Dispute opened by a borrower over a purchase
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    pub loan_id: u64,
+    pub evidence_hash: BytesN<32>, // Hash of the off-chain evidence
+    pub opened_at: u64,            // Unix timestamp
+    pub resolved_at: u64,          // Unix timestamp, 0 while pending
+    pub outcome: DisputeOutcome,
+}
+
+// Borrower loan request awaiting the merchant's acceptance
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanRequest {
+    pub loan_id: u64,
+    pub requested_at: u64, // Unix timestamp
+    pub expires_at: u64,   // Unix timestamp after which the request can no longer be accepted
+    pub accepted_at: u64,  // Unix timestamp, 0 while pending
+}
+
+// Restructuring of a loan's repayment schedule, kept as an audit trail
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Restructuring {
+    pub loan_id: u64,
+    pub previous_schedule: soroban_sdk::Vec<RepaymentInstallment>,
+    pub new_schedule: soroban_sdk::Vec<RepaymentInstallment>,
+    pub remaining_balance: i128, // Balance the new schedule covers, re-priced for the new term
+    pub interest_amount: i128, // Interest charged over the new schedule, accruing from restructured_at
+    pub reason: Symbol,
+    pub restructured_by: Address, // Admin or risk manager
+    pub restructured_at: u64,     // Unix timestamp
 }
 
 // Constants
 pub const MIN_GUARANTEE_PERCENT: i128 = 20; // 20% minimum guarantee
 pub const MIN_REPUTATION_THRESHOLD: u32 = 50; // Minimum reputation score required
+pub const BPS_DENOMINATOR: i128 = 10_000; // 100% in basis points
+pub const SECONDS_PER_YEAR: i128 = 365 * 24 * 60 * 60;
+pub const DEFAULT_DELIVERY_WINDOW_SECONDS: u64 = 14 * 24 * 60 * 60; // 14 days
+pub const DEFAULT_REQUEST_TTL_SECONDS: u64 = 24 * 60 * 60; // 1 day
+pub const DISPUTE_WINDOW_SECONDS: u64 = 30 * 24 * 60 * 60; // Time after origination a purchase can be disputed
+pub const DISPUTE_RESOLUTION_TIMEOUT_SECONDS: u64 = 30 * 24 * 60 * 60; // Time the arbiter has before a dispute falls back to the merchant
+pub const MAX_INSTALLMENTS: u32 = 52; // Longest schedule built from equal-installment terms
+pub const DEFAULT_QUOTE_INSTALLMENTS: u32 = 4; // Installments quoted by `quote_loan`
+pub const DEFAULT_QUOTE_PERIOD_SECONDS: u64 = 30 * 24 * 60 * 60; // Monthly, first one due a period from now
+pub const RESTRUCTURE_REPUTATION_PENALTY: u32 = 5; // Score points lost on restructuring, far less than a default
+pub const DEFAULT_REPUTATION_PENALTY: u32 = 25; // Score points lost when a loan defaults
+pub const REPAYMENT_REPUTATION_REWARD: u32 = 5; // Score points the borrower earns for repaying a loan in full
+pub const MAX_REPUTATION_SCORE: u32 = 100; // Highest score the reputation contract accepts
+
+// Default merchant fee split (85% pool, 10% treasury, 5% merchant incentives)
+pub const DEFAULT_POOL_FEE_BPS: u32 = 8_500;
+pub const DEFAULT_TREASURY_FEE_BPS: u32 = 1_000;
+pub const DEFAULT_INCENTIVE_FEE_BPS: u32 = 500;
@@ -0,0 +1,23 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::errors::LiquidityPoolError;
+use crate::storage;
+
+/// Require that the given address is the admin, otherwise panic with NotAdmin error
+pub fn require_admin(env: &Env, caller: &Address) {
+    let admin = storage::get_admin(env);
+
+    if caller != &admin {
+        panic_with_error!(env, LiquidityPoolError::NotAdmin);
+    }
+}
+
+/// Require authorization from the registered CreditLine contract, otherwise panic with NotCreditLine error
+/// Returns the CreditLine address
+pub fn require_creditline(env: &Env) -> Address {
+    let creditline = storage::get_creditline(env)
+        .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::NotCreditLine));
+
+    creditline.require_auth();
+    creditline
+}
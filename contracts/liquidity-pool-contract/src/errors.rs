@@ -0,0 +1,40 @@
+use soroban_sdk::contracterror;
+
+// Error types for the liquidity pool contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LiquidityPoolError {
+    InvalidDepositAmount = 1,
+    InsufficientShares = 2,
+    InsufficientLiquidity = 3,
+    PoolEmpty = 4,
+    InvalidWithdrawalAmount = 5,
+    OverflowError = 6,
+    NotAdmin = 7,
+    DepositTooSmall = 8,
+    NotCreditLine = 9,
+    InvalidAmount = 10,
+    InvalidRateModel = 11,
+    RequestNotFound = 12,
+    NotRequestOwner = 13,
+    RequestNotPending = 14,
+    InvalidInterestSplit = 15,
+    InvalidReserveFactor = 16,
+    InsufficientReserve = 17,
+    ReserveWithdrawalPending = 18,
+    NoReserveWithdrawal = 19,
+    TimelockNotExpired = 20,
+    ShareTokenNotSet = 21,
+    ShareTokenAlreadySet = 22,
+    PositionNotFound = 23,
+    NotPositionOwner = 24,
+    PositionNotLocked = 25,
+    PositionStillLocked = 26,
+    PositionUnlocked = 27,
+    InvalidConcentrationLimits = 28,
+    BorrowerLimitExceeded = 29,
+    MerchantLimitExceeded = 30,
+    CategoryLimitExceeded = 31,
+    WithdrawalRequestTooSmall = 32,
+}
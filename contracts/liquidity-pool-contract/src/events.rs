@@ -0,0 +1,207 @@
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+use crate::types::{LockedPosition, Tranche};
+
+// Event topics
+const LIQUIDITY_DEPOSITED: Symbol = symbol_short!("LIQDEPOS");
+const LIQUIDITY_WITHDRAWN: Symbol = symbol_short!("LIQWDRAW");
+const LOAN_FUNDED: Symbol = symbol_short!("LOANFUND");
+const REPAYMENT_RECEIVED: Symbol = symbol_short!("REPAYRCV");
+const LOSS_RECOGNIZED: Symbol = symbol_short!("LOSSRCG");
+const LOSS_UNCOVERED: Symbol = symbol_short!("LOSSUNCV");
+const RECOVERY_RECEIVED: Symbol = symbol_short!("RECOVERY");
+const INCOME_RECEIVED: Symbol = symbol_short!("INCOMRCV");
+const WITHDRAWAL_REQUESTED: Symbol = symbol_short!("WREQNEW");
+const WITHDRAWAL_FILLED: Symbol = symbol_short!("WREQFILL");
+const WITHDRAWAL_CANCELLED: Symbol = symbol_short!("WREQCNCL");
+const POSITION_LOCKED: Symbol = symbol_short!("POSLOCK");
+const POSITION_UNLOCKED: Symbol = symbol_short!("POSUNLCK");
+const POSITION_EXITED: Symbol = symbol_short!("POSEXIT");
+const RESERVE_FUNDED: Symbol = symbol_short!("RSVFUND");
+const RESERVE_WITHDRAWAL_SCHEDULED: Symbol = symbol_short!("RSVSCHED");
+const RESERVE_WITHDRAWN: Symbol = symbol_short!("RSVWDRAW");
+const RESERVE_WITHDRAWAL_CANCELLED: Symbol = symbol_short!("RSVCNCL");
+const BOOST_BUDGET_FUNDED: Symbol = symbol_short!("BOOSTFND");
+
+/// Emit a liquidity deposited event
+pub fn emit_liquidity_deposited(
+    env: &Env,
+    provider: &Address,
+    tranche: Tranche,
+    amount: i128,
+    shares: i128,
+) {
+    env.events().publish(
+        (LIQUIDITY_DEPOSITED, provider, tranche),
+        (amount, shares, env.ledger().timestamp()),
+    );
+}
+
+/// Emit a liquidity withdrawn event
+pub fn emit_liquidity_withdrawn(
+    env: &Env,
+    provider: &Address,
+    tranche: Tranche,
+    amount: i128,
+    shares: i128,
+) {
+    env.events().publish(
+        (LIQUIDITY_WITHDRAWN, provider, tranche),
+        (amount, shares, env.ledger().timestamp()),
+    );
+}
+
+/// Emit a loan funded event
+pub fn emit_loan_funded(env: &Env, recipient: &Address, amount: i128, total_loaned: i128) {
+    env.events()
+        .publish((LOAN_FUNDED, recipient), (amount, total_loaned));
+}
+
+/// Emit a repayment received event
+pub fn emit_repayment_received(env: &Env, principal: i128, income: i128, total_loaned: i128) {
+    env.events()
+        .publish((REPAYMENT_RECEIVED,), (principal, income, total_loaned));
+}
+
+/// Emit a loss recognized event with the amount absorbed by the reserve and each tranche
+pub fn emit_loss_recognized(
+    env: &Env,
+    amount: i128,
+    reserve_loss: i128,
+    junior_loss: i128,
+    senior_loss: i128,
+) {
+    env.events().publish(
+        (LOSS_RECOGNIZED,),
+        (amount, reserve_loss, junior_loss, senior_loss),
+    );
+}
+
+/// Emit a loss shortfall event for the part of a loss the reserve and tranches could not absorb
+pub fn emit_loss_uncovered(env: &Env, amount: i128, uncovered_loss: i128) {
+    env.events()
+        .publish((LOSS_UNCOVERED,), (amount, uncovered_loss));
+}
+
+/// Emit a recovery received event
+pub fn emit_recovery_received(env: &Env, amount: i128) {
+    env.events().publish((RECOVERY_RECEIVED,), amount);
+}
+
+/// Emit an income received event
+pub fn emit_income_received(env: &Env, from: &Address, amount: i128) {
+    env.events().publish((INCOME_RECEIVED, from), amount);
+}
+
+/// Emit a withdrawal request queued event
+pub fn emit_withdrawal_requested(env: &Env, provider: &Address, request_id: u64, shares: i128) {
+    env.events()
+        .publish((WITHDRAWAL_REQUESTED, provider, request_id), shares);
+}
+
+/// Emit a withdrawal request (partially) filled event
+pub fn emit_withdrawal_filled(
+    env: &Env,
+    provider: &Address,
+    request_id: u64,
+    amount: i128,
+    shares_burned: i128,
+    shares_remaining: i128,
+) {
+    env.events().publish(
+        (WITHDRAWAL_FILLED, provider, request_id),
+        (amount, shares_burned, shares_remaining),
+    );
+}
+
+/// Emit a withdrawal request cancelled event
+pub fn emit_withdrawal_cancelled(
+    env: &Env,
+    provider: &Address,
+    request_id: u64,
+    shares_returned: i128,
+) {
+    env.events().publish(
+        (WITHDRAWAL_CANCELLED, provider, request_id),
+        shares_returned,
+    );
+}
+
+/// Emit a reserve funded event with the income diverted and the new reserve balance
+pub fn emit_reserve_funded(env: &Env, amount: i128, reserve: i128) {
+    env.events().publish((RESERVE_FUNDED,), (amount, reserve));
+}
+
+/// Emit a reserve withdrawal scheduled event
+pub fn emit_reserve_withdrawal_scheduled(
+    env: &Env,
+    recipient: &Address,
+    amount: i128,
+    executable_at: u64,
+) {
+    env.events().publish(
+        (RESERVE_WITHDRAWAL_SCHEDULED, recipient),
+        (amount, executable_at),
+    );
+}
+
+/// Emit a reserve withdrawn event
+pub fn emit_reserve_withdrawn(env: &Env, recipient: &Address, amount: i128, reserve: i128) {
+    env.events()
+        .publish((RESERVE_WITHDRAWN, recipient), (amount, reserve));
+}
+
+/// Emit a reserve withdrawal cancelled event
+pub fn emit_reserve_withdrawal_cancelled(env: &Env, recipient: &Address, amount: i128) {
+    env.events()
+        .publish((RESERVE_WITHDRAWAL_CANCELLED, recipient), amount);
+}
+
+/// Emit a boost budget funded event
+pub fn emit_boost_budget_funded(env: &Env, from: &Address, amount: i128, budget: i128) {
+    env.events()
+        .publish((BOOST_BUDGET_FUNDED, from), (amount, budget));
+}
+
+/// Emit a locked position opened event
+pub fn emit_position_locked(env: &Env, position: &LockedPosition) {
+    env.events().publish(
+        (
+            POSITION_LOCKED,
+            position.provider.clone(),
+            position.position_id,
+        ),
+        (
+            position.tranche,
+            position.shares,
+            position.bonus_shares,
+            position.unlock_at,
+        ),
+    );
+}
+
+/// Emit a locked position released at maturity event
+pub fn emit_position_unlocked(
+    env: &Env,
+    provider: &Address,
+    position_id: u64,
+    shares: i128,
+    bonus_paid: i128,
+) {
+    env.events().publish(
+        (POSITION_UNLOCKED, provider, position_id),
+        (shares, bonus_paid),
+    );
+}
+
+/// Emit a locked position exited early event
+pub fn emit_position_exited(
+    env: &Env,
+    provider: &Address,
+    position_id: u64,
+    amount: i128,
+    penalty: i128,
+) {
+    env.events()
+        .publish((POSITION_EXITED, provider, position_id), (amount, penalty));
+}
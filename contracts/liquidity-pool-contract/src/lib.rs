@@ -0,0 +1,1198 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, token, Address, Env, Symbol, Vec,
+};
+
+// Module imports
+mod access;
+mod errors;
+mod events;
+mod rates;
+mod shares;
+mod storage;
+mod types;
+
+// Re-export types for external use
+pub use errors::LiquidityPoolError;
+pub use types::{
+    ConcentrationLimits, LoanExposure, LockedPosition, LockupTerm, PositionStatus, RateModel,
+    ReserveWithdrawal, Tranche, TrancheState, WithdrawalRequest, WithdrawalStatus,
+    EARLY_WITHDRAWAL_PENALTY_BPS, MAX_QUEUE_FILLS_PER_CALL, MINIMUM_LIQUIDITY,
+    MIN_WITHDRAWAL_REQUEST_SHARES, RESERVE_TIMELOCK_SECONDS, SHARE_VALUE_PRECISION,
+};
+
+/// Liquidity Pool contract structure
+#[contract]
+pub struct LiquidityPoolContract;
+
+/// Contract implementation
+#[contractimpl]
+impl LiquidityPoolContract {
+    /// Get the version of this contract
+    pub fn get_version() -> Symbol {
+        symbol_short!("v1_0_0")
+    }
+
+    /// Initialize the contract with its admin and the SEP-41 token it holds
+    /// Can only be called once (when admin is not set)
+    pub fn initialize(env: Env, admin: Address, token: Address) {
+        let admin_opt: Option<Address> = env.storage().instance().get(&storage::ADMIN_KEY);
+        if admin_opt.is_some() {
+            panic!("Already initialized");
+        }
+
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+        storage::set_token(&env, &token);
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let old_admin = storage::get_admin(&env);
+        old_admin.require_auth();
+        access::require_admin(&env, &old_admin);
+
+        storage::set_admin(&env, &new_admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    pub fn get_token(env: Env) -> Address {
+        storage::get_token(&env)
+    }
+
+    /// Set the CreditLine contract allowed to draw loan funding (admin only)
+    pub fn set_creditline(env: Env, admin: Address, creditline: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_creditline(&env, &creditline);
+    }
+
+    pub fn get_creditline(env: Env) -> Option<Address> {
+        storage::get_creditline(&env)
+    }
+
+    /// Configure the utilization-based interest rate curve (admin only)
+    pub fn set_rate_model(env: Env, admin: Address, model: RateModel) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if model.optimal_utilization_bps == 0
+            || model.optimal_utilization_bps as i128 >= types::BPS_DENOMINATOR
+        {
+            panic_with_error!(&env, LiquidityPoolError::InvalidRateModel);
+        }
+
+        storage::set_rate_model(&env, &model);
+    }
+
+    pub fn get_rate_model(env: Env) -> RateModel {
+        storage::get_rate_model(&env)
+    }
+
+    /// Set the share of pool income credited to the senior tranche (admin only)
+    /// The junior tranche receives the rest in exchange for absorbing losses first
+    pub fn set_interest_split(env: Env, admin: Address, senior_interest_bps: u32) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if senior_interest_bps as i128 > types::BPS_DENOMINATOR {
+            panic_with_error!(&env, LiquidityPoolError::InvalidInterestSplit);
+        }
+
+        storage::set_senior_interest_bps(&env, senior_interest_bps);
+    }
+
+    pub fn get_interest_split(env: Env) -> u32 {
+        storage::get_senior_interest_bps(&env)
+    }
+
+    /// Set the share of pool income diverted to the reserve (admin only)
+    pub fn set_reserve_factor(env: Env, admin: Address, reserve_factor_bps: u32) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if reserve_factor_bps as i128 > types::BPS_DENOMINATOR {
+            panic_with_error!(&env, LiquidityPoolError::InvalidReserveFactor);
+        }
+
+        storage::set_reserve_factor_bps(&env, reserve_factor_bps);
+    }
+
+    pub fn get_reserve_factor(env: Env) -> u32 {
+        storage::get_reserve_factor_bps(&env)
+    }
+
+    /// Set the caps on outstanding principal per borrower, merchant and merchant category,
+    /// as a share of total pool value (admin only)
+    pub fn set_concentration_limits(env: Env, admin: Address, limits: ConcentrationLimits) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        for bps in [
+            limits.max_borrower_bps,
+            limits.max_merchant_bps,
+            limits.max_category_bps,
+        ] {
+            if bps == 0 || bps as i128 > types::BPS_DENOMINATOR {
+                panic_with_error!(&env, LiquidityPoolError::InvalidConcentrationLimits);
+            }
+        }
+
+        storage::set_concentration_limits(&env, &limits);
+    }
+
+    pub fn get_concentration_limits(env: Env) -> ConcentrationLimits {
+        storage::get_concentration_limits(&env)
+    }
+
+    /// Schedule a withdrawal from the reserve (admin only)
+    /// Executable after RESERVE_TIMELOCK_SECONDS; only one withdrawal can be pending at a time
+    pub fn schedule_reserve_withdrawal(env: Env, admin: Address, recipient: Address, amount: i128) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if amount <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+        if amount > storage::get_reserve(&env) {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientReserve);
+        }
+        if storage::get_reserve_withdrawal(&env).is_some() {
+            panic_with_error!(&env, LiquidityPoolError::ReserveWithdrawalPending);
+        }
+
+        let executable_at = env
+            .ledger()
+            .timestamp()
+            .checked_add(RESERVE_TIMELOCK_SECONDS)
+            .unwrap_or_else(|| panic_with_error!(&env, LiquidityPoolError::OverflowError));
+        storage::set_reserve_withdrawal(
+            &env,
+            &ReserveWithdrawal {
+                amount,
+                recipient: recipient.clone(),
+                executable_at,
+            },
+        );
+
+        events::emit_reserve_withdrawal_scheduled(&env, &recipient, amount, executable_at);
+    }
+
+    /// Execute the scheduled reserve withdrawal once its timelock has passed (admin only)
+    pub fn execute_reserve_withdrawal(env: Env, admin: Address) -> i128 {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let withdrawal = storage::get_reserve_withdrawal(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, LiquidityPoolError::NoReserveWithdrawal));
+        if env.ledger().timestamp() < withdrawal.executable_at {
+            panic_with_error!(&env, LiquidityPoolError::TimelockNotExpired);
+        }
+
+        // The reserve may have absorbed losses since the withdrawal was scheduled
+        let reserve = storage::get_reserve(&env);
+        if withdrawal.amount > reserve {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientReserve);
+        }
+
+        let reserve = reserve - withdrawal.amount;
+        storage::set_reserve(&env, reserve);
+        storage::remove_reserve_withdrawal(&env);
+
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &env.current_contract_address(),
+            &withdrawal.recipient,
+            &withdrawal.amount,
+        );
+
+        events::emit_reserve_withdrawn(&env, &withdrawal.recipient, withdrawal.amount, reserve);
+
+        withdrawal.amount
+    }
+
+    /// Cancel the scheduled reserve withdrawal (admin only)
+    pub fn cancel_reserve_withdrawal(env: Env, admin: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let withdrawal = storage::get_reserve_withdrawal(&env)
+            .unwrap_or_else(|| panic_with_error!(&env, LiquidityPoolError::NoReserveWithdrawal));
+        storage::remove_reserve_withdrawal(&env);
+
+        events::emit_reserve_withdrawal_cancelled(&env, &withdrawal.recipient, withdrawal.amount);
+    }
+
+    pub fn get_reserve_withdrawal(env: Env) -> Option<ReserveWithdrawal> {
+        storage::get_reserve_withdrawal(&env)
+    }
+
+    /// Set the SEP-41 token representing a tranche's shares (admin only, once per tranche)
+    /// The token must be administered by this pool so deposits and withdrawals can mint and burn
+    pub fn set_share_token(env: Env, admin: Address, tranche: Tranche, share_token: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if storage::get_share_token(&env, tranche).is_some() {
+            panic_with_error!(&env, LiquidityPoolError::ShareTokenAlreadySet);
+        }
+
+        storage::set_share_token(&env, tranche, &share_token);
+    }
+
+    pub fn get_share_token(env: Env, tranche: Tranche) -> Option<Address> {
+        storage::get_share_token(&env, tranche)
+    }
+
+    /// Deposit tokens into a tranche and receive shares of that tranche
+    /// The first deposit into each tranche permanently locks MINIMUM_LIQUIDITY shares in the pool
+    pub fn deposit(env: Env, provider: Address, tranche: Tranche, amount: i128) -> i128 {
+        provider.require_auth();
+
+        let issued = Self::deposit_into(&env, &provider, tranche, amount, &provider);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+
+        issued
+    }
+
+    /// Deposit tokens into a tranche for a fixed lockup term in exchange for boosted yield
+    /// The shares are held by the pool until the position unlocks; boost shares are minted on unlock
+    pub fn deposit_locked(
+        env: Env,
+        provider: Address,
+        tranche: Tranche,
+        amount: i128,
+        term: LockupTerm,
+    ) -> u64 {
+        provider.require_auth();
+
+        let shares = Self::deposit_into(
+            &env,
+            &provider,
+            tranche,
+            amount,
+            &env.current_contract_address(),
+        );
+
+        let (lockup_seconds, boost_bps) = types::lockup_terms(term);
+        let now = env.ledger().timestamp();
+        let position = LockedPosition {
+            position_id: storage::increment_position_counter(&env),
+            provider: provider.clone(),
+            tranche,
+            term,
+            shares,
+            bonus_shares: shares::mul_div_floor(
+                &env,
+                shares,
+                boost_bps as i128 * lockup_seconds as i128,
+                types::BPS_DENOMINATOR * types::SECONDS_PER_YEAR,
+            ),
+            bonus_paid: 0,
+            locked_at: now,
+            unlock_at: now + lockup_seconds,
+            status: PositionStatus::Locked,
+        };
+        storage::write_position(&env, &position);
+
+        events::emit_position_locked(&env, &position);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+
+        position.position_id
+    }
+
+    /// Release the shares of a matured position, plus its boost shares, to the provider
+    /// The boost is paid out of the boost budget, capped at what the budget holds
+    pub fn unlock_position(env: Env, provider: Address, position_id: u64) -> i128 {
+        provider.require_auth();
+
+        let mut position = Self::owned_locked_position(&env, &provider, position_id);
+        if env.ledger().timestamp() < position.unlock_at {
+            panic_with_error!(&env, LiquidityPoolError::PositionStillLocked);
+        }
+
+        let share_token = Self::share_token(&env, position.tranche);
+        token::Client::new(&env, &share_token).transfer(
+            &env.current_contract_address(),
+            &provider,
+            &position.shares,
+        );
+        position.bonus_paid = Self::pay_lockup_bonus(&env, &provider, &position);
+
+        position.status = PositionStatus::Unlocked;
+        storage::write_position(&env, &position);
+
+        events::emit_position_unlocked(
+            &env,
+            &provider,
+            position_id,
+            position.shares,
+            position.bonus_paid,
+        );
+
+        position.shares + position.bonus_paid
+    }
+
+    /// Exit a locked position before it matures
+    /// The boost is forfeited and the early-withdrawal penalty stays in the tranche for the remaining LPs
+    pub fn withdraw_position_early(env: Env, provider: Address, position_id: u64) -> i128 {
+        provider.require_auth();
+
+        let mut position = Self::owned_locked_position(&env, &provider, position_id);
+        if env.ledger().timestamp() >= position.unlock_at {
+            panic_with_error!(&env, LiquidityPoolError::PositionUnlocked);
+        }
+
+        let mut state = storage::get_tranche(&env, position.tranche);
+        let value =
+            shares::amount_for_shares(&env, position.shares, state.total_shares, state.value);
+        let penalty = shares::mul_div_floor(
+            &env,
+            value,
+            types::EARLY_WITHDRAWAL_PENALTY_BPS as i128,
+            types::BPS_DENOMINATOR,
+        );
+        let amount = value - penalty;
+
+        let total_liquidity = storage::get_total_liquidity(&env);
+        if amount > total_liquidity {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientLiquidity);
+        }
+
+        token::Client::new(&env, &Self::share_token(&env, position.tranche))
+            .burn(&env.current_contract_address(), &position.shares);
+        state.total_shares -= position.shares;
+        state.value -= amount;
+        storage::set_tranche(&env, position.tranche, &state);
+        storage::set_total_liquidity(&env, total_liquidity - amount);
+
+        position.status = PositionStatus::Withdrawn;
+        storage::write_position(&env, &position);
+
+        if amount > 0 {
+            token::Client::new(&env, &storage::get_token(&env)).transfer(
+                &env.current_contract_address(),
+                &provider,
+                &amount,
+            );
+        }
+
+        events::emit_position_exited(&env, &provider, position_id, amount, penalty);
+
+        amount
+    }
+
+    /// Add tokens to the budget that pays lockup boosts (e.g. from the protocol treasury)
+    /// The budget is kept apart from the reserve and from LP value until a boost is paid
+    pub fn fund_boost_budget(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let budget = Self::add(&env, storage::get_boost_budget(&env), amount);
+        storage::set_boost_budget(&env, budget);
+
+        events::emit_boost_budget_funded(&env, &from, amount, budget);
+    }
+
+    /// Get the budget left to pay lockup boosts
+    pub fn get_boost_budget(env: Env) -> i128 {
+        storage::get_boost_budget(&env)
+    }
+
+    /// Get a locked position
+    pub fn get_position(env: Env, position_id: u64) -> LockedPosition {
+        storage::read_position(&env, position_id)
+            .unwrap_or_else(|| panic_with_error!(&env, LiquidityPoolError::PositionNotFound))
+    }
+
+    /// Burn shares of a tranche and withdraw the corresponding tokens from the pool
+    /// Limited by the liquidity currently available in the pool
+    pub fn withdraw(env: Env, provider: Address, tranche: Tranche, shares: i128) -> i128 {
+        provider.require_auth();
+
+        if shares <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidWithdrawalAmount);
+        }
+
+        let share_token = token::Client::new(&env, &Self::share_token(&env, tranche));
+        if shares > share_token.balance(&provider) {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientShares);
+        }
+
+        let mut state = storage::get_tranche(&env, tranche);
+        let amount = shares::amount_for_shares(&env, shares, state.total_shares, state.value);
+        if amount == 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidWithdrawalAmount);
+        }
+
+        let total_liquidity = storage::get_total_liquidity(&env);
+        if amount > total_liquidity {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientLiquidity);
+        }
+
+        share_token.burn(&provider, &shares);
+        state.total_shares -= shares;
+        state.value -= amount;
+        storage::set_tranche(&env, tranche, &state);
+        storage::set_total_liquidity(&env, total_liquidity - amount);
+
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &env.current_contract_address(),
+            &provider,
+            &amount,
+        );
+
+        events::emit_liquidity_withdrawn(&env, &provider, tranche, amount, shares);
+
+        amount
+    }
+
+    /// Lock shares in a FIFO withdrawal request, filled as liquidity returns to the pool
+    /// Requests are filled immediately as far as the pool's available liquidity allows.
+    /// At least MIN_WITHDRAWAL_REQUEST_SHARES must be queued; smaller amounts use `withdraw`.
+    pub fn request_withdrawal(env: Env, provider: Address, tranche: Tranche, shares: i128) -> u64 {
+        provider.require_auth();
+
+        if shares <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidWithdrawalAmount);
+        }
+        if shares < types::MIN_WITHDRAWAL_REQUEST_SHARES {
+            panic_with_error!(&env, LiquidityPoolError::WithdrawalRequestTooSmall);
+        }
+
+        let share_token = token::Client::new(&env, &Self::share_token(&env, tranche));
+        if shares > share_token.balance(&provider) {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientShares);
+        }
+        share_token.transfer(&provider, &env.current_contract_address(), &shares);
+
+        let request_id = storage::increment_request_counter(&env);
+        storage::write_request(
+            &env,
+            &WithdrawalRequest {
+                request_id,
+                provider: provider.clone(),
+                tranche,
+                shares_requested: shares,
+                shares_remaining: shares,
+                amount_paid: 0,
+                status: WithdrawalStatus::Pending,
+                created_at: env.ledger().timestamp(),
+            },
+        );
+
+        let mut queue = storage::get_queue(&env);
+        queue.push_back(request_id);
+        storage::set_queue(&env, &queue);
+
+        events::emit_withdrawal_requested(&env, &provider, request_id, shares);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+
+        request_id
+    }
+
+    /// Cancel a pending withdrawal request and unlock its unfilled shares
+    pub fn cancel_withdrawal_request(env: Env, provider: Address, request_id: u64) -> i128 {
+        provider.require_auth();
+
+        let mut request = Self::get_withdrawal_request(env.clone(), request_id);
+        if request.provider != provider {
+            panic_with_error!(&env, LiquidityPoolError::NotRequestOwner);
+        }
+        if request.status != WithdrawalStatus::Pending {
+            panic_with_error!(&env, LiquidityPoolError::RequestNotPending);
+        }
+
+        // Locked shares are held by the pool until the request is filled or cancelled
+        let shares_returned = request.shares_remaining;
+        token::Client::new(&env, &Self::share_token(&env, request.tranche)).transfer(
+            &env.current_contract_address(),
+            &provider,
+            &shares_returned,
+        );
+
+        request.shares_remaining = 0;
+        request.status = WithdrawalStatus::Cancelled;
+        storage::write_request(&env, &request);
+
+        let mut queue = storage::get_queue(&env);
+        if let Some(index) = queue.first_index_of(request_id) {
+            queue.remove(index);
+        }
+        storage::set_queue(&env, &queue);
+
+        events::emit_withdrawal_cancelled(&env, &provider, request_id, shares_returned);
+
+        shares_returned
+    }
+
+    /// Get a withdrawal request
+    pub fn get_withdrawal_request(env: Env, request_id: u64) -> WithdrawalRequest {
+        storage::read_request(&env, request_id)
+            .unwrap_or_else(|| panic_with_error!(&env, LiquidityPoolError::RequestNotFound))
+    }
+
+    /// Fill up to `max` queued withdrawal requests from available liquidity (anyone may call)
+    /// Pool operations only fill MAX_QUEUE_FILLS_PER_CALL requests each, so a long queue is
+    /// drained in bounded batches. Returns the number of requests processed.
+    pub fn process_queue(env: Env, max: u32) -> u32 {
+        Self::process_withdrawal_queue(&env, max)
+    }
+
+    /// Get the IDs of pending withdrawal requests, oldest first
+    pub fn get_withdrawal_queue(env: Env) -> Vec<u64> {
+        storage::get_queue(&env)
+    }
+
+    /// Disburse loan principal to the CreditLine, which pays out the merchant (CreditLine only)
+    /// The principal stays part of the pool value as outstanding loans. Fails if the
+    /// borrower, merchant or merchant category would exceed its concentration limit.
+    pub fn fund_loan(
+        env: Env,
+        loan_id: u64,
+        amount: i128,
+        borrower: Address,
+        merchant: Address,
+        category: Symbol,
+    ) {
+        let creditline = access::require_creditline(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+
+        let total_liquidity = storage::get_total_liquidity(&env);
+        if amount > total_liquidity {
+            panic_with_error!(&env, LiquidityPoolError::InsufficientLiquidity);
+        }
+
+        if let Some(error) =
+            Self::concentration_breach(&env, &borrower, &merchant, &category, amount)
+        {
+            panic_with_error!(&env, error);
+        }
+
+        let borrower_exposure = Self::add(
+            &env,
+            storage::get_borrower_exposure(&env, &borrower),
+            amount,
+        );
+        let merchant_exposure = Self::add(
+            &env,
+            storage::get_merchant_exposure(&env, &merchant),
+            amount,
+        );
+        let category_exposure = Self::add(
+            &env,
+            storage::get_category_exposure(&env, &category),
+            amount,
+        );
+        storage::set_borrower_exposure(&env, &borrower, borrower_exposure);
+        storage::set_merchant_exposure(&env, &merchant, merchant_exposure);
+        storage::set_category_exposure(&env, &category, category_exposure);
+
+        let principal = storage::read_loan_exposure(&env, loan_id)
+            .map(|exposure| exposure.principal)
+            .unwrap_or(0);
+        storage::write_loan_exposure(
+            &env,
+            loan_id,
+            &LoanExposure {
+                borrower,
+                merchant: merchant.clone(),
+                category,
+                principal: Self::add(&env, principal, amount),
+            },
+        );
+
+        let total_loaned = Self::add(&env, storage::get_total_loaned(&env), amount);
+        storage::set_total_liquidity(&env, total_liquidity - amount);
+        storage::set_total_loaned(&env, total_loaned);
+
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &env.current_contract_address(),
+            &creditline,
+            &amount,
+        );
+
+        events::emit_loan_funded(&env, &merchant, amount, total_loaned);
+    }
+
+    /// Account for funds returned by the CreditLine (CreditLine only)
+    /// The CreditLine transfers the tokens to the pool before calling. `principal` repays
+    /// outstanding loans; `income` (interest, fees, surplus) funds the reserve per the reserve
+    /// factor and the rest is split between the tranches.
+    pub fn receive_repayment(env: Env, loan_id: u64, principal: i128, income: i128) {
+        access::require_creditline(&env);
+
+        let total_loaned = storage::get_total_loaned(&env);
+        if principal < 0 || income < 0 || principal > total_loaned {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+        Self::reduce_exposure(&env, loan_id, principal);
+
+        let reserve_income = Self::fund_reserve(&env, income);
+        let lp_income = income - reserve_income;
+
+        let total_loaned = total_loaned - principal;
+        storage::set_total_loaned(&env, total_loaned);
+        storage::set_total_liquidity(
+            &env,
+            Self::add(
+                &env,
+                storage::get_total_liquidity(&env),
+                Self::add(&env, principal, lp_income),
+            ),
+        );
+        Self::distribute_income(&env, lp_income);
+
+        events::emit_repayment_received(&env, principal, income, total_loaned);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+    }
+
+    /// Write off defaulted principal that will not be repaid (CreditLine only)
+    /// The reserve covers the loss first by replacing the principal with cash; any remainder
+    /// hits the junior tranche and the senior tranche only once junior is exhausted.
+    /// A loss larger than the reserve and both tranches together never fails the default:
+    /// the tranches are wiped out and the shortfall is recorded as uncovered loss.
+    pub fn recognize_loss(env: Env, loan_id: u64, amount: i128) {
+        access::require_creditline(&env);
+
+        let total_loaned = storage::get_total_loaned(&env);
+        if amount <= 0 || amount > total_loaned {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+        Self::reduce_exposure(&env, loan_id, amount);
+        storage::set_total_loaned(&env, total_loaned - amount);
+
+        let reserve = storage::get_reserve(&env);
+        let reserve_loss = amount.min(reserve);
+        storage::set_reserve(&env, reserve - reserve_loss);
+        storage::set_total_liquidity(
+            &env,
+            Self::add(&env, storage::get_total_liquidity(&env), reserve_loss),
+        );
+
+        let mut junior = storage::get_tranche(&env, Tranche::Junior);
+        let junior_loss = (amount - reserve_loss).min(junior.value);
+        junior.value -= junior_loss;
+        storage::set_tranche(&env, Tranche::Junior, &junior);
+
+        let mut senior = storage::get_tranche(&env, Tranche::Senior);
+        let senior_loss = (amount - reserve_loss - junior_loss).min(senior.value);
+        senior.value -= senior_loss;
+        storage::set_tranche(&env, Tranche::Senior, &senior);
+
+        events::emit_loss_recognized(&env, amount, reserve_loss, junior_loss, senior_loss);
+
+        let shortfall = amount - reserve_loss - junior_loss - senior_loss;
+        if shortfall > 0 {
+            let uncovered_loss = Self::add(&env, storage::get_uncovered_loss(&env), shortfall);
+            storage::set_uncovered_loss(&env, uncovered_loss);
+            events::emit_loss_uncovered(&env, shortfall, uncovered_loss);
+        }
+
+        if reserve_loss > 0 {
+            Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+        }
+    }
+
+    /// Account for funds recovered on written-off loans (CreditLine only)
+    /// The CreditLine transfers the tokens to the pool before calling. Recoveries are
+    /// treated as income: they fund the reserve per the reserve factor and the rest
+    /// is split between the tranches.
+    pub fn receive_recovery(env: Env, amount: i128) {
+        access::require_creditline(&env);
+
+        if amount <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+
+        let reserve_income = Self::fund_reserve(&env, amount);
+        let lp_income = amount - reserve_income;
+        storage::set_total_liquidity(
+            &env,
+            Self::add(&env, storage::get_total_liquidity(&env), lp_income),
+        );
+        Self::distribute_income(&env, lp_income);
+
+        events::emit_recovery_received(&env, amount);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+    }
+
+    /// Pay income into the pool from any source (e.g. slashed merchant bonds)
+    /// The payer's tokens are pulled into the pool and booked like a recovery: the reserve
+    /// takes its factor and the rest is split between the tranches.
+    pub fn receive_income(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+
+        if amount <= 0 {
+            panic_with_error!(&env, LiquidityPoolError::InvalidAmount);
+        }
+
+        token::Client::new(&env, &storage::get_token(&env)).transfer(
+            &from,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        let reserve_income = Self::fund_reserve(&env, amount);
+        let lp_income = amount - reserve_income;
+        storage::set_total_liquidity(
+            &env,
+            Self::add(&env, storage::get_total_liquidity(&env), lp_income),
+        );
+        Self::distribute_income(&env, lp_income);
+
+        events::emit_income_received(&env, &from, amount);
+
+        Self::process_withdrawal_queue(&env, types::MAX_QUEUE_FILLS_PER_CALL);
+    }
+
+    /// Check whether funding `amount` would keep the borrower, merchant and merchant category
+    /// within their concentration limits, without funding anything
+    pub fn check_concentration(
+        env: Env,
+        borrower: Address,
+        merchant: Address,
+        category: Symbol,
+        amount: i128,
+    ) -> bool {
+        Self::concentration_breach(&env, &borrower, &merchant, &category, amount).is_none()
+    }
+
+    /// Get the outstanding principal the pool has funded for a loan
+    pub fn get_loan_exposure(env: Env, loan_id: u64) -> Option<LoanExposure> {
+        storage::read_loan_exposure(&env, loan_id)
+    }
+
+    /// Get the outstanding principal lent to a borrower
+    pub fn get_borrower_exposure(env: Env, borrower: Address) -> i128 {
+        storage::get_borrower_exposure(&env, &borrower)
+    }
+
+    /// Get the outstanding principal disbursed to a merchant
+    pub fn get_merchant_exposure(env: Env, merchant: Address) -> i128 {
+        storage::get_merchant_exposure(&env, &merchant)
+    }
+
+    /// Get the outstanding principal disbursed to merchants of a category
+    pub fn get_category_exposure(env: Env, category: Symbol) -> i128 {
+        storage::get_category_exposure(&env, &category)
+    }
+
+    /// Get the shares a provider holds in a tranche
+    pub fn get_shares(env: Env, provider: Address, tranche: Tranche) -> i128 {
+        token::Client::new(&env, &Self::share_token(&env, tranche)).balance(&provider)
+    }
+
+    /// Get a tranche's total shares (including the locked minimum liquidity) and value
+    pub fn get_tranche(env: Env, tranche: Tranche) -> TrancheState {
+        storage::get_tranche(&env, tranche)
+    }
+
+    /// Get the total value owned by share holders (available liquidity plus outstanding loans)
+    pub fn get_total_pool_value(env: Env) -> i128 {
+        Self::add(
+            &env,
+            storage::get_total_liquidity(&env),
+            storage::get_total_loaned(&env),
+        )
+    }
+
+    /// Get the written-off principal that exceeded the reserve and both tranches
+    pub fn get_uncovered_loss(env: Env) -> i128 {
+        storage::get_uncovered_loss(&env)
+    }
+
+    /// Get the liquidity available for withdrawals and new loans
+    pub fn get_available_liquidity(env: Env) -> i128 {
+        storage::get_total_liquidity(&env)
+    }
+
+    /// Get the principal currently lent out
+    pub fn get_total_loaned(env: Env) -> i128 {
+        storage::get_total_loaned(&env)
+    }
+
+    /// Get the share of the pool value currently lent out, in basis points
+    pub fn get_utilization(env: Env) -> u32 {
+        rates::utilization_bps(
+            storage::get_total_loaned(&env),
+            Self::get_total_pool_value(env.clone()),
+        )
+    }
+
+    /// Get the annual borrow rate for new loans at the current utilization, in basis points
+    pub fn get_current_rate(env: Env) -> u32 {
+        let utilization = Self::get_utilization(env.clone());
+        rates::borrow_rate_bps(&storage::get_rate_model(&env), utilization)
+    }
+
+    /// Get the reserve balance available to absorb default losses
+    pub fn get_reserve_balance(env: Env) -> i128 {
+        storage::get_reserve(&env)
+    }
+
+    /// Get the reserve balance relative to outstanding loans, in basis points
+    /// Returns 0 when nothing is lent out
+    pub fn get_coverage_ratio(env: Env) -> u32 {
+        let total_loaned = storage::get_total_loaned(&env);
+        if total_loaned == 0 {
+            return 0;
+        }
+
+        let ratio = shares::mul_div_floor(
+            &env,
+            storage::get_reserve(&env),
+            types::BPS_DENOMINATOR,
+            total_loaned,
+        );
+        u32::try_from(ratio).unwrap_or(u32::MAX)
+    }
+
+    /// Get the value of one share of a tranche, scaled by SHARE_VALUE_PRECISION
+    pub fn get_share_value(env: Env, tranche: Tranche) -> i128 {
+        let state = storage::get_tranche(&env, tranche);
+        if state.total_shares == 0 {
+            return SHARE_VALUE_PRECISION;
+        }
+
+        shares::mul_div_floor(&env, state.value, SHARE_VALUE_PRECISION, state.total_shares)
+    }
+
+    /// Move the reserve factor's share of pool income into the reserve, returning the amount diverted
+    fn fund_reserve(env: &Env, income: i128) -> i128 {
+        let reserve_income = shares::mul_div_floor(
+            env,
+            income,
+            storage::get_reserve_factor_bps(env) as i128,
+            types::BPS_DENOMINATOR,
+        );
+        if reserve_income == 0 {
+            return 0;
+        }
+
+        let reserve = Self::add(env, storage::get_reserve(env), reserve_income);
+        storage::set_reserve(env, reserve);
+        events::emit_reserve_funded(env, reserve_income, reserve);
+
+        reserve_income
+    }
+
+    /// Credit pool income to the tranches per the interest split
+    /// A tranche without shares cannot earn, so all income goes to the other one
+    fn distribute_income(env: &Env, income: i128) {
+        if income == 0 {
+            return;
+        }
+
+        let mut senior = storage::get_tranche(env, Tranche::Senior);
+        let mut junior = storage::get_tranche(env, Tranche::Junior);
+
+        let senior_income = if senior.total_shares == 0 {
+            0
+        } else if junior.total_shares == 0 {
+            income
+        } else {
+            shares::mul_div_floor(
+                env,
+                income,
+                storage::get_senior_interest_bps(env) as i128,
+                types::BPS_DENOMINATOR,
+            )
+        };
+
+        senior.value = Self::add(env, senior.value, senior_income);
+        junior.value = Self::add(env, junior.value, income - senior_income);
+        storage::set_tranche(env, Tranche::Senior, &senior);
+        storage::set_tranche(env, Tranche::Junior, &junior);
+    }
+
+    /// Fill up to `max` pending withdrawal requests in order from the available liquidity
+    /// Shares are redeemed at the current share value; the last request served may be filled partially
+    fn process_withdrawal_queue(env: &Env, max: u32) -> u32 {
+        let mut queue = storage::get_queue(env);
+        let token = token::Client::new(env, &storage::get_token(env));
+        let mut processed = 0;
+
+        while let Some(request_id) = queue.first() {
+            if processed == max {
+                break;
+            }
+            let total_liquidity = storage::get_total_liquidity(env);
+            if total_liquidity == 0 {
+                break;
+            }
+            processed += 1;
+
+            let mut request = match storage::read_request(env, request_id) {
+                Some(request) => request,
+                None => {
+                    queue.pop_front();
+                    continue;
+                }
+            };
+
+            let mut state = storage::get_tranche(env, request.tranche);
+            let value = shares::amount_for_shares(
+                env,
+                request.shares_remaining,
+                state.total_shares,
+                state.value,
+            );
+
+            let (amount, shares_burned) = if value <= total_liquidity {
+                (value, request.shares_remaining)
+            } else {
+                let shares_burned = shares::shares_for_amount(
+                    env,
+                    total_liquidity,
+                    state.total_shares,
+                    state.value,
+                )
+                .min(request.shares_remaining);
+                (total_liquidity, shares_burned)
+            };
+
+            request.shares_remaining -= shares_burned;
+            request.amount_paid = Self::add(env, request.amount_paid, amount);
+            if request.shares_remaining == 0 {
+                request.status = WithdrawalStatus::Filled;
+                queue.pop_front();
+            }
+            storage::write_request(env, &request);
+
+            state.total_shares -= shares_burned;
+            state.value -= amount;
+            storage::set_tranche(env, request.tranche, &state);
+            storage::set_total_liquidity(env, total_liquidity - amount);
+
+            if shares_burned > 0 {
+                token::Client::new(env, &Self::share_token(env, request.tranche))
+                    .burn(&env.current_contract_address(), &shares_burned);
+            }
+            if amount > 0 {
+                token.transfer(&env.current_contract_address(), &request.provider, &amount);
+            }
+
+            events::emit_withdrawal_filled(
+                env,
+                &request.provider,
+                request_id,
+                amount,
+                shares_burned,
+                request.shares_remaining,
+            );
+
+            if request.status == WithdrawalStatus::Pending {
+                break;
+            }
+        }
+
+        storage::set_queue(env, &queue);
+
+        processed
+    }
+
+    /// Mint shares of a tranche for deposited tokens to `recipient`, returning the shares issued
+    /// The first deposit into each tranche permanently locks MINIMUM_LIQUIDITY shares in the pool
+    fn deposit_into(
+        env: &Env,
+        provider: &Address,
+        tranche: Tranche,
+        amount: i128,
+        recipient: &Address,
+    ) -> i128 {
+        if amount <= 0 {
+            panic_with_error!(env, LiquidityPoolError::InvalidDepositAmount);
+        }
+
+        let share_token = token::StellarAssetClient::new(env, &Self::share_token(env, tranche));
+        let mut state = storage::get_tranche(env, tranche);
+        let minted = shares::shares_for_deposit(env, amount, state.total_shares, state.value);
+
+        let issued = if state.total_shares == 0 {
+            if minted <= MINIMUM_LIQUIDITY {
+                panic_with_error!(env, LiquidityPoolError::DepositTooSmall);
+            }
+            share_token.mint(&env.current_contract_address(), &MINIMUM_LIQUIDITY);
+            minted - MINIMUM_LIQUIDITY
+        } else {
+            if minted == 0 {
+                panic_with_error!(env, LiquidityPoolError::DepositTooSmall);
+            }
+            minted
+        };
+
+        token::Client::new(env, &storage::get_token(env)).transfer(
+            provider,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        share_token.mint(recipient, &issued);
+        state.total_shares = Self::add(env, state.total_shares, minted);
+        state.value = Self::add(env, state.value, amount);
+        storage::set_tranche(env, tranche, &state);
+        storage::set_total_liquidity(
+            env,
+            Self::add(env, storage::get_total_liquidity(env), amount),
+        );
+
+        events::emit_liquidity_deposited(env, provider, tranche, amount, issued);
+
+        issued
+    }
+
+    /// Mint the boost of a matured position, funded from the boost budget at the current share value
+    /// The boost is capped at what the budget holds so the other LPs are never diluted.
+    /// Returns the boost shares actually minted.
+    fn pay_lockup_bonus(env: &Env, provider: &Address, position: &LockedPosition) -> i128 {
+        if position.bonus_shares == 0 {
+            return 0;
+        }
+
+        let mut state = storage::get_tranche(env, position.tranche);
+        let owed =
+            shares::amount_for_shares(env, position.bonus_shares, state.total_shares, state.value);
+        let budget = storage::get_boost_budget(env);
+        let paid = owed.min(budget);
+        let minted = shares::shares_for_deposit(env, paid, state.total_shares, state.value);
+        if minted == 0 {
+            return 0;
+        }
+
+        storage::set_boost_budget(env, budget - paid);
+        storage::set_total_liquidity(env, Self::add(env, storage::get_total_liquidity(env), paid));
+        state.total_shares = Self::add(env, state.total_shares, minted);
+        state.value = Self::add(env, state.value, paid);
+        storage::set_tranche(env, position.tranche, &state);
+
+        token::StellarAssetClient::new(env, &Self::share_token(env, position.tranche))
+            .mint(provider, &minted);
+
+        minted
+    }
+
+    /// Load a position owned by `provider` that is still locked
+    fn owned_locked_position(env: &Env, provider: &Address, position_id: u64) -> LockedPosition {
+        let position = storage::read_position(env, position_id)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::PositionNotFound));
+        if &position.provider != provider {
+            panic_with_error!(env, LiquidityPoolError::NotPositionOwner);
+        }
+        if position.status != PositionStatus::Locked {
+            panic_with_error!(env, LiquidityPoolError::PositionNotLocked);
+        }
+        position
+    }
+
+    /// Get the concentration limit that funding `amount` would exceed, if any
+    fn concentration_breach(
+        env: &Env,
+        borrower: &Address,
+        merchant: &Address,
+        category: &Symbol,
+        amount: i128,
+    ) -> Option<LiquidityPoolError> {
+        let pool_value = Self::add(
+            env,
+            storage::get_total_liquidity(env),
+            storage::get_total_loaned(env),
+        );
+        let limits = storage::get_concentration_limits(env);
+
+        let borrower_exposure =
+            Self::add(env, storage::get_borrower_exposure(env, borrower), amount);
+        if Self::exceeds_limit(env, borrower_exposure, limits.max_borrower_bps, pool_value) {
+            return Some(LiquidityPoolError::BorrowerLimitExceeded);
+        }
+        let merchant_exposure =
+            Self::add(env, storage::get_merchant_exposure(env, merchant), amount);
+        if Self::exceeds_limit(env, merchant_exposure, limits.max_merchant_bps, pool_value) {
+            return Some(LiquidityPoolError::MerchantLimitExceeded);
+        }
+        let category_exposure =
+            Self::add(env, storage::get_category_exposure(env, category), amount);
+        if Self::exceeds_limit(env, category_exposure, limits.max_category_bps, pool_value) {
+            return Some(LiquidityPoolError::CategoryLimitExceeded);
+        }
+
+        None
+    }
+
+    /// Whether an exposure is above `limit_bps` of the pool value
+    fn exceeds_limit(env: &Env, exposure: i128, limit_bps: u32, pool_value: i128) -> bool {
+        let scaled_exposure = exposure
+            .checked_mul(types::BPS_DENOMINATOR)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::OverflowError));
+        let cap = pool_value
+            .checked_mul(limit_bps as i128)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::OverflowError));
+        scaled_exposure > cap
+    }
+
+    /// Release principal repaid or written off from a loan's exposure and its aggregates
+    fn reduce_exposure(env: &Env, loan_id: u64, amount: i128) {
+        if amount == 0 {
+            return;
+        }
+
+        let mut exposure = storage::read_loan_exposure(env, loan_id)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::InvalidAmount));
+        if amount > exposure.principal {
+            panic_with_error!(env, LiquidityPoolError::InvalidAmount);
+        }
+
+        storage::set_borrower_exposure(
+            env,
+            &exposure.borrower,
+            storage::get_borrower_exposure(env, &exposure.borrower) - amount,
+        );
+        storage::set_merchant_exposure(
+            env,
+            &exposure.merchant,
+            storage::get_merchant_exposure(env, &exposure.merchant) - amount,
+        );
+        storage::set_category_exposure(
+            env,
+            &exposure.category,
+            storage::get_category_exposure(env, &exposure.category) - amount,
+        );
+
+        exposure.principal -= amount;
+        storage::write_loan_exposure(env, loan_id, &exposure);
+    }
+
+    fn share_token(env: &Env, tranche: Tranche) -> Address {
+        storage::get_share_token(env, tranche)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::ShareTokenNotSet))
+    }
+
+    fn add(env: &Env, value: i128, amount: i128) -> i128 {
+        value
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::OverflowError))
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use crate::types::{RateModel, BPS_DENOMINATOR};
+
+/// Share of the pool value lent out, in basis points (0 for an empty pool)
+pub fn utilization_bps(total_loaned: i128, pool_value: i128) -> u32 {
+    if pool_value <= 0 || total_loaned <= 0 {
+        return 0;
+    }
+
+    let utilization = total_loaned
+        .saturating_mul(BPS_DENOMINATOR)
+        .checked_div(pool_value)
+        .unwrap_or(BPS_DENOMINATOR);
+
+    utilization.min(BPS_DENOMINATOR) as u32
+}
+
+/// Annual borrow rate in basis points for a utilization, following the kinked curve:
+/// base + slope1 × u / optimal below the kink, base + slope1 + slope2 × excess above it
+pub fn borrow_rate_bps(model: &RateModel, utilization_bps: u32) -> u32 {
+    let utilization = utilization_bps as u64;
+    let optimal = model.optimal_utilization_bps as u64;
+
+    let rate = if utilization <= optimal {
+        model.base_rate_bps as u64 + model.slope1_bps as u64 * utilization / optimal
+    } else {
+        let excess = utilization - optimal;
+        let max_excess = BPS_DENOMINATOR as u64 - optimal;
+        model.base_rate_bps as u64
+            + model.slope1_bps as u64
+            + model.slope2_bps as u64 * excess / max_excess
+    };
+
+    rate.min(u32::MAX as u64) as u32
+}
@@ -0,0 +1,51 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::errors::LiquidityPoolError;
+
+/// Shares issued for a deposit: (amount × total_shares) / total_pool_value
+/// The first deposit is issued 1:1. Rounds down, in the pool's favour.
+/// A pool wiped out by losses still has shares outstanding; it is priced as if worth one
+/// unit, so the new deposit dilutes the worthless shares to dust instead of being blocked.
+pub fn shares_for_deposit(env: &Env, amount: i128, total_shares: i128, pool_value: i128) -> i128 {
+    if total_shares == 0 {
+        return amount;
+    }
+
+    mul_div_floor(env, amount, total_shares, pool_value.max(1))
+}
+
+/// Amount returned for burned shares: (shares × total_pool_value) / total_shares
+/// Rounds down, in the pool's favour.
+pub fn amount_for_shares(env: &Env, shares: i128, total_shares: i128, pool_value: i128) -> i128 {
+    if total_shares <= 0 {
+        panic_with_error!(env, LiquidityPoolError::PoolEmpty);
+    }
+
+    mul_div_floor(env, shares, pool_value, total_shares)
+}
+
+/// (a × b) / denominator with overflow checks, rounding down
+pub fn mul_div_floor(env: &Env, a: i128, b: i128, denominator: i128) -> i128 {
+    a.checked_mul(b)
+        .and_then(|product| product.checked_div(denominator))
+        .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::OverflowError))
+}
+
+/// Shares to burn for a partial payout: (amount × total_shares) / total_pool_value
+/// Rounds up, in the pool's favour.
+pub fn shares_for_amount(env: &Env, amount: i128, total_shares: i128, pool_value: i128) -> i128 {
+    if pool_value <= 0 {
+        panic_with_error!(env, LiquidityPoolError::PoolEmpty);
+    }
+
+    let product = amount
+        .checked_mul(total_shares)
+        .unwrap_or_else(|| panic_with_error!(env, LiquidityPoolError::OverflowError));
+
+    let shares = product / pool_value;
+    if product % pool_value == 0 {
+        shares
+    } else {
+        shares + 1
+    }
+}
@@ -0,0 +1,407 @@
+use soroban_sdk::{symbol_short, Address, Env, IntoVal, Map, Symbol, TryFromVal, Val, Vec};
+
+use crate::types::{
+    ConcentrationLimits, LoanExposure, LockedPosition, RateModel, ReserveWithdrawal, Tranche,
+    TrancheState, WithdrawalRequest, BPS_DENOMINATOR, DEFAULT_BASE_RATE_BPS,
+    DEFAULT_OPTIMAL_UTILIZATION_BPS, DEFAULT_RESERVE_FACTOR_BPS, DEFAULT_SENIOR_INTEREST_BPS,
+    DEFAULT_SLOPE1_BPS, DEFAULT_SLOPE2_BPS,
+};
+
+// Storage keys for the liquidity pool contract
+pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+pub const TOKEN_KEY: Symbol = symbol_short!("TOKEN");
+pub const SENIOR_TOKEN: Symbol = symbol_short!("SRTOKEN");
+pub const JUNIOR_TOKEN: Symbol = symbol_short!("JRTOKEN");
+pub const SENIOR_STATE: Symbol = symbol_short!("SRSTATE");
+pub const JUNIOR_STATE: Symbol = symbol_short!("JRSTATE");
+pub const INTEREST_SPLIT: Symbol = symbol_short!("INTSPLIT");
+pub const TOTAL_LIQUIDITY: Symbol = symbol_short!("TOTLIQ");
+pub const TOTAL_LOANED: Symbol = symbol_short!("TOTLOAN");
+pub const CREDITLINE: Symbol = symbol_short!("CREDITLN");
+pub const RATE_MODEL: Symbol = symbol_short!("RATEMDL");
+pub const REQUEST_COUNTER: Symbol = symbol_short!("WREQCNT");
+pub const REQUESTS_MAP: Symbol = symbol_short!("WREQS");
+pub const WITHDRAWAL_QUEUE: Symbol = symbol_short!("WQUEUE");
+pub const RESERVE_FACTOR: Symbol = symbol_short!("RSVFACT");
+pub const RESERVE_BALANCE: Symbol = symbol_short!("RESERVE");
+pub const RESERVE_WITHDRAWAL: Symbol = symbol_short!("RSVWDRAW");
+pub const BOOST_BUDGET: Symbol = symbol_short!("BOOSTBGT");
+pub const UNCOVERED_LOSS: Symbol = symbol_short!("UNCVLOSS");
+pub const POSITION_COUNTER: Symbol = symbol_short!("POSCNT");
+pub const POSITIONS_MAP: Symbol = symbol_short!("POSITIONS");
+pub const CONCENTRATION_LIMITS: Symbol = symbol_short!("CONCLIM");
+pub const LOAN_EXPOSURES: Symbol = symbol_short!("LOANEXP");
+pub const BORROWER_EXPOSURE: Symbol = symbol_short!("BORREXP");
+pub const MERCHANT_EXPOSURE: Symbol = symbol_short!("MRCHEXP");
+pub const CATEGORY_EXPOSURE: Symbol = symbol_short!("CATEXP");
+
+/// Get the admin address from storage
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ADMIN_KEY)
+        .unwrap_or_else(|| panic!("Admin not set"))
+}
+
+/// Set the admin address in storage
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN_KEY, admin);
+}
+
+/// Get the SEP-41 token backing the pool
+pub fn get_token(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&TOKEN_KEY)
+        .unwrap_or_else(|| panic!("Token not set"))
+}
+
+/// Set the SEP-41 token backing the pool
+pub fn set_token(env: &Env, token: &Address) {
+    env.storage().instance().set(&TOKEN_KEY, token);
+}
+
+/// Storage key of a tranche's share token
+fn share_token_key(tranche: Tranche) -> Symbol {
+    match tranche {
+        Tranche::Senior => SENIOR_TOKEN,
+        Tranche::Junior => JUNIOR_TOKEN,
+    }
+}
+
+/// Storage key of a tranche's share accounting
+fn tranche_key(tranche: Tranche) -> Symbol {
+    match tranche {
+        Tranche::Senior => SENIOR_STATE,
+        Tranche::Junior => JUNIOR_STATE,
+    }
+}
+
+/// Get the SEP-41 token representing a tranche's shares
+pub fn get_share_token(env: &Env, tranche: Tranche) -> Option<Address> {
+    env.storage().instance().get(&share_token_key(tranche))
+}
+
+/// Set the SEP-41 token representing a tranche's shares
+pub fn set_share_token(env: &Env, tranche: Tranche, token: &Address) {
+    env.storage()
+        .instance()
+        .set(&share_token_key(tranche), token);
+}
+
+/// Get a tranche's total shares and value
+pub fn get_tranche(env: &Env, tranche: Tranche) -> TrancheState {
+    env.storage()
+        .instance()
+        .get(&tranche_key(tranche))
+        .unwrap_or_default()
+}
+
+/// Set a tranche's total shares and value
+pub fn set_tranche(env: &Env, tranche: Tranche, state: &TrancheState) {
+    env.storage().instance().set(&tranche_key(tranche), state);
+}
+
+/// Get the share of pool income credited to the senior tranche, in basis points
+pub fn get_senior_interest_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&INTEREST_SPLIT)
+        .unwrap_or(DEFAULT_SENIOR_INTEREST_BPS)
+}
+
+/// Set the share of pool income credited to the senior tranche
+pub fn set_senior_interest_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&INTEREST_SPLIT, &bps);
+}
+
+/// Get the liquidity held by the pool (tracked internally, so token donations
+/// cannot move the share price)
+pub fn get_total_liquidity(env: &Env) -> i128 {
+    env.storage().instance().get(&TOTAL_LIQUIDITY).unwrap_or(0)
+}
+
+/// Set the liquidity held by the pool
+pub fn set_total_liquidity(env: &Env, amount: i128) {
+    env.storage().instance().set(&TOTAL_LIQUIDITY, &amount);
+}
+
+/// Get the principal currently lent out to borrowers
+pub fn get_total_loaned(env: &Env) -> i128 {
+    env.storage().instance().get(&TOTAL_LOANED).unwrap_or(0)
+}
+
+/// Set the principal currently lent out to borrowers
+pub fn set_total_loaned(env: &Env, amount: i128) {
+    env.storage().instance().set(&TOTAL_LOANED, &amount);
+}
+
+/// Get the CreditLine contract allowed to draw loan funding
+pub fn get_creditline(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CREDITLINE)
+}
+
+/// Set the CreditLine contract address
+pub fn set_creditline(env: &Env, address: &Address) {
+    env.storage().instance().set(&CREDITLINE, address);
+}
+
+/// Get the interest rate model (defaults if not configured)
+pub fn get_rate_model(env: &Env) -> RateModel {
+    env.storage()
+        .instance()
+        .get(&RATE_MODEL)
+        .unwrap_or(RateModel {
+            base_rate_bps: DEFAULT_BASE_RATE_BPS,
+            slope1_bps: DEFAULT_SLOPE1_BPS,
+            slope2_bps: DEFAULT_SLOPE2_BPS,
+            optimal_utilization_bps: DEFAULT_OPTIMAL_UTILIZATION_BPS,
+        })
+}
+
+/// Set the interest rate model
+pub fn set_rate_model(env: &Env, model: &RateModel) {
+    env.storage().instance().set(&RATE_MODEL, model);
+}
+
+/// Increment and return the next withdrawal request ID
+pub fn increment_request_counter(env: &Env) -> u64 {
+    let current: u64 = env.storage().instance().get(&REQUEST_COUNTER).unwrap_or(0);
+    let next = current.checked_add(1).expect("Request counter overflow");
+    env.storage().instance().set(&REQUEST_COUNTER, &next);
+    next
+}
+
+/// Read a withdrawal request from storage
+pub fn read_request(env: &Env, request_id: u64) -> Option<WithdrawalRequest> {
+    let requests: Map<u64, WithdrawalRequest> = env
+        .storage()
+        .instance()
+        .get(&REQUESTS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    requests.get(request_id)
+}
+
+/// Write a withdrawal request to storage
+pub fn write_request(env: &Env, request: &WithdrawalRequest) {
+    let mut requests: Map<u64, WithdrawalRequest> = env
+        .storage()
+        .instance()
+        .get(&REQUESTS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    requests.set(request.request_id, request.clone());
+    env.storage().instance().set(&REQUESTS_MAP, &requests);
+}
+
+/// Get the IDs of pending withdrawal requests, oldest first
+pub fn get_queue(env: &Env) -> Vec<u64> {
+    env.storage()
+        .instance()
+        .get(&WITHDRAWAL_QUEUE)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Set the IDs of pending withdrawal requests
+pub fn set_queue(env: &Env, queue: &Vec<u64>) {
+    env.storage().instance().set(&WITHDRAWAL_QUEUE, queue);
+}
+
+/// Get the share of pool income diverted to the reserve, in basis points
+pub fn get_reserve_factor_bps(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&RESERVE_FACTOR)
+        .unwrap_or(DEFAULT_RESERVE_FACTOR_BPS)
+}
+
+/// Set the share of pool income diverted to the reserve
+pub fn set_reserve_factor_bps(env: &Env, bps: u32) {
+    env.storage().instance().set(&RESERVE_FACTOR, &bps);
+}
+
+/// Get the reserve balance (held by the pool but not owned by share holders)
+pub fn get_reserve(env: &Env) -> i128 {
+    env.storage().instance().get(&RESERVE_BALANCE).unwrap_or(0)
+}
+
+/// Set the reserve balance
+pub fn set_reserve(env: &Env, amount: i128) {
+    env.storage().instance().set(&RESERVE_BALANCE, &amount);
+}
+
+/// Get the budget funding lockup boosts (held by the pool but not owned by share holders)
+pub fn get_boost_budget(env: &Env) -> i128 {
+    env.storage().instance().get(&BOOST_BUDGET).unwrap_or(0)
+}
+
+/// Set the budget funding lockup boosts
+pub fn set_boost_budget(env: &Env, amount: i128) {
+    env.storage().instance().set(&BOOST_BUDGET, &amount);
+}
+
+/// Get the written-off principal the reserve and tranches could not absorb
+pub fn get_uncovered_loss(env: &Env) -> i128 {
+    env.storage().instance().get(&UNCOVERED_LOSS).unwrap_or(0)
+}
+
+/// Set the written-off principal the reserve and tranches could not absorb
+pub fn set_uncovered_loss(env: &Env, amount: i128) {
+    env.storage().instance().set(&UNCOVERED_LOSS, &amount);
+}
+
+/// Get the scheduled reserve withdrawal, if any
+pub fn get_reserve_withdrawal(env: &Env) -> Option<ReserveWithdrawal> {
+    env.storage().instance().get(&RESERVE_WITHDRAWAL)
+}
+
+/// Set the scheduled reserve withdrawal
+pub fn set_reserve_withdrawal(env: &Env, withdrawal: &ReserveWithdrawal) {
+    env.storage()
+        .instance()
+        .set(&RESERVE_WITHDRAWAL, withdrawal);
+}
+
+/// Remove the scheduled reserve withdrawal
+pub fn remove_reserve_withdrawal(env: &Env) {
+    env.storage().instance().remove(&RESERVE_WITHDRAWAL);
+}
+
+/// Increment and return the next locked position ID
+pub fn increment_position_counter(env: &Env) -> u64 {
+    let current: u64 = env.storage().instance().get(&POSITION_COUNTER).unwrap_or(0);
+    let next = current.checked_add(1).expect("Position counter overflow");
+    env.storage().instance().set(&POSITION_COUNTER, &next);
+    next
+}
+
+/// Read a locked position from storage
+pub fn read_position(env: &Env, position_id: u64) -> Option<LockedPosition> {
+    let positions: Map<u64, LockedPosition> = env
+        .storage()
+        .instance()
+        .get(&POSITIONS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    positions.get(position_id)
+}
+
+/// Write a locked position to storage
+pub fn write_position(env: &Env, position: &LockedPosition) {
+    let mut positions: Map<u64, LockedPosition> = env
+        .storage()
+        .instance()
+        .get(&POSITIONS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    positions.set(position.position_id, position.clone());
+    env.storage().instance().set(&POSITIONS_MAP, &positions);
+}
+
+/// Get the concentration limits (no limits if not configured)
+pub fn get_concentration_limits(env: &Env) -> ConcentrationLimits {
+    env.storage()
+        .instance()
+        .get(&CONCENTRATION_LIMITS)
+        .unwrap_or(ConcentrationLimits {
+            max_borrower_bps: BPS_DENOMINATOR as u32,
+            max_merchant_bps: BPS_DENOMINATOR as u32,
+            max_category_bps: BPS_DENOMINATOR as u32,
+        })
+}
+
+/// Set the concentration limits
+pub fn set_concentration_limits(env: &Env, limits: &ConcentrationLimits) {
+    env.storage().instance().set(&CONCENTRATION_LIMITS, limits);
+}
+
+/// Read the outstanding principal funded for a loan
+pub fn read_loan_exposure(env: &Env, loan_id: u64) -> Option<LoanExposure> {
+    let exposures: Map<u64, LoanExposure> = env
+        .storage()
+        .instance()
+        .get(&LOAN_EXPOSURES)
+        .unwrap_or_else(|| Map::new(env));
+
+    exposures.get(loan_id)
+}
+
+/// Write the outstanding principal funded for a loan, dropping the entry once fully repaid
+pub fn write_loan_exposure(env: &Env, loan_id: u64, exposure: &LoanExposure) {
+    let mut exposures: Map<u64, LoanExposure> = env
+        .storage()
+        .instance()
+        .get(&LOAN_EXPOSURES)
+        .unwrap_or_else(|| Map::new(env));
+
+    if exposure.principal == 0 {
+        exposures.remove(loan_id);
+    } else {
+        exposures.set(loan_id, exposure.clone());
+    }
+    env.storage().instance().set(&LOAN_EXPOSURES, &exposures);
+}
+
+/// Get the outstanding principal lent to a borrower
+pub fn get_borrower_exposure(env: &Env, borrower: &Address) -> i128 {
+    read_exposure(env, &BORROWER_EXPOSURE, borrower.clone())
+}
+
+/// Set the outstanding principal lent to a borrower
+pub fn set_borrower_exposure(env: &Env, borrower: &Address, amount: i128) {
+    write_exposure(env, &BORROWER_EXPOSURE, borrower.clone(), amount);
+}
+
+/// Get the outstanding principal disbursed to a merchant
+pub fn get_merchant_exposure(env: &Env, merchant: &Address) -> i128 {
+    read_exposure(env, &MERCHANT_EXPOSURE, merchant.clone())
+}
+
+/// Set the outstanding principal disbursed to a merchant
+pub fn set_merchant_exposure(env: &Env, merchant: &Address, amount: i128) {
+    write_exposure(env, &MERCHANT_EXPOSURE, merchant.clone(), amount);
+}
+
+/// Get the outstanding principal disbursed to merchants of a category
+pub fn get_category_exposure(env: &Env, category: &Symbol) -> i128 {
+    read_exposure(env, &CATEGORY_EXPOSURE, category.clone())
+}
+
+/// Set the outstanding principal disbursed to merchants of a category
+pub fn set_category_exposure(env: &Env, category: &Symbol, amount: i128) {
+    write_exposure(env, &CATEGORY_EXPOSURE, category.clone(), amount);
+}
+
+/// Read an entry of an exposure map (0 if none)
+fn read_exposure<K>(env: &Env, map_key: &Symbol, key: K) -> i128
+where
+    K: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let exposures: Map<K, i128> = env
+        .storage()
+        .instance()
+        .get(map_key)
+        .unwrap_or_else(|| Map::new(env));
+
+    exposures.get(key).unwrap_or(0)
+}
+
+/// Write an entry of an exposure map, dropping it when it reaches zero
+fn write_exposure<K>(env: &Env, map_key: &Symbol, key: K, amount: i128)
+where
+    K: IntoVal<Env, Val> + TryFromVal<Env, Val>,
+{
+    let mut exposures: Map<K, i128> = env
+        .storage()
+        .instance()
+        .get(map_key)
+        .unwrap_or_else(|| Map::new(env));
+
+    if amount == 0 {
+        exposures.remove(key);
+    } else {
+        exposures.set(key, amount);
+    }
+    env.storage().instance().set(map_key, &exposures);
+}
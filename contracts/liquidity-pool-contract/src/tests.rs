@@ -0,0 +1,1510 @@
+use crate::{
+    ConcentrationLimits, LiquidityPoolContract, LiquidityPoolContractClient, LockupTerm,
+    PositionStatus, RateModel, Tranche, WithdrawalStatus, MAX_QUEUE_FILLS_PER_CALL,
+    MINIMUM_LIQUIDITY, MIN_WITHDRAWAL_REQUEST_SHARES, RESERVE_TIMELOCK_SECONDS,
+    SHARE_VALUE_PRECISION,
+};
+use lp_token_contract::{LpTokenContract, LpTokenContractClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, Address, Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+struct PoolSetup<'a> {
+    client: LiquidityPoolContractClient<'a>,
+    admin: Address,
+    token: token::Client<'a>,
+    token_admin: token::StellarAssetClient<'a>,
+}
+
+fn setup(env: &Env) -> PoolSetup<'_> {
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+
+    let contract_id = env.register(LiquidityPoolContract, ());
+    let client = LiquidityPoolContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    client.initialize(&admin, &token_id);
+    client.set_share_token(
+        &admin,
+        &Tranche::Senior,
+        &share_token(env, &contract_id, "tuSLP"),
+    );
+    client.set_share_token(
+        &admin,
+        &Tranche::Junior,
+        &share_token(env, &contract_id, "tuJLP"),
+    );
+
+    PoolSetup {
+        client,
+        admin,
+        token: token::Client::new(env, &token_id),
+        token_admin: token::StellarAssetClient::new(env, &token_id),
+    }
+}
+
+// LP share token administered by the pool
+fn share_token(env: &Env, pool: &Address, symbol: &str) -> Address {
+    let token_id = env.register(LpTokenContract, ());
+    LpTokenContractClient::new(env, &token_id).initialize(
+        pool,
+        &7,
+        &String::from_str(env, "TrustUp LP"),
+        &String::from_str(env, symbol),
+    );
+    token_id
+}
+
+fn funded_provider(env: &Env, s: &PoolSetup, amount: i128) -> Address {
+    let provider = Address::generate(env);
+    s.token_admin.mint(&provider, &amount);
+    provider
+}
+
+#[test]
+fn test_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    assert_eq!(s.client.get_admin(), s.admin);
+    assert_eq!(s.client.get_token(), s.token.address);
+    assert_eq!(s.client.get_total_pool_value(), 0);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        SHARE_VALUE_PRECISION
+    );
+}
+
+#[test]
+#[should_panic(expected = "Already initialized")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.initialize(&s.admin, &s.token.address);
+}
+
+#[test]
+fn test_get_version() {
+    let version = LiquidityPoolContract::get_version();
+    assert_eq!(version, symbol_short!("v1_0_0"));
+}
+
+#[test]
+fn test_first_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+
+    let shares = s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    // Shares are issued 1:1, minus the permanently locked minimum liquidity
+    assert_eq!(shares, 10_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(
+        s.client.get_shares(&provider, &Tranche::Senior),
+        10_000 - MINIMUM_LIQUIDITY
+    );
+    assert_eq!(
+        s.client.get_shares(&s.client.address, &Tranche::Senior),
+        MINIMUM_LIQUIDITY
+    );
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).total_shares, 10_000);
+    assert_eq!(s.client.get_total_pool_value(), 10_000);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        SHARE_VALUE_PRECISION
+    );
+
+    assert_eq!(s.token.balance(&provider), 0);
+    assert_eq!(s.token.balance(&s.client.address), 10_000);
+}
+
+#[test]
+fn test_shares_are_transferable_sep41_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    let share_token =
+        LpTokenContractClient::new(&env, &s.client.get_share_token(&Tranche::Senior).unwrap());
+    assert_eq!(share_token.balance(&provider), 9_000);
+    assert_eq!(share_token.total_supply(), 10_000);
+    assert_eq!(share_token.symbol(), String::from_str(&env, "tuSLP"));
+
+    // The new holder redeems the transferred shares directly with the pool
+    let holder = Address::generate(&env);
+    share_token.transfer(&provider, &holder, &4_000);
+    assert_eq!(s.client.get_shares(&holder, &Tranche::Senior), 4_000);
+
+    assert_eq!(s.client.withdraw(&holder, &Tranche::Senior, &4_000), 4_000);
+    assert_eq!(s.token.balance(&holder), 4_000);
+    assert_eq!(share_token.balance(&holder), 0);
+    assert_eq!(share_token.total_supply(), 6_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn test_deposit_without_share_token_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_id = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let client = LiquidityPoolContractClient::new(&env, &env.register(LiquidityPoolContract, ()));
+    client.initialize(&Address::generate(&env), &token_id);
+
+    client.deposit(&Address::generate(&env), &Tranche::Junior, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn test_share_token_cannot_be_replaced() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client
+        .set_share_token(&s.admin, &Tranche::Senior, &Address::generate(&env));
+}
+
+#[test]
+fn test_deposit_emits_event() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LIQDEPOS"));
+
+    let (amount, shares, _timestamp): (i128, i128, u64) = last.2.into_val(&env);
+    assert_eq!(amount, 10_000);
+    assert_eq!(shares, 10_000 - MINIMUM_LIQUIDITY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_first_deposit_below_minimum_liquidity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, MINIMUM_LIQUIDITY);
+
+    s.client
+        .deposit(&provider, &Tranche::Senior, &MINIMUM_LIQUIDITY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_deposit_zero_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 1_000);
+
+    s.client.deposit(&provider, &Tranche::Senior, &0);
+}
+
+#[test]
+fn test_subsequent_deposit() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let first = funded_provider(&env, &s, 10_000);
+    let second = funded_provider(&env, &s, 5_000);
+
+    s.client.deposit(&first, &Tranche::Senior, &10_000);
+    let shares = s.client.deposit(&second, &Tranche::Senior, &5_000);
+
+    // 5,000 × 10,000 shares / 10,000 value
+    assert_eq!(shares, 5_000);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).total_shares, 15_000);
+    assert_eq!(s.client.get_total_pool_value(), 15_000);
+}
+
+#[test]
+fn test_donation_does_not_inflate_share_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let attacker = funded_provider(&env, &s, 1_000_001 + MINIMUM_LIQUIDITY);
+    let victim = funded_provider(&env, &s, 500_000);
+
+    // Attacker takes a single share and donates tokens straight to the pool
+    assert_eq!(
+        s.client
+            .deposit(&attacker, &Tranche::Senior, &(MINIMUM_LIQUIDITY + 1)),
+        1
+    );
+    s.token.transfer(&attacker, &s.client.address, &1_000_000);
+
+    // The donation is not counted as pool value, so the victim is priced fairly
+    assert_eq!(
+        s.client.deposit(&victim, &Tranche::Senior, &500_000),
+        500_000
+    );
+
+    // The attacker can only redeem their own deposit; the donation stays in the pool
+    assert_eq!(s.client.withdraw(&attacker, &Tranche::Senior, &1), 1);
+    assert_eq!(
+        s.client.withdraw(&victim, &Tranche::Senior, &500_000),
+        500_000
+    );
+}
+
+#[test]
+fn test_withdraw_partial() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    let amount = s.client.withdraw(&provider, &Tranche::Senior, &4_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LIQWDRAW"));
+
+    assert_eq!(amount, 4_000);
+    assert_eq!(s.client.get_shares(&provider, &Tranche::Senior), 5_000);
+    assert_eq!(s.client.get_total_pool_value(), 6_000);
+    assert_eq!(s.token.balance(&provider), 4_000);
+}
+
+#[test]
+fn test_withdraw_full() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let first = funded_provider(&env, &s, 10_000);
+    let second = funded_provider(&env, &s, 3_000);
+    s.client.deposit(&first, &Tranche::Senior, &10_000);
+    s.client.deposit(&second, &Tranche::Senior, &3_000);
+
+    assert_eq!(s.client.withdraw(&second, &Tranche::Senior, &3_000), 3_000);
+    assert_eq!(s.client.get_shares(&second, &Tranche::Senior), 0);
+
+    // Only the locked minimum liquidity remains after everyone exits
+    s.client
+        .withdraw(&first, &Tranche::Senior, &(10_000 - MINIMUM_LIQUIDITY));
+    assert_eq!(
+        s.client.get_tranche(&Tranche::Senior).total_shares,
+        MINIMUM_LIQUIDITY
+    );
+    assert_eq!(s.client.get_total_pool_value(), MINIMUM_LIQUIDITY);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_withdraw_too_many_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    s.client.withdraw(&provider, &Tranche::Senior, &10_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_withdraw_zero_shares_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    s.client.withdraw(&provider, &Tranche::Senior, &0);
+}
+
+// Loan funding tests
+
+fn setup_with_creditline(env: &Env) -> (PoolSetup<'_>, Address) {
+    let s = setup(env);
+
+    let creditline = Address::generate(env);
+    s.client.set_creditline(&s.admin, &creditline);
+
+    let provider = funded_provider(env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    (s, creditline)
+}
+
+/// Fund a loan to a fresh borrower and merchant in the default category
+fn fund(env: &Env, s: &PoolSetup, loan_id: u64, amount: i128) {
+    s.client.fund_loan(
+        &loan_id,
+        &amount,
+        &Address::generate(env),
+        &Address::generate(env),
+        &symbol_short!("general"),
+    );
+}
+
+#[test]
+fn test_fund_loan_reduces_available_liquidity() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline) = setup_with_creditline(&env);
+    fund(&env, &s, 1, 800);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LOANFUND"));
+
+    assert_eq!(s.token.balance(&creditline), 800);
+    assert_eq!(s.client.get_available_liquidity(), 9_200);
+    assert_eq!(s.client.get_total_loaned(), 800);
+
+    // Outstanding loans still belong to the share holders
+    assert_eq!(s.client.get_total_pool_value(), 10_000);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        SHARE_VALUE_PRECISION
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_fund_loan_with_insufficient_liquidity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+
+    fund(&env, &s, 1, 10_001);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_fund_loan_without_creditline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    fund(&env, &s, 1, 100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_withdrawal_blocked_when_pool_utilized() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    s.client.set_creditline(&s.admin, &Address::generate(&env));
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    fund(&env, &s, 1, 8_000);
+
+    // 5,000 shares are worth 5,000 but only 2,000 is available
+    s.client.withdraw(&provider, &Tranche::Senior, &5_000);
+}
+
+#[test]
+fn test_repayment_returns_principal_and_distributes_income() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline) = setup_with_creditline(&env);
+    fund(&env, &s, 1, 1_000);
+
+    // CreditLine forwards the borrower's repayment before notifying the pool
+    s.token_admin.mint(&creditline, &1_100);
+    s.token.transfer(&creditline, &s.client.address, &1_100);
+    s.client.receive_repayment(&1, &1_000, &100);
+
+    assert_eq!(s.client.get_total_loaned(), 0);
+    assert_eq!(s.client.get_available_liquidity(), 10_100);
+    assert_eq!(s.client.get_total_pool_value(), 10_100);
+    assert_eq!(s.client.get_share_value(&Tranche::Senior), 10_100_000);
+
+    // A new depositor buys in at the higher share value, rounded in the pool's favour
+    let late = funded_provider(&env, &s, 1_000);
+    assert_eq!(s.client.deposit(&late, &Tranche::Senior, &1_000), 990);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_repayment_above_outstanding_principal_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    fund(&env, &s, 1, 500);
+
+    s.client.receive_repayment(&1, &501, &0);
+}
+
+// Concentration limit tests
+
+fn limits(borrower_bps: u32, merchant_bps: u32, category_bps: u32) -> ConcentrationLimits {
+    ConcentrationLimits {
+        max_borrower_bps: borrower_bps,
+        max_merchant_bps: merchant_bps,
+        max_category_bps: category_bps,
+    }
+}
+
+#[test]
+fn test_exposure_tracked_until_repaid_or_written_off() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline) = setup_with_creditline(&env);
+    let borrower = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let electronics = symbol_short!("electr");
+
+    s.client
+        .fund_loan(&1, &1_000, &borrower, &merchant, &electronics);
+    s.client
+        .fund_loan(&2, &500, &borrower, &merchant, &electronics);
+
+    assert_eq!(s.client.get_borrower_exposure(&borrower), 1_500);
+    assert_eq!(s.client.get_merchant_exposure(&merchant), 1_500);
+    assert_eq!(s.client.get_category_exposure(&electronics), 1_500);
+
+    repay(&s, &creditline, 1_000);
+    assert!(s.client.get_loan_exposure(&1).is_none());
+    assert_eq!(s.client.get_borrower_exposure(&borrower), 500);
+
+    s.client.recognize_loss(&2, &200);
+    assert_eq!(s.client.get_loan_exposure(&2).unwrap().principal, 300);
+    assert_eq!(s.client.get_merchant_exposure(&merchant), 300);
+    assert_eq!(s.client.get_category_exposure(&electronics), 300);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #29)")]
+fn test_fund_loan_above_borrower_limit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    s.client
+        .set_concentration_limits(&s.admin, &limits(1_000, 10_000, 10_000));
+    let borrower = Address::generate(&env);
+
+    // 10% of a 10,000 pool
+    s.client.fund_loan(
+        &1,
+        &600,
+        &borrower,
+        &Address::generate(&env),
+        &symbol_short!("general"),
+    );
+    s.client.fund_loan(
+        &2,
+        &401,
+        &borrower,
+        &Address::generate(&env),
+        &symbol_short!("general"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn test_fund_loan_above_merchant_limit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    s.client
+        .set_concentration_limits(&s.admin, &limits(10_000, 2_000, 10_000));
+    let merchant = Address::generate(&env);
+
+    s.client.fund_loan(
+        &1,
+        &1_500,
+        &Address::generate(&env),
+        &merchant,
+        &symbol_short!("general"),
+    );
+    s.client.fund_loan(
+        &2,
+        &501,
+        &Address::generate(&env),
+        &merchant,
+        &symbol_short!("general"),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #31)")]
+fn test_fund_loan_above_category_limit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    s.client
+        .set_concentration_limits(&s.admin, &limits(10_000, 10_000, 3_000));
+
+    fund(&env, &s, 1, 3_000);
+    fund(&env, &s, 2, 1);
+}
+
+#[test]
+fn test_check_concentration_matches_fund_loan() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    s.client
+        .set_concentration_limits(&s.admin, &limits(1_000, 10_000, 10_000));
+    let borrower = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let general = symbol_short!("general");
+
+    assert!(s
+        .client
+        .check_concentration(&borrower, &merchant, &general, &1_000));
+    assert!(!s
+        .client
+        .check_concentration(&borrower, &merchant, &general, &1_001));
+
+    // The check funds nothing
+    assert_eq!(s.client.get_borrower_exposure(&borrower), 0);
+    assert_eq!(s.client.get_total_loaned(), 0);
+
+    s.client.fund_loan(&1, &600, &borrower, &merchant, &general);
+    assert!(s
+        .client
+        .check_concentration(&borrower, &merchant, &general, &400));
+    assert!(!s
+        .client
+        .check_concentration(&borrower, &merchant, &general, &401));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn test_set_zero_concentration_limit_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client
+        .set_concentration_limits(&s.admin, &limits(0, 10_000, 10_000));
+}
+
+// Interest rate model tests
+
+#[test]
+fn test_utilization_and_rate_follow_kinked_curve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = setup_with_creditline(&env);
+    s.client.set_rate_model(
+        &s.admin,
+        &RateModel {
+            base_rate_bps: 200,
+            slope1_bps: 800,
+            slope2_bps: 6_000,
+            optimal_utilization_bps: 8_000,
+        },
+    );
+
+    // Idle pool pays the base rate
+    assert_eq!(s.client.get_utilization(), 0);
+    assert_eq!(s.client.get_current_rate(), 200);
+
+    // 40% utilization: halfway up the first slope
+    fund(&env, &s, 1, 4_000);
+    assert_eq!(s.client.get_utilization(), 4_000);
+    assert_eq!(s.client.get_current_rate(), 600);
+
+    // 80% utilization: at the kink
+    fund(&env, &s, 2, 4_000);
+    assert_eq!(s.client.get_current_rate(), 1_000);
+
+    // 90% utilization: halfway up the steep slope
+    fund(&env, &s, 3, 1_000);
+    assert_eq!(s.client.get_utilization(), 9_000);
+    assert_eq!(s.client.get_current_rate(), 4_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn test_set_rate_model_with_invalid_kink_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.set_rate_model(
+        &s.admin,
+        &RateModel {
+            base_rate_bps: 200,
+            slope1_bps: 800,
+            slope2_bps: 6_000,
+            optimal_utilization_bps: 10_000,
+        },
+    );
+}
+
+// Withdrawal queue tests
+
+fn fully_utilized_pool(env: &Env) -> (PoolSetup<'_>, Address, Address, Address) {
+    let s = setup(env);
+    let creditline = Address::generate(env);
+    s.client.set_creditline(&s.admin, &creditline);
+
+    let first = funded_provider(env, &s, 10_000);
+    let second = funded_provider(env, &s, 5_000);
+    s.client.deposit(&first, &Tranche::Senior, &10_000);
+    s.client.deposit(&second, &Tranche::Senior, &5_000);
+
+    fund(env, &s, 1, 15_000);
+
+    (s, creditline, first, second)
+}
+
+fn repay(s: &PoolSetup, creditline: &Address, principal: i128) {
+    s.token_admin.mint(creditline, &principal);
+    s.token.transfer(creditline, &s.client.address, &principal);
+    s.client.receive_repayment(&1, &principal, &0);
+}
+
+#[test]
+fn test_withdrawal_request_filled_immediately_when_liquid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+
+    let request_id = s
+        .client
+        .request_withdrawal(&provider, &Tranche::Senior, &4_000);
+
+    let request = s.client.get_withdrawal_request(&request_id);
+    assert_eq!(request.status, WithdrawalStatus::Filled);
+    assert_eq!(request.amount_paid, 4_000);
+    assert_eq!(s.token.balance(&provider), 4_000);
+    assert_eq!(s.client.get_withdrawal_queue().len(), 0);
+}
+
+#[test]
+fn test_repayments_fill_queue_in_order() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline, first, second) = fully_utilized_pool(&env);
+
+    let first_request = s
+        .client
+        .request_withdrawal(&first, &Tranche::Senior, &3_000);
+    let second_request = s
+        .client
+        .request_withdrawal(&second, &Tranche::Senior, &2_000);
+
+    // Shares are locked while the requests wait
+    assert_eq!(s.client.get_shares(&first, &Tranche::Senior), 6_000);
+    assert_eq!(s.client.get_shares(&second, &Tranche::Senior), 3_000);
+    assert_eq!(
+        s.client.get_withdrawal_request(&first_request).status,
+        WithdrawalStatus::Pending
+    );
+
+    repay(&s, &creditline, 4_000);
+
+    // The oldest request is filled first; the next one is filled partially
+    let first_filled = s.client.get_withdrawal_request(&first_request);
+    assert_eq!(first_filled.status, WithdrawalStatus::Filled);
+    assert_eq!(first_filled.amount_paid, 3_000);
+
+    let second_partial = s.client.get_withdrawal_request(&second_request);
+    assert_eq!(second_partial.status, WithdrawalStatus::Pending);
+    assert_eq!(second_partial.amount_paid, 1_000);
+    assert_eq!(second_partial.shares_remaining, 1_000);
+    assert_eq!(s.client.get_available_liquidity(), 0);
+
+    repay(&s, &creditline, 2_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("WREQFILL"));
+    let (amount, shares_burned, shares_remaining): (i128, i128, i128) = last.2.into_val(&env);
+    assert_eq!((amount, shares_burned, shares_remaining), (1_000, 1_000, 0));
+
+    assert_eq!(
+        s.client.get_withdrawal_request(&second_request).status,
+        WithdrawalStatus::Filled
+    );
+    assert_eq!(s.token.balance(&first), 3_000);
+    assert_eq!(s.token.balance(&second), 2_000);
+    assert_eq!(s.client.get_available_liquidity(), 1_000);
+    assert_eq!(s.client.get_withdrawal_queue().len(), 0);
+}
+
+#[test]
+fn test_cancel_withdrawal_request_unlocks_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline, first, second) = fully_utilized_pool(&env);
+
+    let first_request = s
+        .client
+        .request_withdrawal(&first, &Tranche::Senior, &3_000);
+    let second_request = s
+        .client
+        .request_withdrawal(&second, &Tranche::Senior, &2_000);
+
+    // Locked shares are held by the pool until the request is filled or cancelled
+    assert_eq!(s.client.get_shares(&first, &Tranche::Senior), 6_000);
+    assert_eq!(
+        s.client.get_shares(&s.client.address, &Tranche::Senior),
+        MINIMUM_LIQUIDITY + 5_000
+    );
+
+    assert_eq!(
+        s.client.cancel_withdrawal_request(&first, &first_request),
+        3_000
+    );
+    assert_eq!(s.client.get_shares(&first, &Tranche::Senior), 9_000);
+    assert_eq!(
+        s.client.get_withdrawal_request(&first_request).status,
+        WithdrawalStatus::Cancelled
+    );
+
+    // The next request moves to the front of the queue
+    repay(&s, &creditline, 2_000);
+    assert_eq!(
+        s.client.get_withdrawal_request(&second_request).status,
+        WithdrawalStatus::Filled
+    );
+    assert_eq!(s.token.balance(&first), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_cancel_other_providers_request_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, first, second) = fully_utilized_pool(&env);
+    let request_id = s
+        .client
+        .request_withdrawal(&first, &Tranche::Senior, &3_000);
+
+    s.client.cancel_withdrawal_request(&second, &request_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_cancel_filled_request_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    let provider = funded_provider(&env, &s, 10_000);
+    s.client.deposit(&provider, &Tranche::Senior, &10_000);
+    let request_id = s
+        .client
+        .request_withdrawal(&provider, &Tranche::Senior, &1_000);
+
+    s.client.cancel_withdrawal_request(&provider, &request_id);
+}
+
+#[test]
+fn test_queue_is_processed_in_bounded_batches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline, first, second) = fully_utilized_pool(&env);
+    for _ in 0..7 {
+        s.client
+            .request_withdrawal(&first, &Tranche::Senior, &MIN_WITHDRAWAL_REQUEST_SHARES);
+    }
+    for _ in 0..5 {
+        s.client
+            .request_withdrawal(&second, &Tranche::Senior, &MIN_WITHDRAWAL_REQUEST_SHARES);
+    }
+    assert_eq!(s.client.get_withdrawal_queue().len(), 12);
+
+    // A repayment only fills a bounded batch even when it could cover the whole queue
+    repay(&s, &creditline, 15_000);
+    assert_eq!(
+        s.client.get_withdrawal_queue().len(),
+        12 - MAX_QUEUE_FILLS_PER_CALL
+    );
+    assert_eq!(s.token.balance(&first), 7_000);
+    assert_eq!(s.token.balance(&second), 3_000);
+
+    // Anyone can drain the rest in batches of their choosing
+    assert_eq!(s.client.process_queue(&1), 1);
+    assert_eq!(s.client.get_withdrawal_queue().len(), 1);
+    assert_eq!(s.client.process_queue(&5), 1);
+    assert_eq!(s.client.get_withdrawal_queue().len(), 0);
+    assert_eq!(s.token.balance(&second), 5_000);
+    assert_eq!(s.client.process_queue(&5), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn test_withdrawal_request_below_minimum_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, first, _second) = fully_utilized_pool(&env);
+
+    s.client.request_withdrawal(
+        &first,
+        &Tranche::Senior,
+        &(MIN_WITHDRAWAL_REQUEST_SHARES - 1),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_get_unknown_withdrawal_request_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.get_withdrawal_request(&1);
+}
+
+// Tranche tests
+
+fn two_tranche_pool(env: &Env) -> (PoolSetup<'_>, Address, Address, Address) {
+    let s = setup(env);
+    let creditline = Address::generate(env);
+    s.client.set_creditline(&s.admin, &creditline);
+
+    let senior = funded_provider(env, &s, 8_000);
+    let junior = funded_provider(env, &s, 2_000);
+    s.client.deposit(&senior, &Tranche::Senior, &8_000);
+    s.client.deposit(&junior, &Tranche::Junior, &2_000);
+
+    (s, creditline, senior, junior)
+}
+
+#[test]
+fn test_tranches_have_separate_share_accounting() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, senior, junior) = two_tranche_pool(&env);
+
+    assert_eq!(s.client.get_shares(&senior, &Tranche::Senior), 7_000);
+    assert_eq!(s.client.get_shares(&senior, &Tranche::Junior), 0);
+    assert_eq!(s.client.get_shares(&junior, &Tranche::Junior), 1_000);
+
+    let senior_state = s.client.get_tranche(&Tranche::Senior);
+    assert_eq!(senior_state.total_shares, 8_000);
+    assert_eq!(senior_state.value, 8_000);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 2_000);
+    assert_eq!(s.client.get_total_pool_value(), 10_000);
+}
+
+#[test]
+fn test_income_split_between_tranches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline, _senior, junior) = two_tranche_pool(&env);
+    s.client.set_interest_split(&s.admin, &3_000);
+
+    fund(&env, &s, 1, 5_000);
+    s.token_admin.mint(&creditline, &6_000);
+    s.token.transfer(&creditline, &s.client.address, &6_000);
+    s.client.receive_repayment(&1, &5_000, &1_000);
+
+    // 30% of the income goes to the senior tranche, the rest to junior
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_300);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 2_700);
+    assert_eq!(s.client.get_share_value(&Tranche::Junior), 13_500_000);
+
+    // Junior LPs redeem at the higher junior share value
+    assert_eq!(s.client.withdraw(&junior, &Tranche::Junior, &1_000), 1_350);
+}
+
+#[test]
+fn test_losses_hit_junior_tranche_first() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, _senior, _junior) = two_tranche_pool(&env);
+    fund(&env, &s, 1, 5_000);
+
+    s.client.recognize_loss(&1, &1_500);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 500);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_000);
+
+    // Once junior is exhausted the senior tranche absorbs the rest
+    s.client.recognize_loss(&1, &1_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LOSSRCG"));
+    let (amount, reserve_loss, junior_loss, senior_loss): (i128, i128, i128, i128) =
+        last.2.into_val(&env);
+    assert_eq!(
+        (amount, reserve_loss, junior_loss, senior_loss),
+        (1_000, 0, 500, 500)
+    );
+
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 0);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 7_500);
+    assert_eq!(s.client.get_total_loaned(), 2_500);
+    assert_eq!(s.client.get_total_pool_value(), 7_500);
+}
+
+#[test]
+fn test_loss_beyond_pool_value_is_recorded_as_uncovered() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, _senior, _junior) = two_tranche_pool(&env);
+    fund(&env, &s, 1, 5_000);
+
+    // Tranche values marked down below the outstanding principal
+    env.as_contract(&s.client.address, || {
+        let mut senior = crate::storage::get_tranche(&env, Tranche::Senior);
+        senior.value = 2_000;
+        crate::storage::set_tranche(&env, Tranche::Senior, &senior);
+    });
+
+    // The default still goes through; the shortfall is recorded instead of reverting
+    s.client.recognize_loss(&1, &5_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("LOSSUNCV"));
+    let (shortfall, uncovered_loss): (i128, i128) = last.2.into_val(&env);
+    assert_eq!((shortfall, uncovered_loss), (1_000, 1_000));
+
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 0);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 0);
+    assert_eq!(s.client.get_total_loaned(), 0);
+    assert_eq!(s.client.get_uncovered_loss(), 1_000);
+}
+
+#[test]
+fn test_deposit_recapitalizes_wiped_out_tranche() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, _senior, junior) = two_tranche_pool(&env);
+    fund(&env, &s, 1, 2_000);
+    s.client.recognize_loss(&1, &2_000);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 0);
+
+    // The worthless junior shares are diluted to dust by the new deposit
+    let newcomer = funded_provider(&env, &s, 1_000);
+    let issued = s.client.deposit(&newcomer, &Tranche::Junior, &1_000);
+    assert_eq!(issued, 2_000_000);
+
+    let state = s.client.get_tranche(&Tranche::Junior);
+    assert_eq!(state.total_shares, 2_002_000);
+    assert_eq!(state.value, 1_000);
+
+    assert_eq!(s.client.withdraw(&newcomer, &Tranche::Junior, &issued), 999);
+    let result = s.client.try_withdraw(&junior, &Tranche::Junior, &1_000);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_recovery_restores_tranche_value_as_income() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, creditline, _senior, _junior) = two_tranche_pool(&env);
+    fund(&env, &s, 1, 1_000);
+    s.client.recognize_loss(&1, &1_000);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 1_000);
+
+    // A collection on the written-off loan comes back as income
+    s.token_admin.mint(&creditline, &600);
+    s.token.transfer(&creditline, &s.client.address, &600);
+    s.client.receive_recovery(&600);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("RECOVERY"));
+
+    assert_eq!(s.client.get_total_loaned(), 0);
+    assert_eq!(s.client.get_available_liquidity(), 9_600);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_300);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 1_300);
+}
+
+#[test]
+fn test_income_from_any_payer_is_booked_to_reserve_and_tranches() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, _senior, _junior) = two_tranche_pool(&env);
+    s.client.set_reserve_factor(&s.admin, &2_000);
+
+    // e.g. a slashed merchant bond paid in by the merchant registry
+    let payer = Address::generate(&env);
+    s.token_admin.mint(&payer, &1_000);
+    s.client.receive_income(&payer, &1_000);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("INCOMRCV"));
+
+    assert_eq!(s.token.balance(&payer), 0);
+    assert_eq!(s.token.balance(&s.client.address), 11_000);
+    assert_eq!(s.client.get_reserve_balance(), 200);
+    assert_eq!(s.client.get_available_liquidity(), 10_800);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_400);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 2_400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn test_receive_income_zero_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline, _senior, _junior) = two_tranche_pool(&env);
+    s.client.receive_income(&Address::generate(&env), &0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_set_interest_split_above_100_percent_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.set_interest_split(&s.admin, &10_001);
+}
+
+// Reserve tests
+
+fn pool_with_reserve(env: &Env) -> (PoolSetup<'_>, Address) {
+    let (s, creditline, _senior, _junior) = two_tranche_pool(env);
+    s.client.set_reserve_factor(&s.admin, &2_000);
+
+    fund(env, &s, 1, 5_000);
+    s.token_admin.mint(&creditline, &6_000);
+    s.token.transfer(&creditline, &s.client.address, &6_000);
+    s.client.receive_repayment(&1, &5_000, &1_000);
+
+    (s, creditline)
+}
+
+#[test]
+fn test_reserve_factor_diverts_income_to_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+
+    // 20% of the income funds the reserve, the tranches split the other 800
+    assert_eq!(s.client.get_reserve_balance(), 200);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_400);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 2_400);
+
+    // The reserve is held by the pool but is not part of the LP-owned value
+    assert_eq!(s.client.get_total_pool_value(), 10_800);
+    assert_eq!(s.token.balance(&s.client.address), 11_000);
+}
+
+#[test]
+fn test_reserve_absorbs_losses_before_junior_tranche() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+    fund(&env, &s, 2, 4_000);
+    assert_eq!(s.client.get_coverage_ratio(), 500);
+
+    s.client.recognize_loss(&2, &500);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let (amount, reserve_loss, junior_loss, senior_loss): (i128, i128, i128, i128) =
+        last.2.into_val(&env);
+    assert_eq!(
+        (amount, reserve_loss, junior_loss, senior_loss),
+        (500, 200, 300, 0)
+    );
+
+    // The reserve replaces 200 of the lost principal with cash
+    assert_eq!(s.client.get_reserve_balance(), 0);
+    assert_eq!(s.client.get_available_liquidity(), 7_000);
+    assert_eq!(s.client.get_total_loaned(), 3_500);
+    assert_eq!(s.client.get_tranche(&Tranche::Junior).value, 2_100);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 8_400);
+    assert_eq!(s.client.get_coverage_ratio(), 0);
+}
+
+#[test]
+fn test_reserve_withdrawal_after_timelock() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+    let treasury = Address::generate(&env);
+
+    env.ledger().set_timestamp(1_000);
+    s.client
+        .schedule_reserve_withdrawal(&s.admin, &treasury, &150);
+    let scheduled = s.client.get_reserve_withdrawal().unwrap();
+    assert_eq!(scheduled.executable_at, 1_000 + RESERVE_TIMELOCK_SECONDS);
+
+    env.ledger().set_timestamp(1_000 + RESERVE_TIMELOCK_SECONDS);
+    assert_eq!(s.client.execute_reserve_withdrawal(&s.admin), 150);
+
+    assert_eq!(s.token.balance(&treasury), 150);
+    assert_eq!(s.client.get_reserve_balance(), 50);
+    assert_eq!(s.client.get_reserve_withdrawal(), None);
+    assert_eq!(s.client.get_total_pool_value(), 10_800);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn test_reserve_withdrawal_before_timelock_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+    s.client
+        .schedule_reserve_withdrawal(&s.admin, &Address::generate(&env), &100);
+
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + RESERVE_TIMELOCK_SECONDS - 1);
+    s.client.execute_reserve_withdrawal(&s.admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn test_reserve_withdrawal_above_balance_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+
+    s.client
+        .schedule_reserve_withdrawal(&s.admin, &Address::generate(&env), &201);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn test_cancelled_reserve_withdrawal_cannot_execute() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+    s.client
+        .schedule_reserve_withdrawal(&s.admin, &Address::generate(&env), &100);
+    s.client.cancel_reserve_withdrawal(&s.admin);
+
+    env.ledger()
+        .set_timestamp(env.ledger().timestamp() + RESERVE_TIMELOCK_SECONDS);
+    s.client.execute_reserve_withdrawal(&s.admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_reserve_withdrawal_by_non_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _creditline) = pool_with_reserve(&env);
+    let attacker = Address::generate(&env);
+
+    s.client
+        .schedule_reserve_withdrawal(&attacker, &attacker, &100);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn test_set_reserve_factor_above_100_percent_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+
+    s.client.set_reserve_factor(&s.admin, &10_001);
+}
+
+// Lockup tests
+
+const DAY: u64 = 24 * 60 * 60;
+
+fn pool_with_locked_position(env: &Env, term: LockupTerm) -> (PoolSetup<'_>, Address, u64) {
+    let s = setup(env);
+    let flexible = funded_provider(env, &s, 10_000);
+    s.client.deposit(&flexible, &Tranche::Senior, &10_000);
+
+    let locker = funded_provider(env, &s, 10_000);
+    let position_id = s
+        .client
+        .deposit_locked(&locker, &Tranche::Senior, &10_000, &term);
+
+    (s, locker, position_id)
+}
+
+#[test]
+fn test_locked_deposit_records_position() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days90);
+
+    let position = s.client.get_position(&position_id);
+    assert_eq!(position.provider, locker);
+    assert_eq!(position.shares, 10_000);
+    assert_eq!(position.unlock_at, 1_000 + 90 * DAY);
+    assert_eq!(position.status, PositionStatus::Locked);
+    // 2.5% annual boost over 90 days
+    assert_eq!(position.bonus_shares, 61);
+
+    // The shares are held by the pool until the position unlocks
+    assert_eq!(s.client.get_shares(&locker, &Tranche::Senior), 0);
+    assert_eq!(
+        s.client.get_shares(&s.client.address, &Tranche::Senior),
+        MINIMUM_LIQUIDITY + 10_000
+    );
+}
+
+// Fund the lockup boost budget from a sponsor
+fn fund_boost_budget(s: &PoolSetup, amount: i128) {
+    let sponsor = Address::generate(&s.client.env);
+    s.token_admin.mint(&sponsor, &amount);
+    s.client.fund_boost_budget(&sponsor, &amount);
+}
+
+// Route `amount` of income straight into the reserve
+fn fund_reserve(s: &PoolSetup, amount: i128) {
+    let creditline = Address::generate(&s.client.env);
+    s.client.set_creditline(&s.admin, &creditline);
+    s.client.set_reserve_factor(&s.admin, &10_000);
+    s.token_admin.mint(&creditline, &amount);
+    s.token.transfer(&creditline, &s.client.address, &amount);
+    s.client.receive_repayment(&1, &0, &amount);
+}
+
+#[test]
+fn test_fund_boost_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup(&env);
+    fund_boost_budget(&s, 500);
+
+    assert_eq!(s.client.get_boost_budget(), 500);
+    assert_eq!(s.token.balance(&s.client.address), 500);
+    // The budget is not LP value and not part of the reserve
+    assert_eq!(s.client.get_total_pool_value(), 0);
+    assert_eq!(s.client.get_reserve_balance(), 0);
+}
+
+#[test]
+fn test_unlock_position_releases_shares_with_boost() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days180);
+    fund_boost_budget(&s, 1_000);
+    // The boost does not depend on the reserve, which is disabled by default
+    assert_eq!(s.client.get_reserve_factor(), 0);
+
+    env.ledger().set_timestamp(180 * DAY);
+    assert_eq!(s.client.unlock_position(&locker, &position_id), 10_246);
+
+    assert_eq!(s.client.get_shares(&locker, &Tranche::Senior), 10_246);
+    let position = s.client.get_position(&position_id);
+    assert_eq!(position.status, PositionStatus::Unlocked);
+    assert_eq!(position.bonus_shares, 246);
+    assert_eq!(position.bonus_paid, 246);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).total_shares, 20_246);
+
+    // The boost is paid out of the budget, not minted against the other LPs' value
+    assert_eq!(s.client.get_boost_budget(), 754);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 20_246);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        SHARE_VALUE_PRECISION
+    );
+}
+
+#[test]
+fn test_unlock_boost_is_capped_by_budget() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days180);
+    fund_boost_budget(&s, 100);
+    let flexible_value_before = s.client.get_share_value(&Tranche::Senior);
+
+    env.ledger().set_timestamp(180 * DAY);
+    assert_eq!(s.client.unlock_position(&locker, &position_id), 10_100);
+
+    let position = s.client.get_position(&position_id);
+    assert_eq!(position.bonus_shares, 246);
+    assert_eq!(position.bonus_paid, 100);
+    assert_eq!(s.client.get_boost_budget(), 0);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        flexible_value_before
+    );
+}
+
+#[test]
+fn test_unlock_without_budget_pays_no_boost() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days180);
+
+    env.ledger().set_timestamp(180 * DAY);
+    assert_eq!(s.client.unlock_position(&locker, &position_id), 10_000);
+    assert_eq!(s.client.get_position(&position_id).bonus_paid, 0);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).total_shares, 20_000);
+    assert_eq!(
+        s.client.get_share_value(&Tranche::Senior),
+        SHARE_VALUE_PRECISION
+    );
+}
+
+#[test]
+fn test_unlock_boost_never_draws_on_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days180);
+    fund_reserve(&s, 1_000);
+
+    env.ledger().set_timestamp(180 * DAY);
+    assert_eq!(s.client.unlock_position(&locker, &position_id), 10_000);
+    assert_eq!(s.client.get_reserve_balance(), 1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn test_unlock_position_before_maturity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days30);
+
+    env.ledger().set_timestamp(30 * DAY - 1);
+    s.client.unlock_position(&locker, &position_id);
+}
+
+#[test]
+fn test_early_withdrawal_penalty_stays_with_remaining_lps() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days180);
+
+    env.ledger().set_timestamp(10 * DAY);
+    assert_eq!(
+        s.client.withdraw_position_early(&locker, &position_id),
+        9_500
+    );
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("POSEXIT"));
+    let (amount, penalty): (i128, i128) = last.2.into_val(&env);
+    assert_eq!((amount, penalty), (9_500, 500));
+
+    // The boost is forfeited and the 5% penalty raises the remaining share value
+    assert_eq!(s.token.balance(&locker), 9_500);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).total_shares, 10_000);
+    assert_eq!(s.client.get_tranche(&Tranche::Senior).value, 10_500);
+    assert_eq!(s.client.get_share_value(&Tranche::Senior), 10_500_000);
+    assert_eq!(
+        s.client.get_position(&position_id).status,
+        PositionStatus::Withdrawn
+    );
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn test_early_withdrawal_after_maturity_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days30);
+
+    env.ledger().set_timestamp(30 * DAY);
+    s.client.withdraw_position_early(&locker, &position_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn test_unlock_position_by_non_owner_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, _locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days30);
+
+    env.ledger().set_timestamp(30 * DAY);
+    s.client
+        .unlock_position(&Address::generate(&env), &position_id);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #25)")]
+fn test_unlock_position_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (s, locker, position_id) = pool_with_locked_position(&env, LockupTerm::Days30);
+
+    env.ledger().set_timestamp(30 * DAY);
+    s.client.unlock_position(&locker, &position_id);
+    s.client.unlock_position(&locker, &position_id);
+}
@@ -0,0 +1,163 @@
+use soroban_sdk::{contracttype, Address, Symbol};
+
+// Kinked utilization interest rate model (annual rates in basis points)
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RateModel {
+    pub base_rate_bps: u32,           // Rate at 0% utilization
+    pub slope1_bps: u32,              // Added linearly up to optimal utilization
+    pub slope2_bps: u32,              // Added linearly from optimal to 100% utilization
+    pub optimal_utilization_bps: u32, // Kink point
+}
+
+// Pool tranches: junior LPs absorb default losses first in exchange for a larger interest share
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tranche {
+    Senior,
+    Junior,
+}
+
+// Share accounting of a tranche
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TrancheState {
+    pub total_shares: i128,
+    pub value: i128, // Tranche's claim on pool liquidity and outstanding loans
+}
+
+// Withdrawal request status
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WithdrawalStatus {
+    Pending,
+    Filled,
+    Cancelled,
+}
+
+// Queued withdrawal: shares are locked until liquidity returns to the pool
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithdrawalRequest {
+    pub request_id: u64,
+    pub provider: Address,
+    pub tranche: Tranche,
+    pub shares_requested: i128,
+    pub shares_remaining: i128, // Locked shares not yet redeemed
+    pub amount_paid: i128,      // Tokens paid out by fills so far
+    pub status: WithdrawalStatus,
+    pub created_at: u64, // Unix timestamp
+}
+
+// Reserve withdrawal scheduled by the admin, executable once the timelock has passed
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveWithdrawal {
+    pub amount: i128,
+    pub recipient: Address,
+    pub executable_at: u64, // Unix timestamp
+}
+
+// Lockup terms available for locked deposits
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockupTerm {
+    Days30,
+    Days90,
+    Days180,
+}
+
+// Locked position status
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PositionStatus {
+    Locked,
+    Unlocked,  // Shares and boost released to the provider at maturity
+    Withdrawn, // Exited early with a penalty
+}
+
+// Deposit locked for a fixed term; its shares are held by the pool until unlock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockedPosition {
+    pub position_id: u64,
+    pub provider: Address,
+    pub tranche: Tranche,
+    pub term: LockupTerm,
+    pub shares: i128,
+    pub bonus_shares: i128, // Boost owed at maturity
+    pub bonus_paid: i128,   // Boost shares minted on unlock, capped by the boost budget
+    pub locked_at: u64,     // Unix timestamp
+    pub unlock_at: u64,     // Unix timestamp
+    pub status: PositionStatus,
+}
+
+// Caps on outstanding principal as a share of total pool value, in basis points
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConcentrationLimits {
+    pub max_borrower_bps: u32,
+    pub max_merchant_bps: u32,
+    pub max_category_bps: u32, // Per merchant category
+}
+
+// Outstanding principal the pool has funded for a loan
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LoanExposure {
+    pub borrower: Address,
+    pub merchant: Address,
+    pub category: Symbol,
+    pub principal: i128,
+}
+
+// Constants
+
+/// Shares permanently locked by the first deposit so the share price cannot be
+/// inflated by a first depositor holding a handful of shares
+pub const MINIMUM_LIQUIDITY: i128 = 1_000;
+
+/// Fixed-point precision used when reporting the value of one share (7 decimals, like Stellar assets)
+pub const SHARE_VALUE_PRECISION: i128 = 10_000_000;
+
+pub const BPS_DENOMINATOR: i128 = 10_000; // 100% in basis points
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const SECONDS_PER_YEAR: i128 = 365 * SECONDS_PER_DAY as i128;
+
+// Default rate model: 2% base, +8% up to 80% utilization, +60% above it
+pub const DEFAULT_BASE_RATE_BPS: u32 = 200;
+pub const DEFAULT_SLOPE1_BPS: u32 = 800;
+pub const DEFAULT_SLOPE2_BPS: u32 = 6_000;
+pub const DEFAULT_OPTIMAL_UTILIZATION_BPS: u32 = 8_000;
+
+// Default share of pool income credited to the senior tranche
+pub const DEFAULT_SENIOR_INTEREST_BPS: u32 = 5_000;
+
+// Share of pool income diverted to the reserve (disabled until configured)
+pub const DEFAULT_RESERVE_FACTOR_BPS: u32 = 0;
+
+/// Delay between scheduling and executing a reserve withdrawal (7 days)
+pub const RESERVE_TIMELOCK_SECONDS: u64 = 7 * SECONDS_PER_DAY;
+
+// Annual yield boost of locked deposits, paid in tranche shares out of the boost budget on unlock
+pub const LOCKUP_30_DAYS_BOOST_BPS: u32 = 100;
+pub const LOCKUP_90_DAYS_BOOST_BPS: u32 = 250;
+pub const LOCKUP_180_DAYS_BOOST_BPS: u32 = 500;
+
+/// Smallest share amount that may be queued, so the queue cannot be stuffed with dust requests
+pub const MIN_WITHDRAWAL_REQUEST_SHARES: i128 = MINIMUM_LIQUIDITY;
+
+/// Withdrawal requests processed by a single pool operation; `process_queue` drains the rest
+pub const MAX_QUEUE_FILLS_PER_CALL: u32 = 10;
+
+/// Share of a locked position's value kept by the tranche when it exits before unlock
+pub const EARLY_WITHDRAWAL_PENALTY_BPS: u32 = 500;
+
+/// Lockup duration in seconds and annual boost in basis points of a term
+pub fn lockup_terms(term: LockupTerm) -> (u64, u32) {
+    match term {
+        LockupTerm::Days30 => (30 * SECONDS_PER_DAY, LOCKUP_30_DAYS_BOOST_BPS),
+        LockupTerm::Days90 => (90 * SECONDS_PER_DAY, LOCKUP_90_DAYS_BOOST_BPS),
+        LockupTerm::Days180 => (180 * SECONDS_PER_DAY, LOCKUP_180_DAYS_BOOST_BPS),
+    }
+}
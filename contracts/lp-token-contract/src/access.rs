@@ -0,0 +1,12 @@
+use soroban_sdk::{Address, Env};
+
+use crate::storage;
+
+/// Require authorization from the admin
+/// Returns the admin address
+pub fn require_admin(env: &Env) -> Address {
+    let admin = storage::get_admin(env);
+
+    admin.require_auth();
+    admin
+}
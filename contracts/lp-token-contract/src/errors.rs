@@ -0,0 +1,13 @@
+use soroban_sdk::contracterror;
+
+// Error types for the LP share token contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum LpTokenError {
+    InvalidAmount = 1,
+    InsufficientBalance = 2,
+    InsufficientAllowance = 3,
+    InvalidExpiration = 4,
+    OverflowError = 5,
+}
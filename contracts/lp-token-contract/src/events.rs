@@ -0,0 +1,40 @@
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+// Event topics (SEP-41)
+const TRANSFER: Symbol = symbol_short!("transfer");
+const APPROVE: Symbol = symbol_short!("approve");
+const MINT: Symbol = symbol_short!("mint");
+const BURN: Symbol = symbol_short!("burn");
+const SET_ADMIN: Symbol = symbol_short!("set_admin");
+
+/// Emit a transfer event
+pub fn emit_transfer(env: &Env, from: &Address, to: &Address, amount: i128) {
+    env.events().publish((TRANSFER, from, to), amount);
+}
+
+/// Emit an approve event
+pub fn emit_approve(
+    env: &Env,
+    from: &Address,
+    spender: &Address,
+    amount: i128,
+    expiration_ledger: u32,
+) {
+    env.events()
+        .publish((APPROVE, from, spender), (amount, expiration_ledger));
+}
+
+/// Emit a mint event
+pub fn emit_mint(env: &Env, admin: &Address, to: &Address, amount: i128) {
+    env.events().publish((MINT, admin, to), amount);
+}
+
+/// Emit a burn event
+pub fn emit_burn(env: &Env, from: &Address, amount: i128) {
+    env.events().publish((BURN, from), amount);
+}
+
+/// Emit an admin changed event
+pub fn emit_set_admin(env: &Env, admin: &Address, new_admin: &Address) {
+    env.events().publish((SET_ADMIN, admin), new_admin.clone());
+}
@@ -0,0 +1,225 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, token::TokenInterface, Address, Env,
+    String, Symbol,
+};
+
+// Module imports
+mod access;
+mod errors;
+mod events;
+mod storage;
+mod types;
+
+// Re-export types for external use
+pub use errors::LpTokenError;
+pub use types::TokenMetadata;
+
+/// LP share token contract structure
+/// One instance per liquidity pool tranche; the pool is the admin and mints and burns shares
+#[contract]
+pub struct LpTokenContract;
+
+/// Contract implementation
+#[contractimpl]
+impl LpTokenContract {
+    /// Get the version of this contract
+    pub fn get_version() -> Symbol {
+        symbol_short!("v1_0_0")
+    }
+
+    /// Initialize the token with its admin and metadata (requires the admin's auth)
+    /// Can only be called once (when admin is not set); hand admin to the pool with `set_admin`
+    pub fn initialize(env: Env, admin: Address, decimal: u32, name: String, symbol: String) {
+        let admin_opt: Option<Address> = env.storage().instance().get(&storage::ADMIN_KEY);
+        if admin_opt.is_some() {
+            panic!("Already initialized");
+        }
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+        storage::set_metadata(
+            &env,
+            &TokenMetadata {
+                decimal,
+                name,
+                symbol,
+            },
+        );
+        storage::extend_instance(&env);
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let admin = access::require_admin(&env);
+        storage::extend_instance(&env);
+
+        storage::set_admin(&env, &new_admin);
+
+        events::emit_set_admin(&env, &admin, &new_admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    /// Mint shares to an account (admin only)
+    pub fn mint(env: Env, to: Address, amount: i128) {
+        let admin = access::require_admin(&env);
+        Self::require_positive(&env, amount);
+        storage::extend_instance(&env);
+
+        let balance = Self::add(&env, storage::read_balance(&env, &to), amount);
+        storage::write_balance(&env, &to, balance);
+        storage::set_total_supply(
+            &env,
+            Self::add(&env, storage::get_total_supply(&env), amount),
+        );
+
+        events::emit_mint(&env, &admin, &to, amount);
+    }
+
+    /// Get the total supply of shares
+    pub fn total_supply(env: Env) -> i128 {
+        storage::get_total_supply(&env)
+    }
+
+    fn require_positive(env: &Env, amount: i128) {
+        if amount <= 0 {
+            panic_with_error!(env, LpTokenError::InvalidAmount);
+        }
+    }
+
+    fn spend_balance(env: &Env, from: &Address, amount: i128) {
+        let balance = storage::read_balance(env, from);
+        if balance < amount {
+            panic_with_error!(env, LpTokenError::InsufficientBalance);
+        }
+        storage::write_balance(env, from, balance - amount);
+    }
+
+    fn spend_allowance(env: &Env, from: &Address, spender: &Address, amount: i128) {
+        let allowance = Self::allowance_value(env, from, spender);
+        if allowance < amount {
+            panic_with_error!(env, LpTokenError::InsufficientAllowance);
+        }
+
+        if let Some(mut value) = storage::read_allowance(env, from, spender) {
+            value.amount = allowance - amount;
+            storage::write_allowance(env, from, spender, &value);
+        }
+    }
+
+    /// Allowance still valid at the current ledger (0 once expired)
+    fn allowance_value(env: &Env, from: &Address, spender: &Address) -> i128 {
+        match storage::read_allowance(env, from, spender) {
+            Some(value) if value.expiration_ledger >= env.ledger().sequence() => value.amount,
+            _ => 0,
+        }
+    }
+
+    fn burn_shares(env: &Env, from: &Address, amount: i128) {
+        Self::spend_balance(env, from, amount);
+        storage::set_total_supply(env, storage::get_total_supply(env) - amount);
+
+        events::emit_burn(env, from, amount);
+    }
+
+    fn move_shares(env: &Env, from: &Address, to: &Address, amount: i128) {
+        Self::spend_balance(env, from, amount);
+        let balance = Self::add(env, storage::read_balance(env, to), amount);
+        storage::write_balance(env, to, balance);
+
+        events::emit_transfer(env, from, to, amount);
+    }
+
+    fn add(env: &Env, value: i128, amount: i128) -> i128 {
+        value
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, LpTokenError::OverflowError))
+    }
+}
+
+/// SEP-41 token interface
+#[contractimpl]
+impl TokenInterface for LpTokenContract {
+    fn allowance(env: Env, from: Address, spender: Address) -> i128 {
+        Self::allowance_value(&env, &from, &spender)
+    }
+
+    fn approve(env: Env, from: Address, spender: Address, amount: i128, expiration_ledger: u32) {
+        from.require_auth();
+        storage::extend_instance(&env);
+
+        if amount < 0 {
+            panic_with_error!(&env, LpTokenError::InvalidAmount);
+        }
+        if amount > 0 && expiration_ledger < env.ledger().sequence() {
+            panic_with_error!(&env, LpTokenError::InvalidExpiration);
+        }
+
+        storage::write_allowance(
+            &env,
+            &from,
+            &spender,
+            &types::AllowanceValue {
+                amount,
+                expiration_ledger,
+            },
+        );
+
+        events::emit_approve(&env, &from, &spender, amount, expiration_ledger);
+    }
+
+    fn balance(env: Env, id: Address) -> i128 {
+        storage::read_balance(&env, &id)
+    }
+
+    fn transfer(env: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        storage::extend_instance(&env);
+        Self::require_positive(&env, amount);
+
+        Self::move_shares(&env, &from, &to, amount);
+    }
+
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        spender.require_auth();
+        storage::extend_instance(&env);
+        Self::require_positive(&env, amount);
+
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::move_shares(&env, &from, &to, amount);
+    }
+
+    fn burn(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        storage::extend_instance(&env);
+        Self::require_positive(&env, amount);
+
+        Self::burn_shares(&env, &from, amount);
+    }
+
+    fn burn_from(env: Env, spender: Address, from: Address, amount: i128) {
+        spender.require_auth();
+        storage::extend_instance(&env);
+        Self::require_positive(&env, amount);
+
+        Self::spend_allowance(&env, &from, &spender, amount);
+        Self::burn_shares(&env, &from, amount);
+    }
+
+    fn decimals(env: Env) -> u32 {
+        storage::get_metadata(&env).decimal
+    }
+
+    fn name(env: Env) -> String {
+        storage::get_metadata(&env).name
+    }
+
+    fn symbol(env: Env) -> String {
+        storage::get_metadata(&env).symbol
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,111 @@
+use soroban_sdk::{symbol_short, Address, Env, Symbol};
+
+use crate::types::{
+    AllowanceValue, DataKey, TokenMetadata, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+    INSTANCE_BUMP_AMOUNT, INSTANCE_LIFETIME_THRESHOLD,
+};
+
+// Storage keys for the LP share token contract
+pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+pub const METADATA: Symbol = symbol_short!("METADATA");
+pub const TOTAL_SUPPLY: Symbol = symbol_short!("SUPPLY");
+
+/// Get the admin address (the liquidity pool minting the shares)
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ADMIN_KEY)
+        .unwrap_or_else(|| panic!("Admin not set"))
+}
+
+/// Set the admin address
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN_KEY, admin);
+}
+
+/// Get the token metadata
+pub fn get_metadata(env: &Env) -> TokenMetadata {
+    env.storage()
+        .instance()
+        .get(&METADATA)
+        .unwrap_or_else(|| panic!("Metadata not set"))
+}
+
+/// Set the token metadata
+pub fn set_metadata(env: &Env, metadata: &TokenMetadata) {
+    env.storage().instance().set(&METADATA, metadata);
+}
+
+/// Keep the contract instance (admin, metadata, supply) alive
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Read an account balance (0 if none), extending the entry's TTL
+pub fn read_balance(env: &Env, id: &Address) -> i128 {
+    let key = DataKey::Balance(id.clone());
+    match env.storage().persistent().get::<_, i128>(&key) {
+        Some(balance) => {
+            env.storage().persistent().extend_ttl(
+                &key,
+                BALANCE_LIFETIME_THRESHOLD,
+                BALANCE_BUMP_AMOUNT,
+            );
+            balance
+        }
+        None => 0,
+    }
+}
+
+/// Write an account balance, dropping the entry when it reaches zero
+pub fn write_balance(env: &Env, id: &Address, amount: i128) {
+    let key = DataKey::Balance(id.clone());
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+
+    env.storage().persistent().set(&key, &amount);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Read an allowance (None if never approved)
+pub fn read_allowance(env: &Env, from: &Address, spender: &Address) -> Option<AllowanceValue> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Allowance(from.clone(), spender.clone()))
+}
+
+/// Write an allowance, dropping the entry when the amount reaches zero
+/// The entry is kept alive until its expiration ledger
+pub fn write_allowance(env: &Env, from: &Address, spender: &Address, value: &AllowanceValue) {
+    let key = DataKey::Allowance(from.clone(), spender.clone());
+    if value.amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+
+    env.storage().persistent().set(&key, value);
+    let live_for = value
+        .expiration_ledger
+        .saturating_sub(env.ledger().sequence());
+    if live_for > 0 {
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, live_for, live_for);
+    }
+}
+
+/// Get the total supply of shares
+pub fn get_total_supply(env: &Env) -> i128 {
+    env.storage().instance().get(&TOTAL_SUPPLY).unwrap_or(0)
+}
+
+/// Set the total supply of shares
+pub fn set_total_supply(env: &Env, amount: i128) {
+    env.storage().instance().set(&TOTAL_SUPPLY, &amount);
+}
@@ -0,0 +1,230 @@
+use crate::types::{DataKey, BALANCE_BUMP_AMOUNT};
+use crate::{LpTokenContract, LpTokenContractClient};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events, Ledger},
+    Address, Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+fn setup(env: &Env) -> (LpTokenContractClient<'_>, Address) {
+    let contract_id = env.register(LpTokenContract, ());
+    let client = LpTokenContractClient::new(env, &contract_id);
+
+    let pool = Address::generate(env);
+    env.mock_all_auths();
+    client.initialize(
+        &pool,
+        &7,
+        &String::from_str(env, "TrustUp Senior LP"),
+        &String::from_str(env, "tuSLP"),
+    );
+
+    (client, pool)
+}
+
+#[test]
+fn test_initialize_sets_metadata() {
+    let env = Env::default();
+    let (client, pool) = setup(&env);
+
+    assert_eq!(client.get_admin(), pool);
+    assert_eq!(client.decimals(), 7);
+    assert_eq!(client.name(), String::from_str(&env, "TrustUp Senior LP"));
+    assert_eq!(client.symbol(), String::from_str(&env, "tuSLP"));
+    assert_eq!(client.total_supply(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Already initialized")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    let (client, pool) = setup(&env);
+
+    client.initialize(
+        &pool,
+        &7,
+        &String::from_str(&env, "Other"),
+        &String::from_str(&env, "OTH"),
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_initialize_requires_admin_auth() {
+    let env = Env::default();
+    let contract_id = env.register(LpTokenContract, ());
+
+    LpTokenContractClient::new(&env, &contract_id).initialize(
+        &Address::generate(&env),
+        &7,
+        &String::from_str(&env, "TrustUp Senior LP"),
+        &String::from_str(&env, "tuSLP"),
+    );
+}
+
+#[test]
+fn test_mint_and_transfer() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+
+    client.mint(&alice, &1_000);
+    client.transfer(&alice, &bob, &300);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("transfer"));
+    let amount: i128 = last.2.into_val(&env);
+    assert_eq!(amount, 300);
+
+    assert_eq!(client.balance(&alice), 700);
+    assert_eq!(client.balance(&bob), 300);
+    assert_eq!(client.total_supply(), 1_000);
+}
+
+#[test]
+#[should_panic]
+fn test_mint_requires_admin_auth() {
+    let env = Env::default();
+    let (client, _pool) = setup(&env);
+    env.set_auths(&[]);
+
+    client.mint(&Address::generate(&env), &1_000);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_transfer_above_balance_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    client.mint(&alice, &100);
+
+    client.transfer(&alice, &Address::generate(&env), &101);
+}
+
+#[test]
+fn test_transfer_from_consumes_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.mint(&alice, &1_000);
+
+    client.approve(&alice, &spender, &500, &100);
+    assert_eq!(client.allowance(&alice, &spender), 500);
+
+    client.transfer_from(&spender, &alice, &bob, &200);
+
+    assert_eq!(client.allowance(&alice, &spender), 300);
+    assert_eq!(client.balance(&alice), 800);
+    assert_eq!(client.balance(&bob), 200);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_transfer_from_above_allowance_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1_000);
+    client.approve(&alice, &spender, &100, &100);
+
+    client.transfer_from(&spender, &alice, &spender, &101);
+}
+
+#[test]
+fn test_allowance_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+
+    client.approve(&alice, &spender, &500, &100);
+    env.ledger().set_sequence_number(101);
+
+    assert_eq!(client.allowance(&alice, &spender), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_approve_with_past_expiration_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    env.ledger().set_sequence_number(100);
+
+    client.approve(
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &500,
+        &99,
+    );
+}
+
+#[test]
+fn test_burn_and_burn_from_reduce_supply() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1_000);
+
+    client.burn(&alice, &100);
+    client.approve(&alice, &spender, &200, &100);
+    client.burn_from(&spender, &alice, &150);
+
+    assert_eq!(client.balance(&alice), 750);
+    assert_eq!(client.allowance(&alice, &spender), 50);
+    assert_eq!(client.total_supply(), 750);
+}
+
+#[test]
+fn test_balances_live_in_persistent_storage_with_extended_ttl() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _pool) = setup(&env);
+    let alice = Address::generate(&env);
+    let spender = Address::generate(&env);
+    client.mint(&alice, &1_000);
+    client.approve(&alice, &spender, &500, &(BALANCE_BUMP_AMOUNT * 2));
+
+    env.as_contract(&client.address, || {
+        let storage = env.storage().persistent();
+        assert_eq!(
+            storage.get_ttl(&DataKey::Balance(alice.clone())),
+            BALANCE_BUMP_AMOUNT
+        );
+        assert_eq!(
+            storage.get_ttl(&DataKey::Allowance(alice.clone(), spender.clone())),
+            BALANCE_BUMP_AMOUNT * 2
+        );
+    });
+
+    // Spending the whole balance removes the entry
+    client.burn(&alice, &1_000);
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DataKey::Balance(alice.clone())));
+    });
+}
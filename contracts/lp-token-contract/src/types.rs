@@ -0,0 +1,34 @@
+use soroban_sdk::{contracttype, Address, String};
+
+// Token metadata reported through the SEP-41 interface
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub decimal: u32,
+    pub name: String,
+    pub symbol: String,
+}
+
+// Per-account storage keys, kept in persistent storage so the instance does not grow
+// with the number of holders
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DataKey {
+    Balance(Address),
+    Allowance(Address, Address),
+}
+
+// Allowance granted by an owner to a spender, valid up to and including `expiration_ledger`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowanceValue {
+    pub amount: i128,
+    pub expiration_ledger: u32,
+}
+
+// Storage TTLs in ledgers (~5s each), as in the SEP-41 reference token
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+pub const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub const BALANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub const BALANCE_LIFETIME_THRESHOLD: u32 = BALANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
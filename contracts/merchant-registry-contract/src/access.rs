@@ -0,0 +1,21 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::errors::MerchantRegistryError;
+use crate::storage;
+
+/// Require that the given address is the admin, otherwise panic with NotAdmin error
+pub fn require_admin(env: &Env, caller: &Address) {
+    let admin = storage::get_admin(env);
+
+    if caller != &admin {
+        panic_with_error!(env, MerchantRegistryError::NotAdmin);
+    }
+}
+
+/// Require that the given address is the registered CreditLine contract, otherwise panic with NotCreditLine error
+pub fn require_creditline(env: &Env, caller: &Address) {
+    match storage::get_creditline(env) {
+        Some(creditline) if &creditline == caller => {}
+        _ => panic_with_error!(env, MerchantRegistryError::NotCreditLine),
+    }
+}
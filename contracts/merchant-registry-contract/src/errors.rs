@@ -0,0 +1,23 @@
+use soroban_sdk::contracterror;
+
+// Error types for the merchant registry contract
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MerchantRegistryError {
+    NotAdmin = 1,
+    MerchantAlreadyRegistered = 2,
+    MerchantNotFound = 3,
+    InvalidMerchantName = 4,
+    MerchantInactive = 5,
+    InvalidFeeRate = 6,
+    NotCreditLine = 7,
+    InvalidThreshold = 8,
+    InvalidAmount = 9,
+    Overflow = 10,
+    BondNotConfigured = 11,
+    InsufficientBond = 12,
+    BondAlreadyPosted = 13,
+    BondLocked = 14,
+    MerchantStillActive = 15,
+}
@@ -0,0 +1,77 @@
+use soroban_sdk::{symbol_short, Address, BytesN, Env, String, Symbol};
+
+use crate::types::MerchantStatus;
+
+// Event topics
+const MERCHANT_REGISTERED: Symbol = symbol_short!("MRCHREG");
+const MERCHANT_FEE_CHANGED: Symbol = symbol_short!("MRCHFEE");
+const MERCHANT_STATUS_CHANGED: Symbol = symbol_short!("MRCHSTAT");
+const MERCHANT_CATEGORY_CHANGED: Symbol = symbol_short!("MRCHCAT");
+const SIGNING_KEY_CHANGED: Symbol = symbol_short!("MRCHKEY");
+const MERCHANT_SUSPENDED: Symbol = symbol_short!("MRCHSUSP");
+const BOND_POSTED: Symbol = symbol_short!("BONDPOST");
+const BOND_SLASHED: Symbol = symbol_short!("BONDSLSH");
+const BOND_WITHDRAWN: Symbol = symbol_short!("BONDWDRW");
+
+/// Emit a merchant registered event
+pub fn emit_merchant_registered(env: &Env, merchant: &Address, name: &String, fee_rate_bps: u32) {
+    env.events().publish(
+        (MERCHANT_REGISTERED, merchant),
+        (name.clone(), fee_rate_bps),
+    );
+}
+
+/// Emit a merchant fee changed event
+pub fn emit_merchant_fee_changed(env: &Env, merchant: &Address, old_rate: u32, new_rate: u32) {
+    env.events()
+        .publish((MERCHANT_FEE_CHANGED, merchant), (old_rate, new_rate));
+}
+
+/// Emit a merchant category changed event
+pub fn emit_merchant_category_changed(env: &Env, merchant: &Address, category: &Symbol) {
+    env.events()
+        .publish((MERCHANT_CATEGORY_CHANGED, merchant), category.clone());
+}
+
+/// Emit a merchant signing key changed event
+pub fn emit_signing_key_changed(env: &Env, merchant: &Address, signing_key: &BytesN<32>) {
+    env.events()
+        .publish((SIGNING_KEY_CHANGED, merchant), signing_key.clone());
+}
+
+/// Emit a merchant status changed event
+pub fn emit_merchant_status_changed(env: &Env, merchant: &Address, status: &MerchantStatus) {
+    env.events()
+        .publish((MERCHANT_STATUS_CHANGED, merchant), status.clone());
+}
+
+/// Emit a merchant automatically suspended event
+pub fn emit_merchant_suspended(env: &Env, merchant: &Address, default_rate_bps: u32) {
+    env.events()
+        .publish((MERCHANT_SUSPENDED, merchant), default_rate_bps);
+}
+
+/// Emit a merchant bond posted event
+pub fn emit_bond_posted(env: &Env, merchant: &Address, amount: i128, bond_amount: i128) {
+    env.events()
+        .publish((BOND_POSTED, merchant), (amount, bond_amount));
+}
+
+/// Emit a merchant bond slashed event
+pub fn emit_bond_slashed(
+    env: &Env,
+    merchant: &Address,
+    amount: i128,
+    reason: &Symbol,
+    remaining_bond: i128,
+) {
+    env.events().publish(
+        (BOND_SLASHED, merchant),
+        (amount, reason.clone(), remaining_bond),
+    );
+}
+
+/// Emit a merchant bond withdrawn event
+pub fn emit_bond_withdrawn(env: &Env, merchant: &Address, amount: i128) {
+    env.events().publish((BOND_WITHDRAWN, merchant), amount);
+}
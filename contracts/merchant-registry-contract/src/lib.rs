@@ -0,0 +1,563 @@
+#![no_std]
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl, panic_with_error, symbol_short, token, vec, Address, BytesN, Env,
+    IntoVal, String, Symbol,
+};
+
+// Module imports
+mod access;
+mod errors;
+mod events;
+mod storage;
+mod types;
+
+// Re-export types for external use
+pub use errors::MerchantRegistryError;
+pub use types::{BondConfig, Merchant, MerchantStats, MerchantStatus, SuspensionPolicy};
+
+/// Merchant Registry contract structure
+#[contract]
+pub struct MerchantRegistryContract;
+
+/// Contract implementation
+#[contractimpl]
+impl MerchantRegistryContract {
+    /// Get the version of this contract
+    pub fn get_version() -> Symbol {
+        symbol_short!("v1_0_0")
+    }
+
+    /// Initialize the contract with its admin
+    /// Can only be called once (when admin is not set)
+    pub fn initialize(env: Env, admin: Address) {
+        let admin_opt: Option<Address> = env.storage().instance().get(&storage::ADMIN_KEY);
+        if admin_opt.is_some() {
+            panic!("Already initialized");
+        }
+
+        admin.require_auth();
+
+        storage::set_admin(&env, &admin);
+    }
+
+    pub fn set_admin(env: Env, new_admin: Address) {
+        let old_admin = storage::get_admin(&env);
+        old_admin.require_auth();
+        access::require_admin(&env, &old_admin);
+
+        storage::set_admin(&env, &new_admin);
+    }
+
+    pub fn get_admin(env: Env) -> Address {
+        storage::get_admin(&env)
+    }
+
+    /// Register a new merchant with its fee rate (admin only)
+    /// The merchant is active immediately, unless a bond is required, in which case
+    /// it stays pending until the bond is posted
+    pub fn register_merchant(
+        env: Env,
+        admin: Address,
+        merchant: Address,
+        name: String,
+        fee_rate_bps: u32,
+    ) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if storage::read_merchant(&env, &merchant).is_some() {
+            panic_with_error!(&env, MerchantRegistryError::MerchantAlreadyRegistered);
+        }
+
+        if name.is_empty() || name.len() > types::MAX_NAME_LENGTH {
+            panic_with_error!(&env, MerchantRegistryError::InvalidMerchantName);
+        }
+
+        Self::validate_fee_rate(&env, fee_rate_bps);
+
+        let status = if Self::required_bond(&env) > 0 {
+            MerchantStatus::PendingBond
+        } else {
+            MerchantStatus::Active
+        };
+
+        let record = Merchant {
+            name: name.clone(),
+            fee_rate_bps,
+            status,
+            registered_at: env.ledger().timestamp(),
+            bond_amount: 0,
+            deactivated_at: None,
+            category: types::DEFAULT_CATEGORY,
+            signing_key: None,
+        };
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_merchant_registered(&env, &merchant, &name, fee_rate_bps);
+    }
+
+    /// Update the fee rate charged to a merchant on each loan (admin only)
+    pub fn set_merchant_fee(env: Env, admin: Address, merchant: Address, fee_rate_bps: u32) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        Self::validate_fee_rate(&env, fee_rate_bps);
+
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+        let old_rate = record.fee_rate_bps;
+        record.fee_rate_bps = fee_rate_bps;
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_merchant_fee_changed(&env, &merchant, old_rate, fee_rate_bps);
+    }
+
+    /// Set the category a merchant belongs to (admin only)
+    pub fn set_merchant_category(env: Env, admin: Address, merchant: Address, category: Symbol) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+        record.category = category.clone();
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_merchant_category_changed(&env, &merchant, &category);
+    }
+
+    /// Get the category of a merchant
+    pub fn get_merchant_category(env: Env, merchant: Address) -> Symbol {
+        Self::get_merchant(env, merchant).category
+    }
+
+    /// Register the ed25519 public key the merchant signs its checkouts with (merchant only)
+    /// Once a key is set, the CreditLine only finances orders carrying a valid signature
+    pub fn set_signing_key(env: Env, merchant: Address, signing_key: BytesN<32>) {
+        merchant.require_auth();
+
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+        record.signing_key = Some(signing_key.clone());
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_signing_key_changed(&env, &merchant, &signing_key);
+    }
+
+    /// Get the key a merchant signs its checkouts with, if any
+    pub fn get_signing_key(env: Env, merchant: Address) -> Option<BytesN<32>> {
+        Self::get_merchant(env, merchant).signing_key
+    }
+
+    /// Deactivate a merchant so it can no longer originate loans (admin only)
+    pub fn deactivate_merchant(env: Env, admin: Address, merchant: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        Self::set_status(&env, &merchant, MerchantStatus::Inactive);
+    }
+
+    /// Reactivate a deactivated or suspended merchant (admin only)
+    /// The merchant must hold the full required bond
+    pub fn activate_merchant(env: Env, admin: Address, merchant: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let record = Self::get_merchant(env.clone(), merchant.clone());
+        if record.bond_amount < Self::required_bond(&env) {
+            panic_with_error!(&env, MerchantRegistryError::InsufficientBond);
+        }
+
+        Self::set_status(&env, &merchant, MerchantStatus::Active);
+    }
+
+    /// Get a merchant record
+    pub fn get_merchant(env: Env, merchant: Address) -> Merchant {
+        storage::read_merchant(&env, &merchant)
+            .unwrap_or_else(|| panic_with_error!(&env, MerchantRegistryError::MerchantNotFound))
+    }
+
+    /// Check if a merchant is registered and active
+    pub fn is_active_merchant(env: Env, merchant: Address) -> bool {
+        match storage::read_merchant(&env, &merchant) {
+            Some(record) => record.status == MerchantStatus::Active,
+            None => false,
+        }
+    }
+
+    /// Get the fee rate (in basis points) charged to an active merchant
+    pub fn get_merchant_fee(env: Env, merchant: Address) -> u32 {
+        let record = Self::get_merchant(env.clone(), merchant);
+
+        if record.status != MerchantStatus::Active {
+            panic_with_error!(&env, MerchantRegistryError::MerchantInactive);
+        }
+
+        record.fee_rate_bps
+    }
+
+    /// Set the CreditLine contract allowed to report loan outcomes (admin only)
+    pub fn set_creditline(env: Env, admin: Address, creditline: Address) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        storage::set_creditline(&env, &creditline);
+    }
+
+    /// Configure the default-rate threshold above which merchants are suspended (admin only)
+    pub fn set_suspension_policy(env: Env, admin: Address, policy: SuspensionPolicy) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if policy.max_default_rate_bps as i128 > types::BPS_DENOMINATOR {
+            panic_with_error!(&env, MerchantRegistryError::InvalidThreshold);
+        }
+
+        storage::set_suspension_policy(&env, &policy);
+    }
+
+    /// Get the automatic suspension policy, if configured
+    pub fn get_suspension_policy(env: Env) -> Option<SuspensionPolicy> {
+        storage::get_suspension_policy(&env)
+    }
+
+    /// Configure the bond merchants must stake before originating loans (admin only)
+    pub fn set_bond_config(env: Env, admin: Address, config: BondConfig) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        if config.amount < 0 {
+            panic_with_error!(&env, MerchantRegistryError::InvalidAmount);
+        }
+
+        storage::set_bond_config(&env, &config);
+    }
+
+    /// Get the merchant bond configuration, if configured
+    pub fn get_bond_config(env: Env) -> Option<BondConfig> {
+        storage::get_bond_config(&env)
+    }
+
+    /// Post (or top up) the merchant's bond to the required amount
+    /// A merchant pending its bond becomes active once the bond is posted
+    pub fn post_bond(env: Env, merchant: Address) {
+        merchant.require_auth();
+
+        let config = Self::bond_config(&env);
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+
+        let amount = config
+            .amount
+            .checked_sub(record.bond_amount)
+            .unwrap_or_else(|| panic_with_error!(&env, MerchantRegistryError::Overflow));
+        if amount <= 0 {
+            panic_with_error!(&env, MerchantRegistryError::BondAlreadyPosted);
+        }
+
+        token::Client::new(&env, &config.token).transfer(
+            &merchant,
+            &env.current_contract_address(),
+            &amount,
+        );
+
+        record.bond_amount = config.amount;
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_bond_posted(&env, &merchant, amount, record.bond_amount);
+
+        if record.status == MerchantStatus::PendingBond {
+            Self::set_status(&env, &merchant, MerchantStatus::Active);
+        }
+    }
+
+    /// Slash part or all of a merchant's bond into the liquidity pool (admin only)
+    /// An active merchant left with less than the required bond is deactivated
+    pub fn slash_bond(env: Env, admin: Address, merchant: Address, amount: i128, reason: Symbol) {
+        admin.require_auth();
+        access::require_admin(&env, &admin);
+
+        let config = Self::bond_config(&env);
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+
+        if amount <= 0 || amount > record.bond_amount {
+            panic_with_error!(&env, MerchantRegistryError::InvalidAmount);
+        }
+
+        Self::pay_slashed_bond(&env, &config, amount);
+
+        record.bond_amount -= amount;
+        storage::write_merchant(&env, &merchant, &record);
+
+        events::emit_bond_slashed(&env, &merchant, amount, &reason, record.bond_amount);
+
+        if record.status == MerchantStatus::Active && record.bond_amount < config.amount {
+            Self::set_status(&env, &merchant, MerchantStatus::Inactive);
+        }
+    }
+
+    /// Claim up to `amount` of a merchant's bond to cover a refund the merchant owes (CreditLine only)
+    /// The claimed tokens are sent to the CreditLine. An active merchant left with less than
+    /// the required bond is deactivated. Returns the amount claimed (zero without a bond)
+    pub fn claim_bond(env: Env, creditline: Address, merchant: Address, amount: i128) -> i128 {
+        creditline.require_auth();
+        access::require_creditline(&env, &creditline);
+
+        if amount < 0 {
+            panic_with_error!(&env, MerchantRegistryError::InvalidAmount);
+        }
+
+        let config = match storage::get_bond_config(&env) {
+            Some(config) => config,
+            None => return 0,
+        };
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+
+        let claimed = amount.min(record.bond_amount);
+        if claimed == 0 {
+            return 0;
+        }
+
+        record.bond_amount -= claimed;
+        storage::write_merchant(&env, &merchant, &record);
+
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &creditline,
+            &claimed,
+        );
+
+        events::emit_bond_slashed(
+            &env,
+            &merchant,
+            claimed,
+            &symbol_short!("dispute"),
+            record.bond_amount,
+        );
+
+        if record.status == MerchantStatus::Active && record.bond_amount < config.amount {
+            Self::set_status(&env, &merchant, MerchantStatus::Inactive);
+        }
+
+        claimed
+    }
+
+    /// Withdraw the full bond of a deactivated merchant once the cooldown has elapsed
+    pub fn withdraw_bond(env: Env, merchant: Address) -> i128 {
+        merchant.require_auth();
+
+        let config = Self::bond_config(&env);
+        let mut record = Self::get_merchant(env.clone(), merchant.clone());
+
+        let deactivated_at = match (&record.status, record.deactivated_at) {
+            (MerchantStatus::Inactive, Some(deactivated_at)) => deactivated_at,
+            _ => panic_with_error!(&env, MerchantRegistryError::MerchantStillActive),
+        };
+
+        let unlocks_at = deactivated_at.saturating_add(config.cooldown_seconds);
+        if env.ledger().timestamp() < unlocks_at {
+            panic_with_error!(&env, MerchantRegistryError::BondLocked);
+        }
+
+        let amount = record.bond_amount;
+        if amount <= 0 {
+            panic_with_error!(&env, MerchantRegistryError::InvalidAmount);
+        }
+
+        record.bond_amount = 0;
+        storage::write_merchant(&env, &merchant, &record);
+
+        token::Client::new(&env, &config.token).transfer(
+            &env.current_contract_address(),
+            &merchant,
+            &amount,
+        );
+
+        events::emit_bond_withdrawn(&env, &merchant, amount);
+
+        amount
+    }
+
+    /// Record a loan originated by a merchant (CreditLine only)
+    pub fn record_loan_originated(env: Env, creditline: Address, merchant: Address, amount: i128) {
+        let mut stats = Self::authorize_report(&env, &creditline, &merchant, amount);
+
+        stats.loans_originated = Self::increment(&env, stats.loans_originated);
+        stats.volume_originated = Self::add(&env, stats.volume_originated, amount);
+        storage::write_stats(&env, &merchant, &stats);
+    }
+
+    /// Record a defaulted loan and suspend the merchant if its default rate is too high (CreditLine only)
+    pub fn record_loan_defaulted(env: Env, creditline: Address, merchant: Address, amount: i128) {
+        let mut stats = Self::authorize_report(&env, &creditline, &merchant, amount);
+
+        stats.loans_defaulted = Self::increment(&env, stats.loans_defaulted);
+        stats.volume_defaulted = Self::add(&env, stats.volume_defaulted, amount);
+        storage::write_stats(&env, &merchant, &stats);
+
+        Self::enforce_suspension_policy(&env, &merchant, &stats);
+    }
+
+    /// Record a refunded loan amount (CreditLine only)
+    /// A loan refunded in several parts is counted once, on its first refund
+    pub fn record_loan_refunded(
+        env: Env,
+        creditline: Address,
+        merchant: Address,
+        amount: i128,
+        first_refund: bool,
+    ) {
+        let mut stats = Self::authorize_report(&env, &creditline, &merchant, amount);
+
+        if first_refund {
+            stats.loans_refunded = Self::increment(&env, stats.loans_refunded);
+        }
+        stats.volume_refunded = Self::add(&env, stats.volume_refunded, amount);
+        storage::write_stats(&env, &merchant, &stats);
+    }
+
+    /// Get the loan statistics reported for a merchant
+    pub fn get_merchant_stats(env: Env, merchant: Address) -> MerchantStats {
+        storage::read_stats(&env, &merchant)
+    }
+
+    /// Get a merchant's default rate (defaulted / net originated volume) in basis points
+    pub fn get_default_rate(env: Env, merchant: Address) -> u32 {
+        Self::default_rate_bps(&env, &storage::read_stats(&env, &merchant))
+    }
+
+    /// Validate a loan outcome report and return the merchant's current stats
+    fn authorize_report(
+        env: &Env,
+        creditline: &Address,
+        merchant: &Address,
+        amount: i128,
+    ) -> MerchantStats {
+        creditline.require_auth();
+        access::require_creditline(env, creditline);
+
+        if storage::read_merchant(env, merchant).is_none() {
+            panic_with_error!(env, MerchantRegistryError::MerchantNotFound);
+        }
+
+        if amount < 0 {
+            panic_with_error!(env, MerchantRegistryError::InvalidAmount);
+        }
+
+        storage::read_stats(env, merchant)
+    }
+
+    /// Suspend an active merchant whose default rate exceeds the configured threshold
+    fn enforce_suspension_policy(env: &Env, merchant: &Address, stats: &MerchantStats) {
+        let policy = match storage::get_suspension_policy(env) {
+            Some(policy) => policy,
+            None => return,
+        };
+
+        if stats.loans_originated < policy.min_loans {
+            return;
+        }
+
+        let default_rate_bps = Self::default_rate_bps(env, stats);
+        if default_rate_bps <= policy.max_default_rate_bps {
+            return;
+        }
+
+        let record = storage::read_merchant(env, merchant)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::MerchantNotFound));
+        if record.status != MerchantStatus::Active {
+            return;
+        }
+
+        Self::set_status(env, merchant, MerchantStatus::Suspended);
+        events::emit_merchant_suspended(env, merchant, default_rate_bps);
+    }
+
+    /// Defaulted volume as a share of originated volume net of refunds, in basis points
+    /// Refunded loans never had the chance to default, so they do not dilute the rate
+    fn default_rate_bps(env: &Env, stats: &MerchantStats) -> u32 {
+        let net_originated = stats
+            .volume_originated
+            .checked_sub(stats.volume_refunded)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::Overflow));
+        if net_originated <= 0 {
+            return 0;
+        }
+
+        let rate = stats
+            .volume_defaulted
+            .checked_mul(types::BPS_DENOMINATOR)
+            .and_then(|v| v.checked_div(net_originated))
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::Overflow));
+
+        rate.min(types::BPS_DENOMINATOR) as u32
+    }
+
+    fn increment(env: &Env, value: u32) -> u32 {
+        value
+            .checked_add(1)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::Overflow))
+    }
+
+    fn add(env: &Env, value: i128, amount: i128) -> i128 {
+        value
+            .checked_add(amount)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::Overflow))
+    }
+
+    /// Validate fee rate does not exceed the maximum merchant discount rate
+    fn validate_fee_rate(env: &Env, fee_rate_bps: u32) {
+        if fee_rate_bps > types::MAX_FEE_RATE_BPS {
+            panic_with_error!(env, MerchantRegistryError::InvalidFeeRate);
+        }
+    }
+
+    /// Get the bond configuration, panicking if bonds are not configured
+    fn bond_config(env: &Env) -> BondConfig {
+        storage::get_bond_config(env)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::BondNotConfigured))
+    }
+
+    /// Bond a merchant must hold to be active (zero when bonds are not configured)
+    fn required_bond(env: &Env) -> i128 {
+        storage::get_bond_config(env).map_or(0, |config| config.amount)
+    }
+
+    /// Pay a slashed bond into the liquidity pool so it is booked as pool income
+    /// The pool pulls the tokens from this contract, so the nested transfer is pre-authorized
+    fn pay_slashed_bond(env: &Env, config: &BondConfig, amount: i128) {
+        let registry = env.current_contract_address();
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: config.token.clone(),
+                    fn_name: symbol_short!("transfer"),
+                    args: (registry.clone(), config.liquidity_pool.clone(), amount).into_val(env),
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+
+        env.invoke_contract::<()>(
+            &config.liquidity_pool,
+            &Symbol::new(env, "receive_income"),
+            vec![env, registry.into_val(env), amount.into_val(env)],
+        );
+    }
+
+    /// Update a merchant's status and emit the corresponding event
+    /// Deactivation starts the bond withdrawal cooldown
+    fn set_status(env: &Env, merchant: &Address, status: MerchantStatus) {
+        let mut record = storage::read_merchant(env, merchant)
+            .unwrap_or_else(|| panic_with_error!(env, MerchantRegistryError::MerchantNotFound));
+
+        record.deactivated_at = match status {
+            MerchantStatus::Inactive => Some(env.ledger().timestamp()),
+            _ => None,
+        };
+        record.status = status.clone();
+        storage::write_merchant(env, merchant, &record);
+
+        events::emit_merchant_status_changed(env, merchant, &status);
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+use soroban_sdk::{symbol_short, Address, Env, Map, Symbol};
+
+use crate::types::{BondConfig, Merchant, MerchantStats, SuspensionPolicy};
+
+// Storage keys for the merchant registry contract
+pub const ADMIN_KEY: Symbol = symbol_short!("ADMIN");
+pub const MERCHANTS_MAP: Symbol = symbol_short!("MERCHANTS");
+pub const CREDITLINE: Symbol = symbol_short!("CREDITLN");
+pub const STATS_MAP: Symbol = symbol_short!("STATS");
+pub const SUSPENSION_POLICY: Symbol = symbol_short!("SUSPPOL");
+pub const BOND_CONFIG: Symbol = symbol_short!("BONDCFG");
+
+/// Get the admin address from storage
+pub fn get_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&ADMIN_KEY)
+        .unwrap_or_else(|| panic!("Admin not set"))
+}
+
+/// Set the admin address in storage
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&ADMIN_KEY, admin);
+}
+
+/// Read a merchant record from storage
+pub fn read_merchant(env: &Env, merchant: &Address) -> Option<Merchant> {
+    let merchants: Map<Address, Merchant> = env
+        .storage()
+        .instance()
+        .get(&MERCHANTS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    merchants.get(merchant.clone())
+}
+
+/// Write a merchant record to storage
+pub fn write_merchant(env: &Env, merchant: &Address, record: &Merchant) {
+    let mut merchants: Map<Address, Merchant> = env
+        .storage()
+        .instance()
+        .get(&MERCHANTS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    merchants.set(merchant.clone(), record.clone());
+    env.storage().instance().set(&MERCHANTS_MAP, &merchants);
+}
+
+/// Get the CreditLine contract address allowed to report loan outcomes
+pub fn get_creditline(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CREDITLINE)
+}
+
+/// Set the CreditLine contract address
+pub fn set_creditline(env: &Env, address: &Address) {
+    env.storage().instance().set(&CREDITLINE, address);
+}
+
+/// Read a merchant's loan statistics (zeroed if nothing was reported yet)
+pub fn read_stats(env: &Env, merchant: &Address) -> MerchantStats {
+    let stats: Map<Address, MerchantStats> = env
+        .storage()
+        .instance()
+        .get(&STATS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    stats.get(merchant.clone()).unwrap_or_default()
+}
+
+/// Write a merchant's loan statistics
+pub fn write_stats(env: &Env, merchant: &Address, record: &MerchantStats) {
+    let mut stats: Map<Address, MerchantStats> = env
+        .storage()
+        .instance()
+        .get(&STATS_MAP)
+        .unwrap_or_else(|| Map::new(env));
+
+    stats.set(merchant.clone(), record.clone());
+    env.storage().instance().set(&STATS_MAP, &stats);
+}
+
+/// Get the automatic suspension policy, if configured
+pub fn get_suspension_policy(env: &Env) -> Option<SuspensionPolicy> {
+    env.storage().instance().get(&SUSPENSION_POLICY)
+}
+
+/// Set the automatic suspension policy
+pub fn set_suspension_policy(env: &Env, policy: &SuspensionPolicy) {
+    env.storage().instance().set(&SUSPENSION_POLICY, policy);
+}
+
+/// Get the merchant bond configuration, if bonds are required
+pub fn get_bond_config(env: &Env) -> Option<BondConfig> {
+    env.storage().instance().get(&BOND_CONFIG)
+}
+
+/// Set the merchant bond configuration
+pub fn set_bond_config(env: &Env, config: &BondConfig) {
+    env.storage().instance().set(&BOND_CONFIG, config);
+}
@@ -0,0 +1,707 @@
+use crate::{
+    BondConfig, MerchantRegistryContract, MerchantRegistryContractClient, MerchantStats,
+    MerchantStatus, SuspensionPolicy,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    token, Address, BytesN, Env, IntoVal, String, Symbol, Val, Vec,
+};
+
+fn setup(env: &Env) -> (MerchantRegistryContractClient<'_>, Address) {
+    let contract_id = env.register(MerchantRegistryContract, ());
+    let client = MerchantRegistryContractClient::new(env, &contract_id);
+
+    let admin = Address::generate(env);
+    client.initialize(&admin);
+
+    (client, admin)
+}
+
+#[test]
+fn test_initialize() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Already initialized")]
+fn test_initialize_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+
+    client.initialize(&admin);
+}
+
+#[test]
+fn test_get_version() {
+    let version = MerchantRegistryContract::get_version();
+    assert_eq!(version, symbol_short!("v1_0_0"));
+}
+
+#[test]
+fn test_register_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(
+        &admin,
+        &merchant,
+        &String::from_str(&env, "Laptop Store"),
+        &250,
+    );
+
+    let record = client.get_merchant(&merchant);
+    assert_eq!(record.name, String::from_str(&env, "Laptop Store"));
+    assert_eq!(record.fee_rate_bps, 250);
+    assert_eq!(record.status, MerchantStatus::Active);
+    assert!(client.is_active_merchant(&merchant));
+    assert_eq!(client.get_merchant_fee(&merchant), 250);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #2)")]
+fn test_register_merchant_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+    let name = String::from_str(&env, "Laptop Store");
+
+    client.register_merchant(&admin, &merchant, &name, &250);
+    client.register_merchant(&admin, &merchant, &name, &250);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_register_merchant_not_admin_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin) = setup(&env);
+    let not_admin = Address::generate(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&not_admin, &merchant, &String::from_str(&env, "Shop"), &250);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_register_merchant_with_empty_name_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, ""), &250);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_register_merchant_with_excessive_fee_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    // 1001 bps is above the 10% maximum merchant discount rate
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &1_001);
+}
+
+#[test]
+fn test_set_merchant_category() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+    assert_eq!(
+        client.get_merchant_category(&merchant),
+        symbol_short!("general")
+    );
+
+    client.set_merchant_category(&admin, &merchant, &symbol_short!("travel"));
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("MRCHCAT"));
+
+    assert_eq!(
+        client.get_merchant_category(&merchant),
+        symbol_short!("travel")
+    );
+}
+
+#[test]
+fn test_set_signing_key() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+    assert_eq!(client.get_signing_key(&merchant), None);
+
+    let key = BytesN::from_array(&env, &[7; 32]);
+    client.set_signing_key(&merchant, &key);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("MRCHKEY"));
+
+    assert_eq!(client.get_signing_key(&merchant), Some(key));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_set_signing_key_for_unknown_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin) = setup(&env);
+
+    client.set_signing_key(
+        &Address::generate(&env),
+        &BytesN::from_array(&env, &[7; 32]),
+    );
+}
+
+#[test]
+fn test_set_merchant_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+    client.set_merchant_fee(&admin, &merchant, &400);
+
+    // Verify MRCHFEE event carries the old and new rate
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("MRCHFEE"));
+
+    let (old_rate, new_rate): (u32, u32) = last.2.into_val(&env);
+    assert_eq!(old_rate, 250);
+    assert_eq!(new_rate, 400);
+
+    assert_eq!(client.get_merchant_fee(&merchant), 400);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_set_fee_for_unknown_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+
+    client.set_merchant_fee(&admin, &Address::generate(&env), &100);
+}
+
+#[test]
+fn test_deactivate_and_activate_merchant() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.deactivate_merchant(&admin, &merchant);
+    assert!(!client.is_active_merchant(&merchant));
+    assert_eq!(
+        client.get_merchant(&merchant).status,
+        MerchantStatus::Inactive
+    );
+
+    client.activate_merchant(&admin, &merchant);
+    assert!(client.is_active_merchant(&merchant));
+}
+
+#[test]
+fn test_unknown_merchant_is_not_active() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, _admin) = setup(&env);
+
+    assert!(!client.is_active_merchant(&Address::generate(&env)));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn test_get_fee_for_inactive_merchant_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+    let merchant = Address::generate(&env);
+
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+    client.deactivate_merchant(&admin, &merchant);
+
+    client.get_merchant_fee(&merchant);
+}
+
+// Merchant statistics and automatic suspension tests
+
+fn setup_with_creditline(env: &Env) -> (MerchantRegistryContractClient<'_>, Address, Address) {
+    let (client, admin) = setup(env);
+
+    let creditline = Address::generate(env);
+    client.set_creditline(&admin, &creditline);
+
+    (client, admin, creditline)
+}
+
+#[test]
+fn test_record_loan_outcomes_updates_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, creditline) = setup_with_creditline(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.record_loan_originated(&creditline, &merchant, &1000);
+    client.record_loan_originated(&creditline, &merchant, &3000);
+    client.record_loan_refunded(&creditline, &merchant, &300, &true);
+    client.record_loan_refunded(&creditline, &merchant, &200, &false);
+    client.record_loan_defaulted(&creditline, &merchant, &1000);
+
+    let stats = client.get_merchant_stats(&merchant);
+    assert_eq!(
+        stats,
+        MerchantStats {
+            loans_originated: 2,
+            volume_originated: 4000,
+            loans_defaulted: 1,
+            volume_defaulted: 1000,
+            loans_refunded: 1,
+            volume_refunded: 500,
+        }
+    );
+    // 1000 defaulted out of the 3500 originated and not refunded
+    assert_eq!(client.get_default_rate(&merchant), 2_857);
+
+    // No policy configured, so the merchant stays active
+    assert!(client.is_active_merchant(&merchant));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_record_outcome_from_non_creditline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, _creditline) = setup_with_creditline(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.record_loan_originated(&Address::generate(&env), &merchant, &1000);
+}
+
+#[test]
+fn test_merchant_suspended_above_default_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, creditline) = setup_with_creditline(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.set_suspension_policy(
+        &admin,
+        &SuspensionPolicy {
+            max_default_rate_bps: 2_000,
+            min_loans: 2,
+        },
+    );
+
+    client.record_loan_originated(&creditline, &merchant, &1000);
+    client.record_loan_originated(&creditline, &merchant, &1000);
+
+    // 10% default rate stays under the 20% threshold
+    client.record_loan_defaulted(&creditline, &merchant, &200);
+    assert!(client.is_active_merchant(&merchant));
+
+    // 30% default rate crosses the threshold
+    client.record_loan_defaulted(&creditline, &merchant, &400);
+
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let last = events.last().unwrap();
+    let event_type: Symbol = last.1.get(0).unwrap().into_val(&env);
+    assert_eq!(event_type, symbol_short!("MRCHSUSP"));
+    let default_rate: u32 = last.2.into_val(&env);
+    assert_eq!(default_rate, 3_000);
+
+    assert!(!client.is_active_merchant(&merchant));
+    assert_eq!(
+        client.get_merchant(&merchant).status,
+        MerchantStatus::Suspended
+    );
+
+    // Admin can lift the suspension
+    client.activate_merchant(&admin, &merchant);
+    assert!(client.is_active_merchant(&merchant));
+}
+
+#[test]
+fn test_refunded_volume_does_not_dilute_default_rate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, creditline) = setup_with_creditline(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.set_suspension_policy(
+        &admin,
+        &SuspensionPolicy {
+            max_default_rate_bps: 2_000,
+            min_loans: 2,
+        },
+    );
+
+    client.record_loan_originated(&creditline, &merchant, &1000);
+    client.record_loan_originated(&creditline, &merchant, &1000);
+    client.record_loan_refunded(&creditline, &merchant, &1000, &true);
+
+    // 300 defaulted out of 1000 still outstanding is a 30% rate, not 15%
+    client.record_loan_defaulted(&creditline, &merchant, &300);
+    assert_eq!(client.get_default_rate(&merchant), 3_000);
+    assert!(!client.is_active_merchant(&merchant));
+}
+
+#[test]
+fn test_suspension_waits_for_minimum_loan_count() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin, creditline) = setup_with_creditline(&env);
+    let merchant = Address::generate(&env);
+    client.register_merchant(&admin, &merchant, &String::from_str(&env, "Shop"), &250);
+
+    client.set_suspension_policy(
+        &admin,
+        &SuspensionPolicy {
+            max_default_rate_bps: 1_000,
+            min_loans: 5,
+        },
+    );
+
+    client.record_loan_originated(&creditline, &merchant, &1000);
+    client.record_loan_defaulted(&creditline, &merchant, &1000);
+
+    // 100% default rate, but only one loan has been originated
+    assert!(client.is_active_merchant(&merchant));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn test_set_suspension_policy_above_100_percent_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let (client, admin) = setup(&env);
+
+    client.set_suspension_policy(
+        &admin,
+        &SuspensionPolicy {
+            max_default_rate_bps: 10_001,
+            min_loans: 1,
+        },
+    );
+}
+
+// Merchant bond tests
+
+// Liquidity pool stand-in that pulls slashed bonds in and books them as income
+#[contract]
+struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn init(env: Env, token: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("TOKEN"), &token);
+    }
+
+    pub fn receive_income(env: Env, from: Address, amount: i128) {
+        from.require_auth();
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("TOKEN"))
+            .unwrap();
+        token::Client::new(&env, &token).transfer(&from, &env.current_contract_address(), &amount);
+
+        let income: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("INCOME"))
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("INCOME"), &(income + amount));
+    }
+
+    pub fn income(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("INCOME"))
+            .unwrap_or(0)
+    }
+}
+
+const BOND: i128 = 5_000;
+const COOLDOWN: u64 = 7 * 24 * 60 * 60;
+
+struct BondSetup<'a> {
+    client: MerchantRegistryContractClient<'a>,
+    admin: Address,
+    token: token::Client<'a>,
+    liquidity_pool: Address,
+    merchant: Address,
+}
+
+fn setup_with_bond(env: &Env) -> BondSetup<'_> {
+    let (client, admin) = setup(env);
+
+    let token_admin = Address::generate(env);
+    let token_id = env
+        .register_stellar_asset_contract_v2(token_admin)
+        .address();
+    let liquidity_pool = env.register(MockPool, ());
+    MockPoolClient::new(env, &liquidity_pool).init(&token_id);
+
+    client.set_bond_config(
+        &admin,
+        &BondConfig {
+            token: token_id.clone(),
+            amount: BOND,
+            liquidity_pool: liquidity_pool.clone(),
+            cooldown_seconds: COOLDOWN,
+        },
+    );
+
+    let merchant = Address::generate(env);
+    token::StellarAssetClient::new(env, &token_id).mint(&merchant, &(BOND * 2));
+    client.register_merchant(&admin, &merchant, &String::from_str(env, "Shop"), &250);
+
+    BondSetup {
+        client,
+        admin,
+        token: token::Client::new(env, &token_id),
+        liquidity_pool,
+        merchant,
+    }
+}
+
+#[test]
+fn test_merchant_pending_until_bond_posted() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+
+    assert_eq!(
+        s.client.get_merchant(&s.merchant).status,
+        MerchantStatus::PendingBond
+    );
+    assert!(!s.client.is_active_merchant(&s.merchant));
+
+    s.client.post_bond(&s.merchant);
+
+    let record = s.client.get_merchant(&s.merchant);
+    assert_eq!(record.status, MerchantStatus::Active);
+    assert_eq!(record.bond_amount, BOND);
+    assert_eq!(s.token.balance(&s.client.address), BOND);
+    assert_eq!(s.token.balance(&s.merchant), BOND);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn test_post_bond_twice_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+
+    s.client.post_bond(&s.merchant);
+    s.client.post_bond(&s.merchant);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn test_activate_without_bond_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+
+    s.client.activate_merchant(&s.admin, &s.merchant);
+}
+
+#[test]
+fn test_slash_bond_sends_to_liquidity_pool() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+
+    s.client
+        .slash_bond(&s.admin, &s.merchant, &2_000, &symbol_short!("fraud"));
+
+    // Slashing emits BONDSLSH before the status change that follows it
+    let events: Vec<(Address, Vec<Val>, Val)> = env.events().all();
+    let slashed = events
+        .iter()
+        .find(|event| {
+            let event_type: Symbol = event.1.get(0).unwrap().into_val(&env);
+            event_type == symbol_short!("BONDSLSH")
+        })
+        .unwrap();
+    let (amount, reason, remaining): (i128, Symbol, i128) = slashed.2.into_val(&env);
+    assert_eq!(amount, 2_000);
+    assert_eq!(reason, symbol_short!("fraud"));
+    assert_eq!(remaining, 3_000);
+
+    // The pool books the slashed bond as income rather than an untracked transfer
+    assert_eq!(s.token.balance(&s.liquidity_pool), 2_000);
+    assert_eq!(MockPoolClient::new(&env, &s.liquidity_pool).income(), 2_000);
+    assert_eq!(s.token.balance(&s.client.address), 3_000);
+
+    // Bond is now short of the requirement, so the merchant is deactivated
+    let record = s.client.get_merchant(&s.merchant);
+    assert_eq!(record.status, MerchantStatus::Inactive);
+    assert_eq!(record.bond_amount, 3_000);
+
+    // Topping the bond back up allows the admin to reactivate
+    s.client.post_bond(&s.merchant);
+    s.client.activate_merchant(&s.admin, &s.merchant);
+    assert!(s.client.is_active_merchant(&s.merchant));
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn test_slash_more_than_bond_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+
+    s.client
+        .slash_bond(&s.admin, &s.merchant, &(BOND + 1), &symbol_short!("fraud"));
+}
+
+#[test]
+fn test_claim_bond_pays_creditline_up_to_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+    let creditline = Address::generate(&env);
+    s.client.set_creditline(&s.admin, &creditline);
+
+    assert_eq!(s.client.claim_bond(&creditline, &s.merchant, &2_000), 2_000);
+    assert_eq!(
+        s.client.claim_bond(&creditline, &s.merchant, &(BOND * 2)),
+        BOND - 2_000
+    );
+
+    assert_eq!(s.token.balance(&creditline), BOND);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    let record = s.client.get_merchant(&s.merchant);
+    assert_eq!(record.bond_amount, 0);
+    assert_eq!(record.status, MerchantStatus::Inactive);
+
+    // Nothing left to claim
+    assert_eq!(s.client.claim_bond(&creditline, &s.merchant, &1), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn test_claim_bond_by_non_creditline_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+    s.client.set_creditline(&s.admin, &Address::generate(&env));
+
+    s.client
+        .claim_bond(&Address::generate(&env), &s.merchant, &1_000);
+}
+
+#[test]
+fn test_withdraw_bond_after_cooldown() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+
+    env.ledger().set_timestamp(1_000);
+    s.client.deactivate_merchant(&s.admin, &s.merchant);
+
+    env.ledger().set_timestamp(1_000 + COOLDOWN);
+    assert_eq!(s.client.withdraw_bond(&s.merchant), BOND);
+
+    assert_eq!(s.token.balance(&s.merchant), BOND * 2);
+    assert_eq!(s.token.balance(&s.client.address), 0);
+    assert_eq!(s.client.get_merchant(&s.merchant).bond_amount, 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn test_withdraw_bond_during_cooldown_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+
+    env.ledger().set_timestamp(1_000);
+    s.client.deactivate_merchant(&s.admin, &s.merchant);
+
+    env.ledger().set_timestamp(1_000 + COOLDOWN - 1);
+    s.client.withdraw_bond(&s.merchant);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn test_withdraw_bond_while_active_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let s = setup_with_bond(&env);
+    s.client.post_bond(&s.merchant);
+
+    s.client.withdraw_bond(&s.merchant);
+}
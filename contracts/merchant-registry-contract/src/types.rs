@@ -0,0 +1,61 @@
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, String, Symbol};
+
+// Merchant status enum
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum MerchantStatus {
+    Active,
+    Inactive,
+    Suspended,   // Automatically suspended for exceeding the default-rate threshold
+    PendingBond, // Registered, waiting for the merchant to post its bond
+}
+
+// Merchant record
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Merchant {
+    pub name: String,
+    pub fee_rate_bps: u32, // Merchant discount rate in basis points
+    pub status: MerchantStatus,
+    pub registered_at: u64,              // Unix timestamp
+    pub bond_amount: i128,               // Bond currently staked by the merchant
+    pub deactivated_at: Option<u64>, // Set while the merchant is inactive; starts the bond cooldown
+    pub category: Symbol,            // Merchant category used for pool concentration limits
+    pub signing_key: Option<BytesN<32>>, // Ed25519 public key signing the merchant's checkouts
+}
+
+// Loan outcomes reported by the CreditLine contract for a merchant
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MerchantStats {
+    pub loans_originated: u32,
+    pub volume_originated: i128,
+    pub loans_defaulted: u32,
+    pub volume_defaulted: i128,
+    pub loans_refunded: u32,
+    pub volume_refunded: i128,
+}
+
+// Policy for automatically suspending merchants with poorly performing customers
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SuspensionPolicy {
+    pub max_default_rate_bps: u32, // Defaulted volume / originated volume net of refunds, in basis points
+    pub min_loans: u32,            // Minimum originated loans before the policy applies
+}
+
+// Refundable bond merchants must stake before they can originate loans
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BondConfig {
+    pub token: Address,          // Token the bond is posted in
+    pub amount: i128,            // Required bond per merchant
+    pub liquidity_pool: Address, // Destination of slashed bonds
+    pub cooldown_seconds: u64,   // Delay between deactivation and bond withdrawal
+}
+
+// Constants
+pub const MAX_NAME_LENGTH: u32 = 64; // Maximum merchant name length in bytes
+pub const MAX_FEE_RATE_BPS: u32 = 1_000; // 10% maximum merchant discount rate
+pub const BPS_DENOMINATOR: i128 = 10_000; // 100% in basis points
+pub const DEFAULT_CATEGORY: Symbol = symbol_short!("general"); // Category of newly registered merchants